@@ -53,6 +53,12 @@ const int HEALING_BEAM = 13;
 const int ENERGY_NATURE = 14;
 const int FLAMETHROWER = 15;
 const int FIRE_SHOCKWAVE = 16;
+const int BLOCK_GHOST = 17;
+const int CHUNK_BORDER = 18;
+const int RANGE_RING = 19;
+const int DEBUG_MARKER = 20;
+const int BUILD_GRID = 21;
+const int LOOT_BEAM = 22;
 
 // meters per second squared (acceleration)
 const float earth_gravity = 9.807;
@@ -302,6 +308,53 @@ void main() {
 			vec4(1, 0.6 + rand7 * 0.3 - 5 * inst_lifespan + 2 * lifetime, 0, 0.8 - 3.5 * inst_lifespan),
 			spin_in_axis(vec3(rand3, rand4, rand5), rand6)
 		);
+	} else if (inst_mode == BLOCK_GHOST) {
+		// Slightly larger than a block to avoid z-fighting with its neighbours
+		attr = Attr(
+			vec3(0),
+			vec3(1.02 / SCALE),
+			vec4(inst_dir, 0.35 + 0.1 * sin(tick.x * 4)),
+			identity()
+		);
+	} else if (inst_mode == CHUNK_BORDER) {
+		// A thin box stretched along the line
+		attr = Attr(
+			vec3(0),
+			(abs(inst_dir) + 0.05) / SCALE,
+			vec4(1, 0.9, 0.2, 0.7),
+			identity()
+		);
+	} else if (inst_mode == RANGE_RING) {
+		// A flat tile, faded by the opacity of the ring
+		attr = Attr(
+			vec3(0),
+			vec3(0.25, 0.25, 0.05) / SCALE,
+			vec4(0.4, 0.9, 1, 0.6 * inst_dir.x),
+			identity()
+		);
+	} else if (inst_mode == DEBUG_MARKER) {
+		attr = Attr(
+			vec3(0),
+			vec3(inst_entropy / SCALE),
+			vec4(inst_dir, 0.9),
+			identity()
+		);
+	} else if (inst_mode == BUILD_GRID) {
+		// A thin line, faded with the distance from the cursor
+		attr = Attr(
+			vec3(0),
+			(abs(inst_dir) + 0.02) / SCALE,
+			vec4(1, 1, 1, 0.4 * inst_entropy),
+			identity()
+		);
+	} else if (inst_mode == LOOT_BEAM) {
+		// A tall thin column, slowly pulsing
+		attr = Attr(
+			vec3(0),
+			vec3(0.15, 0.15, 6.0) / SCALE,
+			vec4(inst_dir, (0.4 + 0.1 * sin(tick.x * 3.0)) * inst_entropy),
+			identity()
+		);
 	} else {
 		attr = Attr(
 			linear_motion(
@@ -55,11 +55,20 @@ uniform u_locals {
 
 out vec4 tgt_color;
 
+// Seconds over which newly loaded chunks dither in
+const float CHUNK_FADE_IN_TIME = 1.0;
+
 #include <sky.glsl>
 #include <light.glsl>
 #include <lod.glsl>
 
 void main() {
+    // Chunks that have only just loaded are dithered in, rather than popping in
+    float fade_in = clamp((tick.x - load_time) / CHUNK_FADE_IN_TIME, 0.0, 1.0);
+    if (fade_in < 1.0 && hash(vec4(floor(gl_FragCoord.xy), 0.0, 0.0)) * 0.5 + 0.5 > fade_in) {
+        discard;
+    }
+
     // discard;
     // vec4 f_col_light = textureGrad(t_col_light, f_uv_pos / texSize, 0.25, 0.25);
     // vec4 f_col_light = texture(t_col_light, (f_uv_pos) / texSize);
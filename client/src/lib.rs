@@ -25,10 +25,10 @@ use common::{
     },
     event::{EventBus, LocalEvent},
     msg::{
-        validate_chat_msg, ChatMsgValidationError, ClientGeneral, ClientInGame, ClientMsg,
-        ClientRegister, ClientType, DisconnectReason, InviteAnswer, Notification, PingMsg,
-        PlayerInfo, PlayerListUpdate, RegisterError, ServerGeneral, ServerInfo, ServerInit,
-        ServerRegisterAnswer, MAX_BYTES_CHAT_MSG,
+        validate_chat_msg, BlockPlacementError, ChatMsgValidationError, ClientGeneral,
+        ClientInGame, ClientMsg, ClientRegister, ClientType, DisconnectReason, InviteAnswer,
+        Notification, PingMsg, PlayerInfo, PlayerListUpdate, RegisterError, ServerGeneral,
+        ServerInfo, ServerInit, ServerRegisterAnswer, MAX_BYTES_CHAT_MSG,
     },
     outcome::Outcome,
     recipe::RecipeBook,
@@ -56,6 +56,8 @@ use uvth::{ThreadPool, ThreadPoolBuilder};
 use vek::*;
 
 const PING_ROLLING_AVERAGE_SECS: usize = 10;
+/// How long the reported server tick rate is trusted without a new report
+const SERVER_TICK_RATE_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub enum Event {
     Chat(comp::ChatMsg),
@@ -66,6 +68,7 @@ pub enum Event {
     Notification(Notification),
     SetViewDistance(u32),
     Outcome(Outcome),
+    BlockPlacementFailed(Vec3<i32>, BlockPlacementError),
 }
 
 pub struct Client {
@@ -123,6 +126,8 @@ pub struct Client {
     last_server_pong: f64,
     last_ping_delta: f64,
     ping_deltas: VecDeque<f64>,
+    /// The tick rate last reported by the server, and when it was received
+    server_tick_rate: Option<(f64, Instant)>,
 
     tick: u64,
     state: State,
@@ -408,6 +413,7 @@ impl Client {
             last_server_pong: 0.0,
             last_ping_delta: 0.0,
             ping_deltas: VecDeque::new(),
+            server_tick_rate: None,
 
             tick: 0,
             state,
@@ -1245,6 +1251,9 @@ impl Client {
             ServerGeneral::Notification(n) => {
                 frontend_events.push(Event::Notification(n));
             },
+            ServerGeneral::TickRate(tps) => {
+                self.server_tick_rate = Some((tps, Instant::now()));
+            },
             _ => unreachable!("Not a general msg"),
         }
         Ok(())
@@ -1390,6 +1399,9 @@ impl Client {
             ServerGeneral::Outcomes(outcomes) => {
                 frontend_events.extend(outcomes.into_iter().map(Event::Outcome))
             },
+            ServerGeneral::BlockPlacementFailed(pos, error) => {
+                frontend_events.push(Event::BlockPlacementFailed(pos, error));
+            },
             ServerGeneral::Knockback(impulse) => {
                 self.state
                     .ecs()
@@ -1534,6 +1546,13 @@ impl Client {
 
     pub fn get_ping_ms(&self) -> f64 { self.last_ping_delta * 1000.0 }
 
+    /// The tick rate the server last reported, unless it stopped reporting it.
+    pub fn get_server_tick_rate(&self) -> Option<f64> {
+        self.server_tick_rate
+            .filter(|(_, received)| received.elapsed() < SERVER_TICK_RATE_TIMEOUT)
+            .map(|(tps, _)| tps)
+    }
+
     pub fn get_ping_ms_rolling_avg(&self) -> f64 {
         let mut total_weight = 0.;
         let pings = self.ping_deltas.len() as f64;
@@ -135,6 +135,24 @@ impl CharacterState {
 
     pub fn is_dodge(&self) -> bool { matches!(self, CharacterState::Roll(_)) }
 
+    /// Whether the character is recovering from an attack, and can't start
+    /// another one yet
+    pub fn is_recovering(&self) -> bool {
+        let section = match self {
+            CharacterState::BasicMelee(data) => return data.exhausted,
+            CharacterState::DashMelee(data) => data.stage_section,
+            CharacterState::ComboMelee(data) => data.stage_section,
+            CharacterState::LeapMelee(data) => data.stage_section,
+            CharacterState::SpinMelee(data) => data.stage_section,
+            CharacterState::ChargedMelee(data) => data.stage_section,
+            CharacterState::RepeaterRanged(data) => data.stage_section,
+            CharacterState::Shockwave(data) => data.stage_section,
+            CharacterState::BasicBeam(data) => data.stage_section,
+            _ => return false,
+        };
+        section == utils::StageSection::Recover
+    }
+
     /// Compares for shallow equality (does not check internal struct equality)
     pub fn same_variant(&self, other: &Self) -> bool {
         // Check if state is the same without looking at the inner data
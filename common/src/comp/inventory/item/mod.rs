@@ -62,7 +62,7 @@ impl Lantern {
 pub struct Glider {
     pub kind: String,
 }
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Copy)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Copy)]
 pub enum Quality {
     Low,       // Grey
     Common,    // UI Main Color
@@ -101,6 +101,8 @@ pub enum ServerGeneral {
     SetViewDistance(u32),
     Outcomes(Vec<Outcome>),
     Knockback(Vec3<f32>),
+    /// A block the client asked to place at the position was not placed
+    BlockPlacementFailed(Vec3<i32>, BlockPlacementError),
     // Always possible
     PlayerListUpdate(PlayerListUpdate),
     /// A message to go into the client chat box. The client is responsible for
@@ -115,6 +117,8 @@ pub enum ServerGeneral {
     Disconnect(DisconnectReason),
     /// Send a popup notification such as "Waypoint Saved"
     Notification(Notification),
+    /// How many ticks per second the server is running at
+    TickRate(f64),
 }
 
 /*
@@ -154,6 +158,17 @@ pub enum InviteAnswer {
     TimedOut,
 }
 
+/// Why a block placement requested by a client was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BlockPlacementError {
+    /// The client isn't allowed to build
+    NoPermission,
+    /// The position isn't within loaded terrain
+    OutOfBounds,
+    /// Another change to the same block was already accepted this tick
+    Contended,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Notification {
     WaypointSaved,
@@ -213,7 +228,8 @@ impl ServerMsg {
                         | ServerGeneral::TerrainBlockUpdates(_)
                         | ServerGeneral::SetViewDistance(_)
                         | ServerGeneral::Outcomes(_)
-                        | ServerGeneral::Knockback(_) => {
+                        | ServerGeneral::Knockback(_)
+                        | ServerGeneral::BlockPlacementFailed(_, _) => {
                             c_type == ClientType::Game && in_game.is_some()
                         },
                         // Always possible
@@ -226,7 +242,8 @@ impl ServerMsg {
                         | ServerGeneral::CreateEntity(_)
                         | ServerGeneral::DeleteEntity(_)
                         | ServerGeneral::Disconnect(_)
-                        | ServerGeneral::Notification(_) => true,
+                        | ServerGeneral::Notification(_)
+                        | ServerGeneral::TickRate(_) => true,
                     }
             },
             ServerMsg::Ping(_) => true,
@@ -24,6 +24,13 @@ pub enum Outcome {
     },
 }
 
+/// The kind of an [`Outcome`], without its data.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OutcomeCategory {
+    Explosion,
+    ProjectileShot,
+}
+
 impl Outcome {
     pub fn get_pos(&self) -> Option<Vec3<f32>> {
         match self {
@@ -31,4 +38,11 @@ impl Outcome {
             Outcome::ProjectileShot { pos, .. } => Some(*pos),
         }
     }
+
+    pub fn category(&self) -> OutcomeCategory {
+        match self {
+            Outcome::Explosion { .. } => OutcomeCategory::Explosion,
+            Outcome::ProjectileShot { .. } => OutcomeCategory::ProjectileShot,
+        }
+    }
 }
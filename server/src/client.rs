@@ -78,7 +78,8 @@ impl Client {
                     | ServerGeneral::TerrainBlockUpdates(_)
                     | ServerGeneral::SetViewDistance(_)
                     | ServerGeneral::Outcomes(_)
-                    | ServerGeneral::Knockback(_) => &mut self.in_game_stream,
+                    | ServerGeneral::Knockback(_)
+                    | ServerGeneral::BlockPlacementFailed(_, _) => &mut self.in_game_stream,
                     // Always possible
                     ServerGeneral::PlayerListUpdate(_)
                     | ServerGeneral::ChatMsg(_)
@@ -89,7 +90,8 @@ impl Client {
                     | ServerGeneral::CreateEntity(_)
                     | ServerGeneral::DeleteEntity(_)
                     | ServerGeneral::Disconnect(_)
-                    | ServerGeneral::Notification(_) => &mut self.general_stream,
+                    | ServerGeneral::Notification(_)
+                    | ServerGeneral::TickRate(_) => &mut self.general_stream,
                 };
                 Self::internal_send(&mut self.network_error, stream, &msg)
             },
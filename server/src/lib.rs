@@ -93,6 +93,9 @@ struct SpawnPoint(Vec3<f32>);
 #[derive(Copy, Clone, Default)]
 pub struct Tick(u64);
 
+/// How often the clients are told the tick rate of the server
+const TICK_RATE_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct Server {
     state: State,
     world: Arc<World>,
@@ -106,6 +109,8 @@ pub struct Server {
     metrics: ServerMetrics,
     tick_metrics: TickMetrics,
     state_tick_metrics: StateTickMetrics,
+    /// When the tick rate was last reported, and the tick count at that time
+    last_tick_rate_report: (Instant, u64),
 }
 
 impl Server {
@@ -349,6 +354,7 @@ impl Server {
             metrics,
             tick_metrics,
             state_tick_metrics,
+            last_tick_rate_report: (Instant::now(), 0),
         };
 
         debug!(?settings, "created veloren server with");
@@ -782,6 +788,15 @@ impl Server {
             .set(end_of_server_tick.elapsed().as_nanos() as i64);
         self.metrics.tick();
 
+        // Let the clients know how fast the server is running, to help diagnose desyncs
+        let (last_report, last_tick) = self.last_tick_rate_report;
+        if last_report.elapsed() >= TICK_RATE_REPORT_INTERVAL {
+            let tick = self.state.ecs().read_resource::<Tick>().0;
+            let tps = (tick - last_tick) as f64 / last_report.elapsed().as_secs_f64();
+            self.notify_registered_clients(ServerGeneral::TickRate(tps));
+            self.last_tick_rate_report = (Instant::now(), tick);
+        }
+
         // 9) Finish the tick, pass control back to the frontend.
 
         Ok(frontend_events)
@@ -15,9 +15,9 @@ use common::{
     },
     event::{EventBus, ServerEvent},
     msg::{
-        validate_chat_msg, CharacterInfo, ChatMsgValidationError, ClientGeneral, ClientInGame,
-        ClientRegister, DisconnectReason, PingMsg, PlayerInfo, PlayerListUpdate, RegisterError,
-        ServerGeneral, ServerRegisterAnswer, MAX_BYTES_CHAT_MSG,
+        validate_chat_msg, BlockPlacementError, CharacterInfo, ChatMsgValidationError,
+        ClientGeneral, ClientInGame, ClientRegister, DisconnectReason, PingMsg, PlayerInfo,
+        PlayerListUpdate, RegisterError, ServerGeneral, ServerRegisterAnswer, MAX_BYTES_CHAT_MSG,
     },
     span,
     state::{BlockChange, Time},
@@ -183,8 +183,18 @@ impl Sys {
                 }
             },
             ClientGeneral::PlaceBlock(pos, block) => {
-                if can_build.get(entity).is_some() {
-                    block_changes.try_set(pos, block);
+                let result = if can_build.get(entity).is_none() {
+                    Err(BlockPlacementError::NoPermission)
+                } else if terrain.get(pos).is_err() {
+                    Err(BlockPlacementError::OutOfBounds)
+                } else {
+                    block_changes
+                        .try_set(pos, block)
+                        .ok_or(BlockPlacementError::Contended)
+                };
+                // Let the client know, so it can retry or tell the player why
+                if let Err(error) = result {
+                    client.send_msg(ServerGeneral::BlockPlacementFailed(pos, error));
                 }
             },
             ClientGeneral::TerrainChunkRequest { key } => {
@@ -0,0 +1,77 @@
+//! Log of the actions the session sends to the server, used to debug desyncs.
+//!
+//! Every line holds the seconds since the log was opened, the client tick to
+//! match it up with the server logs, and the action with its arguments.
+
+use crate::settings::voxygen_data_dir;
+use client::Client;
+use std::{
+    fmt, fs,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::Instant,
+};
+use tracing::{info, warn};
+
+pub struct ActionLog {
+    writer: Option<BufWriter<fs::File>>,
+    opened: Instant,
+}
+
+impl ActionLog {
+    /// Open the log, replacing the one of the previous session, if `enabled`.
+    /// Otherwise logging does nothing.
+    pub fn new(enabled: bool) -> Self {
+        let writer = if enabled {
+            let path = Self::get_log_path();
+            match path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::File::create(&path))
+            {
+                Ok(file) => {
+                    info!(?path, "Logging client actions");
+                    Some(BufWriter::new(file))
+                },
+                Err(e) => {
+                    warn!(?e, ?path, "Failed to create client action log");
+                    None
+                },
+            }
+        } else {
+            None
+        };
+        Self {
+            writer,
+            opened: Instant::now(),
+        }
+    }
+
+    /// Record `action`, sent by `client`, along with its arguments. The
+    /// arguments are only formatted when the log is enabled.
+    pub fn log(&mut self, client: &Client, action: &str, args: impl fmt::Debug) {
+        if let Some(writer) = &mut self.writer {
+            // Flushed right away so the log survives a crash
+            let written = writeln!(
+                writer,
+                "{:.3} tick={} {} {:?}",
+                self.opened.elapsed().as_secs_f64(),
+                client.get_tick(),
+                action,
+                args
+            )
+            .and_then(|_| writer.flush());
+            if let Err(e) = written {
+                warn!(?e, "Failed to write client action log, stopping");
+                self.writer = None;
+            }
+        }
+    }
+
+    pub fn get_log_path() -> PathBuf {
+        let mut path = voxygen_data_dir();
+        path.push("logs");
+        path.push("client_actions.log");
+        path
+    }
+}
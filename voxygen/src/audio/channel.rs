@@ -13,8 +13,9 @@
 //! When the AudioFrontend's
 //! [`play_sfx`](../struct.AudioFrontend.html#method.play_sfx)
 //! methods is called, it attempts to retrieve an SfxChannel for playback. If
-//! the channel capacity has been reached and all channels are occupied, a
-//! warning is logged, and no sound is played.
+//! the channel capacity has been reached and all channels are occupied, the
+//! least important sound is cut off to make room, as long as the new one is
+//! more important. Otherwise the new sound isn't played.
 
 use crate::audio::{
     fader::{FadeDirection, Fader},
@@ -23,6 +24,9 @@ use crate::audio::{
 use rodio::{Device, Sample, Sink, Source, SpatialSink};
 use vek::*;
 
+/// How quickly positional sounds get quieter with distance from the listener
+const FALLOFF: f32 = 0.13;
+
 #[derive(PartialEq, Clone, Copy)]
 enum ChannelState {
     Playing,
@@ -142,6 +146,7 @@ impl MusicChannel {
 pub struct SfxChannel {
     sink: SpatialSink,
     pub pos: Vec3<f32>,
+    priority: SfxPriority,
 }
 
 impl SfxChannel {
@@ -149,6 +154,7 @@ impl SfxChannel {
         Self {
             sink: SpatialSink::new(device, [0.0; 3], [1.0, 0.0, 0.0], [-1.0, 0.0, 0.0]),
             pos: Vec3::zero(),
+            priority: SfxPriority::default(),
         }
     }
 
@@ -168,9 +174,11 @@ impl SfxChannel {
 
     pub fn set_pos(&mut self, pos: Vec3<f32>) { self.pos = pos; }
 
-    pub fn update(&mut self, listener: &Listener) {
-        const FALLOFF: f32 = 0.13;
+    pub fn get_priority(&self) -> SfxPriority { self.priority }
+
+    pub fn set_priority(&mut self, priority: SfxPriority) { self.priority = priority; }
 
+    pub fn update(&mut self, listener: &Listener) {
         self.sink
             .set_emitter_position(((self.pos - listener.pos) * FALLOFF).into_array());
         self.sink
@@ -179,3 +187,22 @@ impl SfxChannel {
             .set_right_ear_position(listener.ear_right_rpos.into_array());
     }
 }
+
+/// How much a sound matters when there are too many to play at once. Important
+/// sounds are kept over all others, then louder ones over quieter ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct SfxPriority {
+    /// Whether the sound is one of the player's own or their group's
+    pub important: bool,
+    /// Rough loudness at the listener
+    pub loudness: f32,
+}
+
+impl SfxPriority {
+    pub fn new(important: bool, pos: Vec3<f32>, volume: f32, listener: &Listener) -> Self {
+        Self {
+            important,
+            loudness: volume / (1.0 + (pos - listener.pos).magnitude_squared() * FALLOFF.powi(2)),
+        }
+    }
+}
@@ -6,7 +6,7 @@ pub mod music;
 pub mod sfx;
 pub mod soundcache;
 
-use channel::{MusicChannel, MusicChannelTag, SfxChannel};
+use channel::{MusicChannel, MusicChannelTag, SfxChannel, SfxPriority};
 use fader::Fader;
 use soundcache::SoundCache;
 use tracing::warn;
@@ -14,8 +14,13 @@ use tracing::warn;
 use common::assets;
 use cpal::traits::DeviceTrait;
 use rodio::{source::Source, Decoder, Device};
+use std::cmp::Ordering;
 use vek::*;
 
+/// Most sfx channels that are created, however many are asked for. Every
+/// channel mixes in another sink, so too many would bog down the audio thread.
+const MAX_SFX_CHANNELS: usize = 64;
+
 #[derive(Default, Clone)]
 pub struct Listener {
     pos: Vec3<f32>,
@@ -49,6 +54,7 @@ impl AudioFrontend {
     pub fn new(device: String, max_sfx_channels: usize) -> Self {
         let audio_device = get_device_raw(&device);
 
+        let max_sfx_channels = max_sfx_channels.min(MAX_SFX_CHANNELS);
         let mut sfx_channels = Vec::with_capacity(max_sfx_channels);
         if let Some(audio_device) = &audio_device {
             sfx_channels.resize_with(max_sfx_channels, || SfxChannel::new(&audio_device));
@@ -92,16 +98,34 @@ impl AudioFrontend {
         }
     }
 
-    fn get_sfx_channel(&mut self) -> Option<&mut SfxChannel> {
-        if self.audio_device.is_some() {
-            if let Some(channel) = self.sfx_channels.iter_mut().find(|c| c.is_done()) {
-                channel.set_volume(self.sfx_volume);
-
-                return Some(channel);
-            }
-        }
-
-        None
+    /// Retrieve a free sfx channel for a sound of the given `priority`. When
+    /// all of them are in use, the least important sound is cut off if the new
+    /// one matters more.
+    fn get_sfx_channel(&mut self, priority: SfxPriority) -> Option<&mut SfxChannel> {
+        let audio_device = self.audio_device.as_ref()?;
+        let index = match self.sfx_channels.iter().position(|c| c.is_done()) {
+            Some(index) => index,
+            None => {
+                let (index, lowest) = self
+                    .sfx_channels
+                    .iter()
+                    .map(SfxChannel::get_priority)
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))?;
+                if lowest >= priority {
+                    return None;
+                }
+                // A sink can't play anything once stopped, so it is replaced. Dropping the old
+                // one stops its sound.
+                self.sfx_channels[index] = SfxChannel::new(audio_device);
+                index
+            },
+        };
+
+        let channel = &mut self.sfx_channels[index];
+        channel.set_volume(self.sfx_volume);
+        channel.set_priority(priority);
+        Some(channel)
     }
 
     /// Retrieve a music channel from the channel list. This inspects the
@@ -138,16 +162,17 @@ impl AudioFrontend {
         self.music_channels.last_mut()
     }
 
-    /// Play (once) an sfx file by file path at the give position and volume
-    pub fn play_sfx(&mut self, sound: &str, pos: Vec3<f32>, vol: Option<f32>) {
+    /// Play (once) an sfx file by file path at the give position and volume.
+    /// `important` sounds, like the player's own, are kept over others when
+    /// there are too many to play at once.
+    pub fn play_sfx(&mut self, sound: &str, pos: Vec3<f32>, vol: Option<f32>, important: bool) {
         if self.audio_device.is_some() {
-            let sound = self
-                .sound_cache
-                .load_sound(sound)
-                .amplify(vol.unwrap_or(1.0));
+            let vol = vol.unwrap_or(1.0);
+            let sound = self.sound_cache.load_sound(sound).amplify(vol);
 
             let listener = self.listener.clone();
-            if let Some(channel) = self.get_sfx_channel() {
+            let priority = SfxPriority::new(important, pos, vol, &listener);
+            if let Some(channel) = self.get_sfx_channel(priority) {
                 channel.set_pos(pos);
                 channel.update(&listener);
                 channel.play(sound);
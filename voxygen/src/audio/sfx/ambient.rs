@@ -0,0 +1,151 @@
+//! Ambient sounds picked from the surroundings of the player, such as birds in
+//! forests or dripping water in caves.
+//!
+//! Ambient sounds are played as regular sfx events at an interval. When the
+//! player moves to a different zone the sounds of the old zone fade out while
+//! the new ones fade in, rather than cutting over abruptly.
+//!
+//! Nothing drives this from the session yet, as no ambient sounds ship with
+//! the game.
+
+use common::terrain::BiomeKind;
+use serde::Deserialize;
+
+/// Seconds between two plays of the sounds of a zone
+const AMBIENT_INTERVAL: f32 = 6.0;
+/// Seconds for the sounds of a zone to fade fully in or out
+const AMBIENT_CROSSFADE: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum AmbientZone {
+    Grassland,
+    Forest,
+    Swamp,
+    Snowlands,
+    Desert,
+    Mountain,
+    Ocean,
+    Cave,
+}
+
+impl AmbientZone {
+    /// The zone of a player in `biome`, who is `underground` or not.
+    pub fn from_surroundings(biome: BiomeKind, underground: bool) -> Option<Self> {
+        if underground {
+            return Some(AmbientZone::Cave);
+        }
+        match biome {
+            BiomeKind::Void => None,
+            BiomeKind::Grassland => Some(AmbientZone::Grassland),
+            BiomeKind::Forest => Some(AmbientZone::Forest),
+            BiomeKind::Swamp => Some(AmbientZone::Swamp),
+            BiomeKind::Snowlands => Some(AmbientZone::Snowlands),
+            BiomeKind::Desert => Some(AmbientZone::Desert),
+            BiomeKind::Mountain => Some(AmbientZone::Mountain),
+            BiomeKind::Ocean => Some(AmbientZone::Ocean),
+        }
+    }
+}
+
+struct AudibleZone {
+    zone: AmbientZone,
+    volume: f32,
+    until_next_play: f32,
+}
+
+#[derive(Default)]
+pub struct AmbientMgr {
+    zones: Vec<AudibleZone>,
+}
+
+impl AmbientMgr {
+    /// Fade the zones towards `current` by `dt` seconds, and return the zones
+    /// whose sounds should be played now along with their volume.
+    pub fn maintain(&mut self, dt: f32, current: Option<AmbientZone>) -> Vec<(AmbientZone, f32)> {
+        if let Some(current) = current {
+            if !self.zones.iter().any(|z| z.zone == current) {
+                self.zones.push(AudibleZone {
+                    zone: current,
+                    volume: 0.0,
+                    // Wait until the zone has faded in partway
+                    until_next_play: AMBIENT_CROSSFADE / 2.0,
+                });
+            }
+        }
+
+        let fade = dt / AMBIENT_CROSSFADE;
+        let mut to_play = Vec::new();
+        for zone in self.zones.iter_mut() {
+            zone.volume = if Some(zone.zone) == current {
+                (zone.volume + fade).min(1.0)
+            } else {
+                (zone.volume - fade).max(0.0)
+            };
+            zone.until_next_play -= dt;
+            if zone.until_next_play <= 0.0 {
+                zone.until_next_play = AMBIENT_INTERVAL;
+                if zone.volume > 0.0 {
+                    to_play.push((zone.zone, zone.volume));
+                }
+            }
+        }
+        self.zones
+            .retain(|z| z.volume > 0.0 || Some(z.zone) == current);
+
+        to_play
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn volume(ambient: &AmbientMgr, zone: AmbientZone) -> Option<f32> {
+        ambient
+            .zones
+            .iter()
+            .find(|z| z.zone == zone)
+            .map(|z| z.volume)
+    }
+
+    #[test]
+    fn zone_fades_in() {
+        let mut ambient = AmbientMgr::default();
+
+        // First played once faded in halfway
+        let played = ambient.maintain(AMBIENT_CROSSFADE / 2.0, Some(AmbientZone::Forest));
+        assert_eq!(played, vec![(AmbientZone::Forest, 0.5)]);
+
+        let played = ambient.maintain(AMBIENT_CROSSFADE / 2.0, Some(AmbientZone::Forest));
+        assert!(played.is_empty());
+        assert_eq!(volume(&ambient, AmbientZone::Forest), Some(1.0));
+    }
+
+    #[test]
+    fn zone_fades_out() {
+        let mut ambient = AmbientMgr::default();
+        ambient.maintain(AMBIENT_CROSSFADE, Some(AmbientZone::Forest));
+
+        ambient.maintain(AMBIENT_CROSSFADE / 2.0, None);
+        assert_eq!(volume(&ambient, AmbientZone::Forest), Some(0.5));
+
+        // Dropped once silent
+        ambient.maintain(AMBIENT_CROSSFADE / 2.0, None);
+        assert_eq!(volume(&ambient, AmbientZone::Forest), None);
+    }
+
+    #[test]
+    fn zone_switch_crossfades() {
+        let mut ambient = AmbientMgr::default();
+        ambient.maintain(AMBIENT_CROSSFADE, Some(AmbientZone::Forest));
+
+        let played = ambient.maintain(AMBIENT_CROSSFADE / 2.0, Some(AmbientZone::Cave));
+        assert_eq!(played, vec![(AmbientZone::Cave, 0.5)]);
+        assert_eq!(volume(&ambient, AmbientZone::Forest), Some(0.5));
+        assert_eq!(volume(&ambient, AmbientZone::Cave), Some(0.5));
+
+        ambient.maintain(AMBIENT_CROSSFADE / 2.0, Some(AmbientZone::Cave));
+        assert_eq!(volume(&ambient, AmbientZone::Forest), None);
+        assert_eq!(volume(&ambient, AmbientZone::Cave), Some(1.0));
+    }
+}
@@ -80,25 +80,28 @@
 //! ),
 //! ```
 
+pub mod ambient;
 mod event_mapper;
 
 use crate::{audio::AudioFrontend, scene::Camera};
+use ambient::AmbientZone;
 
 use common::{
     assets,
     comp::{
-        item::{ItemKind, ToolCategory},
-        object, Body, CharacterAbilityType, InventoryUpdateEvent,
+        item::{Item, ItemKind, ToolCategory},
+        object, Body, CharacterAbilityType, Group, InventoryUpdateEvent, Pos,
     },
     event::EventBus,
     outcome::Outcome,
     state::State,
+    vol::ReadVol,
 };
 use event_mapper::SfxEventMapper;
 use hashbrown::HashMap;
 use rand::prelude::*;
 use serde::Deserialize;
-use specs::WorldExt;
+use specs::{Join, WorldExt};
 use std::convert::TryFrom;
 use tracing::{debug, warn};
 use vek::*;
@@ -110,6 +113,15 @@ use vek::*;
 /// player.
 const SFX_DIST_LIMIT_SQR: f32 = 20000.0;
 
+/// Sounds this close to the player are never muffled, so that their own
+/// sounds aren't
+const OCCLUSION_MIN_DIST: f32 = 2.0;
+/// Most terrain samples taken on the way to a sound, spread out evenly
+const OCCLUSION_MAX_SAMPLES: usize = 24;
+/// Sounds this close to the player or a member of their group are kept over
+/// others when there are too many to play at once
+const IMPORTANT_SFX_DIST_SQR: f32 = 4.0;
+
 pub struct SfxEventItem {
     pub sfx: SfxEvent,
     pub pos: Option<Vec3<f32>>,
@@ -143,12 +155,15 @@ pub enum SfxEvent {
     Fall,
     ExperienceGained,
     LevelUp,
+    /// A creature was picked as target with the target nearest key
+    TargetAcquired,
     Attack(CharacterAbilityType, ToolCategory),
     Wield(ToolCategory),
     Unwield(ToolCategory),
     Inventory(SfxInventoryEvent),
     Explosion,
     ProjectileShot,
+    Ambient(AmbientZone),
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Hash, Eq)]
@@ -161,6 +176,23 @@ pub enum SfxInventoryEvent {
     Dropped,
     Given,
     Swapped,
+    Used,
+    UsedTool(ToolCategory),
+}
+
+impl SfxEvent {
+    /// The sound played when `item` is activated from an inventory or hotbar
+    /// slot. Consumables are skipped since consuming them already plays a
+    /// sound of its own.
+    pub fn from_used_item(item: &Item) -> Option<Self> {
+        match item.kind() {
+            ItemKind::Consumable { .. } => None,
+            ItemKind::Tool(tool) => Some(SfxEvent::Inventory(SfxInventoryEvent::UsedTool(
+                ToolCategory::try_from(&tool.kind).unwrap(),
+            ))),
+            _ => Some(SfxEvent::Inventory(SfxInventoryEvent::Used)),
+        }
+    }
 }
 
 impl From<&InventoryUpdateEvent> for SfxEvent {
@@ -228,6 +260,7 @@ impl SfxMgr {
         state: &State,
         player_entity: specs::Entity,
         camera: &Camera,
+        occlusion_strength: f32,
     ) {
         if !audio.sfx_enabled() {
             return;
@@ -245,11 +278,17 @@ impl SfxMgr {
 
         // TODO: replace; deprecated in favor of outcomes
         let events = ecs.read_resource::<EventBus<SfxEventItem>>().recv_all();
+        let important = important_positions(state, player_entity);
 
         for event in events {
-            let position = match event.pos {
-                Some(pos) => pos,
-                _ => cam_pos,
+            // Sounds without a position, such as those of the UI, are never muffled
+            let (position, occlusion, is_important) = match event.pos {
+                Some(pos) => (
+                    pos,
+                    occlusion(state, player_entity, pos, occlusion_strength),
+                    is_important(&important, pos),
+                ),
+                _ => (cam_pos, 1.0, true),
             };
 
             if let Some(item) = self.triggers.get_trigger(&event.sfx) {
@@ -268,17 +307,30 @@ impl SfxMgr {
                     },
                 };
 
-                audio.play_sfx(sfx_file, position, event.vol);
+                audio.play_sfx(
+                    sfx_file,
+                    position,
+                    Some(event.vol.unwrap_or(1.0) * occlusion),
+                    is_important,
+                );
             } else {
                 debug!("Missing sfx trigger config for sfx event. {:?}", event.sfx);
             }
         }
     }
 
-    pub fn handle_outcome(&mut self, outcome: &Outcome, audio: &mut AudioFrontend) {
+    pub fn handle_outcome(
+        &mut self,
+        outcome: &Outcome,
+        audio: &mut AudioFrontend,
+        state: &State,
+        player_entity: specs::Entity,
+        occlusion_strength: f32,
+    ) {
         if !audio.sfx_enabled() {
             return;
         }
+        let important = important_positions(state, player_entity);
 
         match outcome {
             Outcome::Explosion { pos, power, .. } => {
@@ -286,10 +338,16 @@ impl SfxMgr {
                     // TODO: from sfx config?
                     "voxygen.audio.sfx.explosion",
                     *pos,
-                    Some((power.abs() / 2.5).min(1.5)),
+                    Some(
+                        (power.abs() / 2.5).min(1.5)
+                            * occlusion(state, player_entity, *pos, occlusion_strength),
+                    ),
+                    is_important(&important, *pos),
                 );
             },
             Outcome::ProjectileShot { pos, body, .. } => {
+                let vol = Some(occlusion(state, player_entity, *pos, occlusion_strength));
+                let is_important = is_important(&important, *pos);
                 // TODO: from sfx config?
                 match body {
                     Body::Object(
@@ -302,7 +360,7 @@ impl SfxMgr {
                             "voxygen.audio.sfx.abilities.arrow_shot_4",
                         ][rand::thread_rng().gen_range(1, 4)];
 
-                        audio.play_sfx(file_ref, *pos, None);
+                        audio.play_sfx(file_ref, *pos, vol, is_important);
                     },
                     Body::Object(object::Body::BoltFire | object::Body::BoltFireBig) => {
                         let file_ref = vec![
@@ -310,7 +368,7 @@ impl SfxMgr {
                             "voxygen.audio.sfx.abilities.fire_shot_2",
                         ][rand::thread_rng().gen_range(1, 2)];
 
-                        audio.play_sfx(file_ref, *pos, None);
+                        audio.play_sfx(file_ref, *pos, vol, is_important);
                     },
                     _ => {
                         // not mapped to sfx file
@@ -344,3 +402,61 @@ impl SfxMgr {
         }
     }
 }
+
+/// Positions of the player and the members of their group, whose sounds are
+/// the most important to hear.
+fn important_positions(state: &State, player_entity: specs::Entity) -> Vec<Vec3<f32>> {
+    let ecs = state.ecs();
+    let positions = ecs.read_storage::<Pos>();
+    let groups = ecs.read_storage::<Group>();
+    let player_group = groups.get(player_entity);
+    let group_members = (&ecs.entities(), &positions, &groups)
+        .join()
+        .filter(|(entity, _, group)| *entity != player_entity && Some(*group) == player_group)
+        .map(|(_, pos, _)| pos.0);
+    positions
+        .get(player_entity)
+        .map(|pos| pos.0)
+        .into_iter()
+        .chain(group_members)
+        .collect()
+}
+
+fn is_important(important: &[Vec3<f32>], pos: Vec3<f32>) -> bool {
+    important
+        .iter()
+        .any(|p| p.distance_squared(pos) < IMPORTANT_SFX_DIST_SQR)
+}
+
+/// Volume multiplier for a sound at `pos`, lowered by `strength` when terrain
+/// blocks the way between it and the player. The terrain is only sampled
+/// along the way rather than walked voxel by voxel, to keep it cheap.
+fn occlusion(state: &State, player_entity: specs::Entity, pos: Vec3<f32>, strength: f32) -> f32 {
+    if strength <= 0.0 {
+        return 1.0;
+    }
+    let player_pos = match state.read_storage::<Pos>().get(player_entity) {
+        Some(pos) => pos.0,
+        None => return 1.0,
+    };
+    // Lift both ends off the ground they stand on
+    let (from, to) = (player_pos + Vec3::unit_z(), pos + Vec3::unit_z());
+    let dist = from.distance(to);
+    if dist < OCCLUSION_MIN_DIST {
+        return 1.0;
+    }
+
+    let samples = (dist.ceil() as usize).min(OCCLUSION_MAX_SAMPLES);
+    let terrain = state.terrain();
+    let blocked = (1..samples).any(|i| {
+        let sample = Lerp::lerp(from, to, i as f32 / samples as f32);
+        terrain
+            .get(sample.map(|e| e.floor() as i32))
+            .map_or(false, |block| block.is_filled())
+    });
+    if blocked {
+        1.0 - strength.min(1.0)
+    } else {
+        1.0
+    }
+}
@@ -0,0 +1,133 @@
+//! Scripted freefly flythrough used to measure client performance.
+//!
+//! The camera flies a fixed loop around the world spawn, where the player is
+//! moved for the duration, while the duration of every frame is collected.
+//! Once the loop is complete the min/avg/max frame rate is reported along with
+//! that point, so runs are flown over the same terrain and can be compared.
+
+use crate::scene::camera::CameraMode;
+use std::f32::consts::PI;
+use tracing::{debug, info};
+use vek::*;
+
+/// View distance forced for the duration of the benchmark so that runs are
+/// comparable regardless of the player's settings.
+pub const BENCHMARK_VIEW_DISTANCE: u32 = 10;
+
+/// How long the flythrough lasts, in seconds.
+const DURATION: f32 = 30.0;
+/// Radius of the loop flown around the origin.
+const RADIUS: f32 = 96.0;
+/// Height of the loop above the origin.
+const HEIGHT: f32 = 48.0;
+/// How far the camera rises and falls over the course of the loop.
+const HEIGHT_VARIATION: f32 = 16.0;
+/// Downwards pitch of the camera so that the terrain stays in view.
+const PITCH: f32 = 0.3;
+
+/// Frame rate statistics collected over a completed benchmark.
+#[derive(Clone, Copy, Debug)]
+pub struct BenchmarkResults {
+    /// Point the loop was flown around.
+    pub origin: Vec3<f32>,
+    pub frames: usize,
+    pub min_fps: f32,
+    pub avg_fps: f32,
+    pub max_fps: f32,
+}
+
+pub struct Benchmark {
+    origin: Vec3<f32>,
+    elapsed: f32,
+    frame_times: Vec<f32>,
+    prev_pos: Option<Vec3<f32>>,
+    prev_camera_mode: CameraMode,
+    prev_view_distance: Option<u32>,
+}
+
+impl Benchmark {
+    /// Start a benchmark flying around `origin`, remembering the position of
+    /// the player, camera mode and view distance to restore once it is over.
+    pub fn new(
+        origin: Vec3<f32>,
+        prev_pos: Option<Vec3<f32>>,
+        prev_camera_mode: CameraMode,
+        prev_view_distance: Option<u32>,
+    ) -> Self {
+        info!(?origin, "Starting benchmark flythrough");
+        Self {
+            origin,
+            elapsed: 0.0,
+            frame_times: Vec::new(),
+            prev_pos,
+            prev_camera_mode,
+            prev_view_distance,
+        }
+    }
+
+    pub fn prev_pos(&self) -> Option<Vec3<f32>> { self.prev_pos }
+
+    pub fn prev_camera_mode(&self) -> CameraMode { self.prev_camera_mode }
+
+    pub fn prev_view_distance(&self) -> Option<u32> { self.prev_view_distance }
+
+    /// Record a frame that took `dt` seconds and advance along the path.
+    ///
+    /// Returns the camera focus position and orientation for this frame, or
+    /// `None` once the flythrough is complete.
+    pub fn step(&mut self, dt: f32) -> Option<(Vec3<f32>, Vec3<f32>)> {
+        if self.elapsed >= DURATION {
+            return None;
+        }
+        debug!(
+            frame = self.frame_times.len(),
+            ms = dt * 1000.0,
+            "Benchmark frame"
+        );
+        self.frame_times.push(dt);
+        self.elapsed += dt;
+
+        let angle = (self.elapsed / DURATION).min(1.0) * 2.0 * PI;
+        let pos = self.origin
+            + Vec3::new(
+                angle.sin() * RADIUS,
+                angle.cos() * RADIUS,
+                HEIGHT + (angle * 2.0).sin() * HEIGHT_VARIATION,
+            );
+        // Look along the tangent of the loop
+        let ori = Vec3::new(angle + PI / 2.0, PITCH, 0.0);
+
+        Some((pos, ori))
+    }
+
+    /// Summarise and log the frame times collected during the flythrough.
+    pub fn finish(&self) -> BenchmarkResults {
+        let fps = |dt: f32| if dt > 0.0 { 1.0 / dt } else { 0.0 };
+        let (min_dt, max_dt, total) = self
+            .frame_times
+            .iter()
+            .fold((f32::MAX, 0.0f32, 0.0f32), |(min, max, total), &dt| {
+                (min.min(dt), max.max(dt), total + dt)
+            });
+        let frames = self.frame_times.len();
+        let results = if frames == 0 {
+            BenchmarkResults {
+                origin: self.origin,
+                frames,
+                min_fps: 0.0,
+                avg_fps: 0.0,
+                max_fps: 0.0,
+            }
+        } else {
+            BenchmarkResults {
+                origin: self.origin,
+                frames,
+                min_fps: fps(max_dt),
+                avg_fps: fps(total / frames as f32),
+                max_fps: fps(min_dt),
+            }
+        };
+        info!(?results, "Benchmark finished");
+        results
+    }
+}
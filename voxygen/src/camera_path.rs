@@ -0,0 +1,88 @@
+//! Rolling record of where the camera was over the last seconds of play.
+//!
+//! When saved, the path can be flown again by other tools to re-render a clip
+//! at a higher quality. Only the camera is kept, never the rendered frames, so
+//! this is no replacement for capturing video.
+
+use crate::{scene::camera::Camera, settings::voxygen_data_dir};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, fs, io, path::PathBuf};
+use tracing::info;
+use vek::*;
+
+/// Samples taken per second of game time. Frames in between are left out to
+/// keep sampling cheap, the path is smooth enough to interpolate.
+const SAMPLE_RATE: f64 = 30.0;
+
+/// The camera state at one point of the path.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct CameraSample {
+    /// Game time in seconds
+    pub time: f64,
+    pub tick: u64,
+    pub focus_pos: Vec3<f32>,
+    pub ori: Vec3<f32>,
+    pub dist: f32,
+    pub fov: f32,
+}
+
+pub struct CameraPath {
+    samples: VecDeque<CameraSample>,
+    capacity: usize,
+}
+
+impl CameraPath {
+    /// Keep the last `seconds` of the camera path. Nothing is kept for 0.
+    pub fn new(seconds: u32) -> Self {
+        let capacity = (f64::from(seconds) * SAMPLE_RATE) as usize;
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Add the state of `camera` to the path, unless it was sampled too
+    /// recently. The oldest sample is dropped once the path is full.
+    pub fn sample(&mut self, time: f64, tick: u64, camera: &Camera) {
+        if self.capacity == 0
+            || self
+                .samples
+                .back()
+                .map_or(false, |last| time - last.time < 1.0 / SAMPLE_RATE)
+        {
+            return;
+        }
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(CameraSample {
+            time,
+            tick,
+            focus_pos: camera.get_focus_pos(),
+            ori: camera.get_orientation(),
+            dist: camera.get_distance(),
+            fov: camera.get_fov(),
+        });
+    }
+
+    /// Write the path to a new file, returning where it was saved.
+    pub fn save(&self) -> io::Result<PathBuf> {
+        let path = Self::get_path_dir().join(format!(
+            "camera_path_{}.ron",
+            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+        ));
+        fs::create_dir_all(Self::get_path_dir())?;
+        let samples = self.samples.iter().collect::<Vec<_>>();
+        let ron = ron::ser::to_string_pretty(&samples, ron::ser::PrettyConfig::default())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        fs::write(&path, ron)?;
+        info!(?path, samples = samples.len(), "Saved camera path");
+        Ok(path)
+    }
+
+    pub fn get_path_dir() -> PathBuf {
+        let mut path = voxygen_data_dir();
+        path.push("camera_paths");
+        path
+    }
+}
@@ -0,0 +1,154 @@
+//! Chat messages kept across sessions, so that reconnecting to a server
+//! doesn't lose them.
+
+use common::{character::CharacterId, comp::ChatMsg};
+use std::{
+    collections::VecDeque,
+    mem,
+    time::{Duration, Instant},
+};
+
+/// Number of messages kept, the same as the chat box shows
+const MAX_MESSAGES: usize = 100;
+/// How long after a reconnect messages identical to ones already in the
+/// history are assumed to be re-sent by the server and dropped
+const RESEND_WINDOW: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+pub struct ChatHistory {
+    messages: VecDeque<ChatMsg>,
+    /// Server and character the messages were received with
+    owner: Option<(String, Option<CharacterId>)>,
+    /// Number of messages from before the last reconnect, and until when
+    /// re-sent copies of them are dropped
+    resent_until: Option<(usize, Instant)>,
+}
+
+impl ChatHistory {
+    /// Start a session on `server` with `character_id`. If it is the same
+    /// server and character as the previous session, returns the messages
+    /// from before to show again. Otherwise the history is cleared.
+    pub fn resume(&mut self, server: &str, character_id: Option<CharacterId>) -> Vec<ChatMsg> {
+        let owner = (server.to_string(), character_id);
+        if self.owner.as_ref() != Some(&owner) {
+            self.owner = Some(owner);
+            self.messages.clear();
+            self.resent_until = None;
+            return Vec::new();
+        }
+        self.resent_until = Some((self.messages.len(), Instant::now() + RESEND_WINDOW));
+        self.messages.iter().cloned().collect()
+    }
+
+    /// Whether `msg` is a copy of a message from before the last reconnect
+    /// that the server sent again.
+    pub fn is_resent(&self, msg: &ChatMsg) -> bool {
+        match self.resent_until {
+            Some((count, until)) if Instant::now() < until => {
+                self.messages.iter().take(count).any(|old| {
+                    old.message == msg.message
+                        && mem::discriminant(&old.chat_type) == mem::discriminant(&msg.chat_type)
+                })
+            },
+            _ => false,
+        }
+    }
+
+    /// Forget every message, so none are shown again on reconnecting.
+    pub fn clear(&mut self) {
+        self.messages.clear();
+        self.resent_until = None;
+    }
+
+    pub fn record(&mut self, msg: ChatMsg) {
+        self.messages.push_back(msg);
+        while self.messages.len() > MAX_MESSAGES {
+            self.messages.pop_front();
+            if let Some((count, _)) = &mut self.resent_until {
+                *count = count.saturating_sub(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::comp::ChatType;
+
+    fn msg(message: &str) -> ChatMsg {
+        ChatMsg {
+            chat_type: ChatType::CommandInfo,
+            message: message.to_string(),
+        }
+    }
+
+    fn messages(msgs: &[ChatMsg]) -> Vec<&str> { msgs.iter().map(|m| m.message.as_str()).collect() }
+
+    #[test]
+    fn resume_keeps_messages_of_same_owner() {
+        let mut history = ChatHistory::default();
+        assert!(history.resume("server", Some(1)).is_empty());
+        history.record(msg("hello"));
+        history.record(msg("world"));
+
+        assert_eq!(messages(&history.resume("server", Some(1))), vec![
+            "hello", "world"
+        ]);
+    }
+
+    #[test]
+    fn resume_clears_messages_of_other_owner() {
+        let mut history = ChatHistory::default();
+        history.resume("server", Some(1));
+        history.record(msg("hello"));
+
+        assert!(history.resume("server", Some(2)).is_empty());
+        history.record(msg("world"));
+        assert!(history.resume("other", Some(2)).is_empty());
+        // Messages of the previous owner are gone for good
+        assert!(history.resume("other", Some(2)).is_empty());
+    }
+
+    #[test]
+    fn record_caps_messages_and_resent_count() {
+        let mut history = ChatHistory::default();
+        history.resume("server", None);
+        for i in 0..3 {
+            history.record(msg(&i.to_string()));
+        }
+        history.resume("server", None);
+        for i in 3..=MAX_MESSAGES {
+            history.record(msg(&i.to_string()));
+        }
+
+        assert_eq!(history.messages.len(), MAX_MESSAGES);
+        assert_eq!(history.resent_until.map(|(count, _)| count), Some(2));
+        // Pushed out of the history, so no longer dropped
+        assert!(!history.is_resent(&msg("0")));
+        assert!(history.is_resent(&msg("2")));
+        // Received after the reconnect
+        assert!(!history.is_resent(&msg("3")));
+    }
+
+    #[test]
+    fn resent_messages_dropped_within_window() {
+        let mut history = ChatHistory::default();
+        history.resume("server", None);
+        history.record(msg("hello"));
+        history.resume("server", None);
+
+        assert!(history.is_resent(&msg("hello")));
+        assert!(!history.is_resent(&msg("goodbye")));
+        assert!(!history.is_resent(&ChatMsg {
+            chat_type: ChatType::CommandError,
+            message: "hello".to_string(),
+        }));
+
+        // Pretend the window has passed
+        history.resent_until = history
+            .resent_until
+            .map(|(count, until)| (count, until - RESEND_WINDOW));
+        assert!(!history.is_resent(&msg("hello")));
+    }
+}
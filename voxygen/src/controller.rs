@@ -5,6 +5,11 @@ use crate::window::{GameInput, MenuInput};
 use gilrs::{ev::Code as GilCode, Axis as GilAxis, Button as GilButton};
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// How long after pressing a button of a chord the other one can be pressed
+/// for the chord to count
+const CHORD_WINDOW: Duration = Duration::from_millis(100);
 
 /// Contains all controller related settings and keymaps
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -20,6 +25,7 @@ pub struct ControllerSettings {
     pub button_deadzones: HashMap<AnalogButton, f32>,
     pub mouse_emulation_sensitivity: u32,
     pub inverted_axes: Vec<Axis>,
+    pub chords: Vec<Chord>,
 }
 
 impl ControllerSettings {
@@ -247,6 +253,111 @@ impl From<&crate::settings::GamepadSettings> for ControllerSettings {
             button_deadzones: settings.button_deadzones.clone(),
             mouse_emulation_sensitivity: settings.mouse_emulation_sensitivity,
             inverted_axes: settings.inverted_axes.clone(),
+            chords: settings.chords.clone(),
+        }
+    }
+}
+
+/// Two buttons pressed together, triggering a game input of their own rather
+/// than those of the buttons
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Chord {
+    pub buttons: [Button; 2],
+    pub input: GameInput,
+}
+
+impl Chord {
+    fn other(&self, button: Button) -> Option<Button> {
+        match self.buttons {
+            [a, b] if a == button => Some(b),
+            [a, b] if b == button => Some(a),
+            _ => None,
+        }
+    }
+}
+
+/// A button press or release, or chord input, to dispatch once chords have
+/// been resolved
+#[derive(Debug, PartialEq)]
+pub enum ResolvedInput {
+    Button(Button, bool),
+    Chord(GameInput, bool),
+}
+
+/// Turns button presses into chords. Presses of buttons that are part of a
+/// chord are held back for a short time, so that a chord doesn't also trigger
+/// the actions of its buttons. Other buttons are passed through right away.
+#[derive(Default)]
+pub struct ChordResolver {
+    /// Button of a chord pressed and not dispatched yet, and when
+    pending: Option<(Button, Instant)>,
+    /// Chord being held
+    active: Option<Chord>,
+    /// Button of a released chord that is still held, whose release is dropped
+    swallowed: Option<Button>,
+}
+
+impl ChordResolver {
+    pub fn is_chord_button(chords: &[Chord], button: Button) -> bool {
+        chords.iter().any(|chord| chord.other(button).is_some())
+    }
+
+    pub fn press(&mut self, chords: &[Chord], button: Button) -> Vec<ResolvedInput> {
+        let mut resolved = Vec::new();
+        if let Some((first, _)) = self.pending.take() {
+            match chords
+                .iter()
+                .find(|chord| chord.other(first) == Some(button))
+            {
+                Some(chord) => {
+                    self.active = Some(*chord);
+                    resolved.push(ResolvedInput::Chord(chord.input, true));
+                    return resolved;
+                },
+                // Not a chord after all
+                None => resolved.push(ResolvedInput::Button(first, true)),
+            }
+        }
+        if self.active.is_none() && Self::is_chord_button(chords, button) {
+            self.pending = Some((button, Instant::now()));
+        } else {
+            resolved.push(ResolvedInput::Button(button, true));
+        }
+        resolved
+    }
+
+    pub fn release(&mut self, button: Button) -> Vec<ResolvedInput> {
+        if let Some(chord) = self.active.filter(|chord| chord.other(button).is_some()) {
+            self.active = None;
+            self.swallowed = chord.other(button);
+            return vec![ResolvedInput::Chord(chord.input, false)];
+        }
+        if self.swallowed == Some(button) {
+            self.swallowed = None;
+            return Vec::new();
+        }
+        match self.pending {
+            // Tapped too quickly to wait out the chord window
+            Some((pending, _)) if pending == button => {
+                self.pending = None;
+                vec![
+                    ResolvedInput::Button(button, true),
+                    ResolvedInput::Button(button, false),
+                ]
+            },
+            _ => vec![ResolvedInput::Button(button, false)],
+        }
+    }
+
+    /// Dispatch a held back press once the other button of the chord can't
+    /// follow anymore.
+    pub fn maintain(&mut self) -> Option<ResolvedInput> {
+        match self.pending {
+            Some((button, pressed)) if pressed.elapsed() >= CHORD_WINDOW => {
+                self.pending = None;
+                Some(ResolvedInput::Button(button, true))
+            },
+            _ => None,
         }
     }
 }
@@ -327,3 +438,80 @@ impl From<(GilButton, GilCode)> for AnalogButton {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST: Button = Button::Simple(GilButton::LeftTrigger);
+    const SECOND: Button = Button::Simple(GilButton::RightTrigger);
+    const PLAIN: Button = Button::Simple(GilButton::South);
+
+    fn chords() -> Vec<Chord> {
+        vec![Chord {
+            buttons: [FIRST, SECOND],
+            input: GameInput::Roll,
+        }]
+    }
+
+    #[test]
+    fn chord_pressed_within_window() {
+        let chords = chords();
+        let mut resolver = ChordResolver::default();
+        assert_eq!(resolver.press(&chords, FIRST), vec![]);
+        assert_eq!(resolver.press(&chords, SECOND), vec![ResolvedInput::Chord(
+            GameInput::Roll,
+            true
+        )]);
+        assert_eq!(resolver.maintain(), None);
+    }
+
+    #[test]
+    fn chord_button_released_early_is_plain_press() {
+        let chords = chords();
+        let mut resolver = ChordResolver::default();
+        assert_eq!(resolver.press(&chords, FIRST), vec![]);
+        assert_eq!(resolver.release(FIRST), vec![
+            ResolvedInput::Button(FIRST, true),
+            ResolvedInput::Button(FIRST, false),
+        ]);
+        assert_eq!(resolver.maintain(), None);
+    }
+
+    #[test]
+    fn chord_button_held_past_window() {
+        let chords = chords();
+        let mut resolver = ChordResolver::default();
+        assert_eq!(resolver.press(&chords, FIRST), vec![]);
+        // Pretend the window has passed
+        resolver.pending = resolver
+            .pending
+            .map(|(button, pressed)| (button, pressed - CHORD_WINDOW));
+        assert_eq!(
+            resolver.maintain(),
+            Some(ResolvedInput::Button(FIRST, true))
+        );
+        // Too late for the chord
+        assert_eq!(resolver.press(&chords, SECOND), vec![]);
+        assert_eq!(resolver.release(FIRST), vec![ResolvedInput::Button(
+            FIRST, false
+        )]);
+    }
+
+    #[test]
+    fn chord_released_by_one_button() {
+        let chords = chords();
+        let mut resolver = ChordResolver::default();
+        resolver.press(&chords, FIRST);
+        resolver.press(&chords, SECOND);
+        assert_eq!(resolver.release(SECOND), vec![ResolvedInput::Chord(
+            GameInput::Roll,
+            false
+        )]);
+        // The release of the button still held doesn't reach the game
+        assert_eq!(resolver.release(FIRST), vec![]);
+        assert_eq!(resolver.press(&chords, PLAIN), vec![ResolvedInput::Button(
+            PLAIN, true
+        )]);
+    }
+}
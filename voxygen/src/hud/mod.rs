@@ -46,11 +46,14 @@ use crate::{
     ecs::{comp as vcomp, comp::HpFloaterList},
     hud::img_ids::ImgsRot,
     i18n::{i18n_asset_key, LanguageMetadata, VoxygenLocalization},
+    profile::SessionStats,
     render::{Consts, Globals, RenderMode, Renderer},
+    replay::ReplayState,
     scene::{
         camera::{self, Camera},
         lod,
     },
+    settings::ControlSettings,
     ui::{fonts::ConrodVoxygenFonts, img_ids::Rotations, slot, Graphic, Ingameable, ScaleMode, Ui},
     window::{Event as WinEvent, FullScreenSettings, GameInput},
     GlobalState,
@@ -60,19 +63,22 @@ use common::{
     assets::Asset,
     comp,
     comp::item::{ItemDesc, Quality},
+    region::REGION_SIZE,
     span,
     sync::Uid,
-    terrain::TerrainChunk,
-    vol::RectRasterableVol,
+    terrain::{Block, TerrainChunk, TerrainChunkSize},
+    vol::{RectRasterableVol, RectVolSize},
 };
 use conrod_core::{
+    color,
     text::cursor::Index,
-    widget::{self, Button, Image, Text},
+    widget::{self, Button, Image, Rectangle, Text},
     widget_ids, Color, Colorable, Labelable, Positionable, Sizeable, Widget,
 };
 use specs::{Join, WorldExt};
 use std::{
     collections::{HashMap, VecDeque},
+    path::PathBuf,
     sync::Arc,
     time::Instant,
 };
@@ -156,12 +162,27 @@ const NAMETAG_DMG_TIME: f32 = 60.0;
 const NAMETAG_DMG_RANGE: f32 = 120.0;
 /// Range to display speech-bubbles at
 const SPEECH_BUBBLE_RANGE: f32 = NAMETAG_RANGE;
+/// Distance at which distance scaled nametags are drawn at their normal size
+const NAMETAG_SCALE_DIST: f32 = 15.0;
+/// Smallest and largest scale of distance scaled nametags
+const NAMETAG_MIN_SCALE: f32 = 0.6;
+const NAMETAG_MAX_SCALE: f32 = 1.3;
+/// Dropping more stacks than this at once asks for confirmation first
+const DROP_ALL_CONFIRM_STACKS: usize = 3;
+/// How many times faster one of the client and server may tick than the
+/// other before the server tick rate is flagged in the debug overlay
+const TPS_DIVERGENCE_RATIO: f64 = 2.0;
 
 widget_ids! {
     struct Ids {
         // Crosshair
         crosshair_inner,
         crosshair_outer,
+        crosshair_indicator,
+
+        // Climbing stamina
+        climb_stamina_bg,
+        climb_stamina_fill,
 
         // SCT
         player_scts[],
@@ -177,6 +198,7 @@ widget_ids! {
 
         overheads[],
         overitems[],
+        scout_tags[],
 
         // Intro Text
         intro_bg,
@@ -196,6 +218,7 @@ widget_ids! {
         debug_bg,
         fps_counter,
         ping,
+        server_tps,
         coordinates,
         velocity,
         orientation,
@@ -254,6 +277,53 @@ widget_ids! {
         // Auto walk indicator
         auto_walk_txt,
         auto_walk_bg,
+        walk_txt,
+        walk_bg,
+
+        // Input replay indicator
+        replay_txt,
+        replay_bg,
+
+        // Disconnect countdown banner
+        disconnect_txt,
+        disconnect_bg,
+        afk_logout_txt,
+        afk_logout_bg,
+        dnd_txt,
+        dnd_bg,
+        quick_consumable_txt,
+        quick_consumable_bg,
+        close_confirm_bg,
+        close_confirm_txt,
+        close_confirm_logout,
+        close_confirm_cancel,
+        logout_summary_bg,
+        logout_summary_txt,
+        logout_summary_okay,
+        leave_group_confirm_bg,
+        leave_group_confirm_txt,
+        leave_group_confirm_leave,
+        leave_group_confirm_cancel,
+        drop_all_confirm_bg,
+        drop_all_confirm_txt,
+        drop_all_confirm_drop,
+        drop_all_confirm_cancel,
+        compass_bg,
+        compass_heading,
+        compass_points[],
+        clock,
+
+        // Hint for targets with several interactions
+        interactions_txt,
+        interactions_bg,
+        session_stats_txt,
+        session_stats_bg,
+        inspect_txt,
+        inspect_bg,
+        inspect_block_txt,
+        inspect_block_bg,
+        group_coordinates_txt,
+        group_coordinates_bg,
 
         // Example Quest
         quest_bg,
@@ -267,6 +337,7 @@ widget_ids! {
 
 pub struct DebugInfo {
     pub tps: f64,
+    pub server_tps: Option<f64>,
     pub ping_ms: f64,
     pub coordinates: Option<comp::Pos>,
     pub velocity: Option<comp::Vel>,
@@ -286,6 +357,42 @@ pub struct HudInfo {
     pub is_first_person: bool,
     pub target_entity: Option<specs::Entity>,
     pub selected_entity: Option<(specs::Entity, std::time::Instant)>,
+    /// FOV and gamma in effect, including per-character overrides
+    pub fov: u16,
+    pub gamma: f32,
+    pub session_stats: SessionStats,
+    /// Lifetime totals of the character, if they are being saved
+    pub lifetime_stats: Option<SessionStats>,
+    /// Degrees clockwise from north
+    pub compass_heading: f32,
+    /// Seconds since the start of the first in-game day, if the world has a
+    /// time of day
+    pub time_of_day: Option<f64>,
+    /// Where the aim ray lands on screen, in normalized device coordinates, if
+    /// the crosshair should follow it. Can lie outside of the screen.
+    pub aim_point: Option<Vec2<f32>>,
+    /// Gear of the selected entity, if it is being inspected
+    pub inspected_gear: Option<InspectedGear>,
+    /// Position of the block under the cursor and the block, if blocks are
+    /// being inspected. `Some(None)` when no block is targeted.
+    pub inspected_block: Option<Option<(Vec3<i32>, Block)>>,
+    /// Energy left as a fraction of the maximum, if the player is climbing
+    pub climb_stamina: Option<f32>,
+    /// Name and amount left of the consumable used with the quick use key, if
+    /// one is selected
+    pub quick_consumable: Option<(String, u32)>,
+    /// Names of the players tagged by the scout overlay and where to tag them
+    pub scout_targets: Vec<(String, Vec3<f32>)>,
+    /// Names of the group members, with where they are and how far away if
+    /// known, while their coordinates are shown
+    pub group_coordinates: Vec<(String, Option<(Vec3<f32>, f32)>)>,
+}
+
+/// What the selected entity has equipped, as far as the server sends it.
+pub struct InspectedGear {
+    pub name: String,
+    /// Localization key of each filled slot, and the name of the item in it
+    pub slots: Vec<(&'static str, String)>,
 }
 
 pub enum Event {
@@ -330,17 +437,32 @@ pub enum Event {
     UseSlot(comp::slot::Slot),
     SwapSlots(comp::slot::Slot, comp::slot::Slot),
     DropSlot(comp::slot::Slot),
+    /// Drop every inventory stack of the item with this definition id
+    DropAllOfType(String),
     ChangeHotbarState(Box<HotbarState>),
     Ability3(bool),
     Logout,
     Quit,
+    /// Log out safely and then quit, after the window close was confirmed
+    ConfirmClose,
+    CancelClose,
+    /// The summary shown before leaving the game was dismissed
+    CloseLogoutSummary,
+    /// Leave the group after it was confirmed
+    ConfirmLeaveGroup,
+    CancelLeaveGroup,
     ChangeLanguage(Box<LanguageMetadata>),
     ChangeBinding(GameInput),
     ResetBindings,
+    ExportBindings(PathBuf),
+    ImportBindings(PathBuf),
     ChangeFreeLookBehavior(PressBehavior),
     ChangeRenderMode(Box<RenderMode>),
     ChangeAutoWalkBehavior(PressBehavior),
     ChangeStopAutoWalkOnInput(bool),
+    AdjustFreeflyAccel(f32),
+    AdjustFreeflyDamping(f32),
+    AdjustFreeflyMaxSpeed(f32),
     CraftRecipe(String),
     InviteMember(common::sync::Uid),
     AcceptInvite,
@@ -383,6 +505,30 @@ pub enum BarNumbers {
     Percent,
     Off,
 }
+/// How the player's position is shown in the debug overlay
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CoordinateDisplayMode {
+    /// World position in blocks
+    Absolute,
+    /// Chunk, and position within that chunk
+    Chunk,
+    /// Region, and position within that region
+    Region,
+}
+/// How the size of nametags is chosen
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NameplateScaleMode {
+    Fixed,
+    /// Shrink nametags with distance, within limits
+    DistanceScaled,
+}
+/// How the in-game time of day is shown next to the compass
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ClockFormat {
+    Off,
+    TwentyFourHour,
+    TwelveHour,
+}
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum ShortcutNumbers {
     On,
@@ -416,6 +562,17 @@ pub struct Show {
     stats: bool,
     free_look: bool,
     auto_walk: bool,
+    walking: bool,
+    replay_state: ReplayState,
+    multiple_interactions: bool,
+    session_stats: bool,
+    close_confirmation: bool,
+    logout_summary: bool,
+    /// Confirmation before leaving the group, and whether the player leads it
+    leave_group_confirmation: Option<bool>,
+    /// Confirmation before dropping every stack of an item, with the item
+    /// definition id, its name and the number of stacks
+    drop_all_confirmation: Option<(String, String, usize)>,
 }
 impl Show {
     fn bag(&mut self, open: bool) {
@@ -593,6 +750,20 @@ pub struct Hud {
     hotbar: hotbar::State,
     events: Vec<Event>,
     crosshair_opacity: f32,
+    /// Shown climbing stamina, following the actual one smoothly
+    climb_stamina: Option<f32>,
+    /// Seconds until the server disconnects us, and when we were told so
+    disconnect_countdown: Option<(u64, Instant)>,
+    /// Seconds until the player is logged out for being AFK, once close enough
+    /// to warn them
+    afk_logout_countdown: Option<u64>,
+    /// Number of notifications held back while do not disturb is on
+    dnd_queued: Option<usize>,
+    /// Hidden for a screenshot, independent of the player's interface toggle
+    hidden_for_screenshot: bool,
+    /// Inventory slot whose item type should all be dropped, waiting for the
+    /// inventory to be looked at
+    drop_all_slot: Option<usize>,
 }
 
 impl Hud {
@@ -675,6 +846,14 @@ impl Hud {
                 stats: false,
                 free_look: false,
                 auto_walk: false,
+                walking: false,
+                replay_state: ReplayState::Idle,
+                multiple_interactions: false,
+                session_stats: false,
+                close_confirmation: false,
+                logout_summary: false,
+                leave_group_confirmation: None,
+                drop_all_confirmation: None,
             },
             to_focus: None,
             //never_show: false,
@@ -689,6 +868,12 @@ impl Hud {
             hotbar: hotbar_state,
             events: Vec::new(),
             crosshair_opacity: 0.0,
+            climb_stamina: None,
+            disconnect_countdown: None,
+            afk_logout_countdown: None,
+            dnd_queued: None,
+            hidden_for_screenshot: false,
+            drop_all_slot: None,
         }
     }
 
@@ -776,6 +961,20 @@ impl Hud {
                     5.0 * dt.as_secs_f32(),
                 );
 
+                // Keep the crosshair on screen when the aim point isn't, with an arrow
+                // pointing towards it
+                let crosshair_margin = 21.0 * 1.5;
+                let (crosshair_offset, crosshair_clamped) = match info.aim_point {
+                    Some(aim_point) if show_crosshair => {
+                        let half_win = Vec2::new(ui_widgets.win_w, ui_widgets.win_h) / 2.0;
+                        let offset = aim_point.map(f64::from) * half_win;
+                        let max = half_win - crosshair_margin;
+                        let clamped = offset.map2(max, |o, max| o.max(-max).min(max));
+                        (clamped, clamped != offset)
+                    },
+                    _ => (Vec2::zero(), false),
+                };
+
                 if !self.show.help {
                     Image::new(
                         // TODO: Do we want to match on this every frame?
@@ -786,7 +985,7 @@ impl Hud {
                         },
                     )
                     .w_h(21.0 * 1.5, 21.0 * 1.5)
-                    .middle_of(ui_widgets.window)
+                    .x_y_relative_to(ui_widgets.window, crosshair_offset.x, crosshair_offset.y)
                     .color(Some(Color::Rgba(
                         1.0,
                         1.0,
@@ -799,6 +998,51 @@ impl Hud {
                         .middle_of(self.ids.crosshair_outer)
                         .color(Some(Color::Rgba(1.0, 1.0, 1.0, 0.6)))
                         .set(self.ids.crosshair_inner, ui_widgets);
+                    if crosshair_clamped {
+                        let (arrow, dir) = if crosshair_offset.x.abs() / ui_widgets.win_w
+                            > crosshair_offset.y.abs() / ui_widgets.win_h
+                        {
+                            if crosshair_offset.x > 0.0 {
+                                (self.rot_imgs.indicator_mmap_small.cw90, Vec2::unit_x())
+                            } else {
+                                (self.rot_imgs.indicator_mmap_small.cw270, -Vec2::unit_x())
+                            }
+                        } else if crosshair_offset.y > 0.0 {
+                            (self.rot_imgs.indicator_mmap_small.none, Vec2::unit_y())
+                        } else {
+                            (self.rot_imgs.indicator_mmap_small.cw180, -Vec2::unit_y())
+                        };
+                        let arrow_offset = dir * crosshair_margin * 0.6;
+                        Image::new(arrow)
+                            .w_h(32.0 * 0.4, 37.0 * 0.4)
+                            .x_y_relative_to(
+                                self.ids.crosshair_outer,
+                                arrow_offset.x,
+                                arrow_offset.y,
+                            )
+                            .color(Some(Color::Rgba(
+                                1.0,
+                                1.0,
+                                1.0,
+                                self.crosshair_opacity
+                                    * global_state.settings.gameplay.crosshair_transp,
+                            )))
+                            .set(self.ids.crosshair_indicator, ui_widgets);
+                    }
+                }
+
+                // Climbing stamina, next to the crosshair
+                self.climb_stamina = info.climb_stamina.map(|stamina| match self.climb_stamina {
+                    Some(shown) => Lerp::lerp(shown, stamina, 8.0 * dt.as_secs_f32()),
+                    None => stamina,
+                });
+                if let Some(stamina) = self.climb_stamina {
+                    Rectangle::fill_with([6.0, 40.0], color::BLACK.alpha(0.6))
+                        .x_y_relative_to(ui_widgets.window, 40.0, 0.0)
+                        .set(self.ids.climb_stamina_bg, ui_widgets);
+                    Rectangle::fill_with([4.0, 38.0 * f64::from(stamina)], MANA_COLOR)
+                        .mid_bottom_with_margin_on(self.ids.climb_stamina_bg, 1.0)
+                        .set(self.ids.climb_stamina_fill, ui_widgets);
                 }
             }
 
@@ -1117,10 +1361,33 @@ impl Hud {
                 .set(overitem_id, ui_widgets);
             }
 
+            // Render scout tags, which show through terrain
+            let mut scout_tag_walker = self.ids.scout_tags.walk();
+            for (name, pos) in &info.scout_targets {
+                let scout_tag_id = scout_tag_walker.next(
+                    &mut self.ids.scout_tags,
+                    &mut ui_widgets.widget_id_generator(),
+                );
+                Text::new(
+                    &self
+                        .voxygen_i18n
+                        .get("hud.scout_tag")
+                        .replace("{name}", name)
+                        .replace("{distance}", &format!("{:.0}", pos.distance(player_pos))),
+                )
+                .font_size(self.fonts.cyri.scale(14))
+                .font_id(self.fonts.cyri.conrod_id)
+                .color(KILL_COLOR)
+                .x_y(0.0, 0.0)
+                .position_ingame(*pos)
+                .set(scout_tag_id, ui_widgets);
+            }
+
             let speech_bubbles = &self.speech_bubbles;
+            let nameplate_scale_mode = global_state.settings.gameplay.nameplate_scale_mode;
 
             // Render overhead name tags and health bars
-            for (pos, info, bubble, stats, height_offset, hpfl, in_group) in (
+            for (pos, info, bubble, stats, height_offset, hpfl, in_group, name_scale) in (
                 &entities,
                 &pos,
                 interpolated.maybe(),
@@ -1165,6 +1432,19 @@ impl Hud {
                                     })
                                     .powi(2);
 
+                        // Targeted and selected entities keep their normal size so they stay
+                        // readable
+                        let is_targeted = info.target_entity.map_or(false, |e| e == entity)
+                            || info.selected_entity.map_or(false, |s| s.0 == entity);
+                        let name_scale = match nameplate_scale_mode {
+                            NameplateScaleMode::DistanceScaled if !is_targeted => {
+                                (NAMETAG_SCALE_DIST / dist_sqr.sqrt().max(0.01))
+                                    .max(NAMETAG_MIN_SCALE)
+                                    .min(NAMETAG_MAX_SCALE)
+                            },
+                            _ => 1.0,
+                        };
+
                         let info = display_overhead_info.then(|| overhead::Info {
                             name: &stats.name,
                             stats,
@@ -1185,6 +1465,7 @@ impl Hud {
                                 body.height() * scale.map_or(1.0, |s| s.0) + 0.5,
                                 hpfl,
                                 in_group,
+                                name_scale,
                             )
                         })
                     },
@@ -1205,6 +1486,7 @@ impl Hud {
                     bubble,
                     own_level,
                     in_group,
+                    name_scale,
                     &global_state.settings.gameplay,
                     self.pulse,
                     &self.voxygen_i18n,
@@ -1495,17 +1777,40 @@ impl Hud {
                 .font_id(self.fonts.cyri.conrod_id)
                 .font_size(self.fonts.cyri.scale(14))
                 .set(self.ids.ping, ui_widgets);
+            // Server ticks per second, flagged when far off from the client's
+            let (server_tps_text, server_tps_color) = match debug_info.server_tps {
+                Some(server_tps) => {
+                    let ratio = server_tps / debug_info.tps.max(f64::EPSILON);
+                    let diverged =
+                        !(1.0 / TPS_DIVERGENCE_RATIO..=TPS_DIVERGENCE_RATIO).contains(&ratio);
+                    (
+                        format!("Server TPS: {:.0}", server_tps),
+                        if diverged {
+                            CRITICAL_HP_COLOR
+                        } else {
+                            TEXT_COLOR
+                        },
+                    )
+                },
+                None => ("Server TPS: N/A".to_owned(), TEXT_COLOR),
+            };
+            Text::new(&server_tps_text)
+                .color(server_tps_color)
+                .down_from(self.ids.ping, 5.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.server_tps, ui_widgets);
             // Player's position
             let coordinates_text = match debug_info.coordinates {
-                Some(coordinates) => format!(
-                    "Coordinates: ({:.0}, {:.0}, {:.0})",
-                    coordinates.0.x, coordinates.0.y, coordinates.0.z,
+                Some(coordinates) => format_coordinates(
+                    coordinates.0,
+                    global_state.settings.gameplay.coordinate_display_mode,
                 ),
                 None => "Player has no Pos component".to_owned(),
             };
             Text::new(&coordinates_text)
                 .color(TEXT_COLOR)
-                .down_from(self.ids.ping, 5.0)
+                .down_from(self.ids.server_tps, 5.0)
                 .font_id(self.fonts.cyri.conrod_id)
                 .font_size(self.fonts.cyri.scale(14))
                 .set(self.ids.coordinates, ui_widgets);
@@ -1943,6 +2248,8 @@ impl Hud {
                 &self.fonts,
                 &self.voxygen_i18n,
                 fps as f32,
+                info.fov,
+                info.gamma,
             )
             .set(self.ids.settings_window, ui_widgets)
             {
@@ -2070,6 +2377,12 @@ impl Hud {
                     settings_window::Event::ResetBindings => {
                         events.push(Event::ResetBindings);
                     },
+                    settings_window::Event::ExportBindings => {
+                        events.push(Event::ExportBindings(ControlSettings::get_bindings_path()));
+                    },
+                    settings_window::Event::ImportBindings => {
+                        events.push(Event::ImportBindings(ControlSettings::get_bindings_path()));
+                    },
                     settings_window::Event::ChangeFreeLookBehavior(behavior) => {
                         events.push(Event::ChangeFreeLookBehavior(behavior));
                     },
@@ -2079,6 +2392,15 @@ impl Hud {
                     settings_window::Event::ChangeStopAutoWalkOnInput(state) => {
                         events.push(Event::ChangeStopAutoWalkOnInput(state));
                     },
+                    settings_window::Event::AdjustFreeflyAccel(accel) => {
+                        events.push(Event::AdjustFreeflyAccel(accel));
+                    },
+                    settings_window::Event::AdjustFreeflyDamping(damping) => {
+                        events.push(Event::AdjustFreeflyDamping(damping));
+                    },
+                    settings_window::Event::AdjustFreeflyMaxSpeed(max_speed) => {
+                        events.push(Event::AdjustFreeflyMaxSpeed(max_speed));
+                    },
                 }
             }
         }
@@ -2271,6 +2593,646 @@ impl Hud {
                 .set(self.ids.auto_walk_txt, ui_widgets);
         }
 
+        // Walk indicator
+        if self.show.walking {
+            Text::new(&self.voxygen_i18n.get("hud.walk_indicator"))
+                .color(TEXT_BG)
+                .mid_top_with_margin_on(ui_widgets.window, 250.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(20))
+                .set(self.ids.walk_bg, ui_widgets);
+            Text::new(&self.voxygen_i18n.get("hud.walk_indicator"))
+                .color(TEXT_COLOR)
+                .top_left_with_margins_on(self.ids.walk_bg, -1.0, -1.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(20))
+                .set(self.ids.walk_txt, ui_widgets);
+        }
+
+        // Input replay indicator
+        let replay_key = match self.show.replay_state {
+            ReplayState::Idle => None,
+            ReplayState::Recording => Some("hud.replay_recording_indicator"),
+            ReplayState::Playback => Some("hud.replay_playback_indicator"),
+        };
+        if let Some(replay_key) = replay_key {
+            Text::new(&self.voxygen_i18n.get(replay_key))
+                .color(TEXT_BG)
+                .mid_top_with_margin_on(ui_widgets.window, 100.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(20))
+                .set(self.ids.replay_bg, ui_widgets);
+            Text::new(&self.voxygen_i18n.get(replay_key))
+                .color(KILL_COLOR)
+                .top_left_with_margins_on(self.ids.replay_bg, -1.0, -1.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(20))
+                .set(self.ids.replay_txt, ui_widgets);
+        }
+
+        // Hint for targets that can be both mounted and picked up
+        if self.show.multiple_interactions {
+            let controls = &global_state.settings.controls;
+            if let (Some(mount_key), Some(interact_key)) = (
+                controls.get_binding(GameInput::Mount),
+                controls.get_binding(GameInput::Interact),
+            ) {
+                let text = self
+                    .voxygen_i18n
+                    .get("hud.multiple_interactions")
+                    .replace("{mount_key}", mount_key.to_string().as_str())
+                    .replace("{interact_key}", interact_key.to_string().as_str());
+                Text::new(&text)
+                    .color(TEXT_BG)
+                    .mid_top_with_margin_on(ui_widgets.window, 160.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(20))
+                    .set(self.ids.interactions_bg, ui_widgets);
+                Text::new(&text)
+                    .color(TEXT_COLOR)
+                    .top_left_with_margins_on(self.ids.interactions_bg, -1.0, -1.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(20))
+                    .set(self.ids.interactions_txt, ui_widgets);
+            }
+        }
+
+        // Session stats panel
+        if self.show.session_stats {
+            let stats = info.session_stats;
+            let lifetime = info.lifetime_stats;
+            let i18n = &self.voxygen_i18n;
+            let line =
+                |key: &str, value: String, lifetime_value: Option<String>| match lifetime_value {
+                    Some(lifetime_value) => format!(
+                        "{}: {} ({}: {})",
+                        i18n.get(key),
+                        value,
+                        i18n.get("hud.session_stats.lifetime"),
+                        lifetime_value
+                    ),
+                    None => format!("{}: {}", i18n.get(key), value),
+                };
+            let text = [
+                i18n.get("hud.session_stats.title").to_owned(),
+                line(
+                    "hud.session_stats.play_time",
+                    format_play_time(stats.play_time),
+                    lifetime.map(|l| format_play_time(l.play_time)),
+                ),
+                line(
+                    "hud.session_stats.distance_traveled",
+                    format!("{:.0}m", stats.distance_traveled),
+                    lifetime.map(|l| format!("{:.0}m", l.distance_traveled)),
+                ),
+                line(
+                    "hud.session_stats.blocks_placed",
+                    stats.blocks_placed.to_string(),
+                    lifetime.map(|l| l.blocks_placed.to_string()),
+                ),
+                line(
+                    "hud.session_stats.blocks_removed",
+                    stats.blocks_removed.to_string(),
+                    lifetime.map(|l| l.blocks_removed.to_string()),
+                ),
+                line(
+                    "hud.session_stats.items_collected",
+                    stats.items_collected.to_string(),
+                    lifetime.map(|l| l.items_collected.to_string()),
+                ),
+            ]
+            .join("\n");
+            Text::new(&text)
+                .color(TEXT_BG)
+                .mid_left_with_margin_on(ui_widgets.window, 10.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.session_stats_bg, ui_widgets);
+            Text::new(&text)
+                .color(TEXT_COLOR)
+                .top_left_with_margins_on(self.ids.session_stats_bg, -1.0, -1.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.session_stats_txt, ui_widgets);
+        }
+
+        // Disconnect countdown banner
+        // Notifications arrive roughly every second while the connection is bad, so
+        // if they stop coming the connection has recovered.
+        const DISCONNECT_COUNTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+        if let Some((time, received)) = self.disconnect_countdown {
+            let elapsed = received.elapsed();
+            if elapsed > DISCONNECT_COUNTDOWN_TIMEOUT {
+                self.disconnect_countdown = None;
+            } else {
+                let remaining = time.saturating_sub(elapsed.as_secs());
+                let text = self
+                    .voxygen_i18n
+                    .get("hud.disconnect_countdown")
+                    .replace("{time}", remaining.to_string().as_str());
+                Text::new(&text)
+                    .color(TEXT_BG)
+                    .mid_top_with_margin_on(ui_widgets.window, 130.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(30))
+                    .set(self.ids.disconnect_bg, ui_widgets);
+                Text::new(&text)
+                    .color(KILL_COLOR)
+                    .top_left_with_margins_on(self.ids.disconnect_bg, -1.0, -1.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(30))
+                    .set(self.ids.disconnect_txt, ui_widgets);
+            }
+        }
+
+        // Compass strip
+        if global_state.settings.gameplay.show_compass {
+            const COMPASS_WIDTH: f64 = 360.0;
+            // Degrees of heading visible across the strip
+            const COMPASS_SPAN: f32 = 180.0;
+            const COMPASS_POINTS: [&str; 8] = [
+                "hud.compass.n",
+                "hud.compass.ne",
+                "hud.compass.e",
+                "hud.compass.se",
+                "hud.compass.s",
+                "hud.compass.sw",
+                "hud.compass.w",
+                "hud.compass.nw",
+            ];
+            let heading = info.compass_heading;
+            Rectangle::fill_with([COMPASS_WIDTH, 20.0], color::TRANSPARENT)
+                .mid_top_with_margin_on(ui_widgets.window, 10.0)
+                .set(self.ids.compass_bg, ui_widgets);
+            let mut point_walker = self.ids.compass_points.walk();
+            for (i, key) in COMPASS_POINTS.iter().enumerate() {
+                let point_id = point_walker.next(
+                    &mut self.ids.compass_points,
+                    &mut ui_widgets.widget_id_generator(),
+                );
+                // Offset from the current heading, from -180 to 180 degrees
+                let offset = (i as f32 * 45.0 - heading + 540.0).rem_euclid(360.0) - 180.0;
+                if offset.abs() > COMPASS_SPAN / 2.0 {
+                    continue;
+                }
+                Text::new(self.voxygen_i18n.get(key))
+                    .x_y_relative_to(
+                        self.ids.compass_bg,
+                        (offset / COMPASS_SPAN) as f64 * COMPASS_WIDTH,
+                        0.0,
+                    )
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(if i % 2 == 0 { 16 } else { 12 }))
+                    .color(if i == 0 { KILL_COLOR } else { TEXT_COLOR })
+                    .set(point_id, ui_widgets);
+            }
+            Text::new(&format!("{:03.0}°", heading.round() % 360.0))
+                .right_from(self.ids.compass_bg, 10.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .color(TEXT_COLOR)
+                .set(self.ids.compass_heading, ui_widgets);
+        }
+
+        // Time of day clock
+        let clock_format = match global_state.settings.gameplay.clock_format {
+            ClockFormat::Off => None,
+            ClockFormat::TwentyFourHour => Some("%H:%M"),
+            ClockFormat::TwelveHour => Some("%-I:%M %p"),
+        };
+        if let (Some(clock_format), Some(time_of_day)) = (clock_format, info.time_of_day) {
+            let time = NaiveTime::from_num_seconds_from_midnight(
+                time_of_day.rem_euclid(86400.0) as u32,
+                0,
+            );
+            // Use the sun position the scene is lit with, so the clock agrees with the sky
+            let period = if Globals::get_sun_dir(time_of_day).z < 0.0 {
+                "hud.clock.day"
+            } else {
+                "hud.clock.night"
+            };
+            let text = format!(
+                "{} {}",
+                time.format(clock_format),
+                self.voxygen_i18n.get(period)
+            );
+            let clock = Text::new(&text)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .color(TEXT_COLOR);
+            if global_state.settings.gameplay.show_compass {
+                clock.left_from(self.ids.compass_bg, 10.0)
+            } else {
+                clock.mid_top_with_margin_on(ui_widgets.window, 10.0)
+            }
+            .set(self.ids.clock, ui_widgets);
+        }
+
+        // AFK logout warning
+        if let Some(remaining) = self.afk_logout_countdown {
+            let text = self
+                .voxygen_i18n
+                .get("hud.afk_logout_countdown")
+                .replace("{time}", remaining.to_string().as_str());
+            Text::new(&text)
+                .color(TEXT_BG)
+                .mid_top_with_margin_on(ui_widgets.window, 190.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(20))
+                .set(self.ids.afk_logout_bg, ui_widgets);
+            Text::new(&text)
+                .color(KILL_COLOR)
+                .top_left_with_margins_on(self.ids.afk_logout_bg, -1.0, -1.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(20))
+                .set(self.ids.afk_logout_txt, ui_widgets);
+        }
+
+        // Do not disturb badge
+        if let Some(queued) = self.dnd_queued {
+            let text = self
+                .voxygen_i18n
+                .get("hud.do_not_disturb")
+                .replace("{count}", &queued.to_string());
+            Text::new(&text)
+                .color(TEXT_BG)
+                .mid_top_with_margin_on(ui_widgets.window, 220.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.dnd_bg, ui_widgets);
+            Text::new(&text)
+                .color(TEXT_COLOR)
+                .top_left_with_margins_on(self.ids.dnd_bg, -1.0, -1.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.dnd_txt, ui_widgets);
+        }
+
+        // Coordinates of the group members
+        if !info.group_coordinates.is_empty() {
+            let i18n = &self.voxygen_i18n;
+            let text = info
+                .group_coordinates
+                .iter()
+                .map(|(name, located)| match located {
+                    Some((pos, distance)) => i18n
+                        .get("hud.group_coordinates")
+                        .replace("{name}", name)
+                        .replace("{x}", &format!("{:.0}", pos.x))
+                        .replace("{y}", &format!("{:.0}", pos.y))
+                        .replace("{z}", &format!("{:.0}", pos.z))
+                        .replace("{distance}", &format!("{:.0}", distance)),
+                    None => i18n
+                        .get("hud.group_coordinates_unknown")
+                        .replace("{name}", name),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Text::new(&text)
+                .color(TEXT_BG)
+                .top_right_with_margins_on(ui_widgets.window, 260.0, 10.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.group_coordinates_bg, ui_widgets);
+            Text::new(&text)
+                .color(TEXT_COLOR)
+                .top_left_with_margins_on(self.ids.group_coordinates_bg, -1.0, -1.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.group_coordinates_txt, ui_widgets);
+        }
+
+        // Consumable used with the quick use key
+        if let Some((name, amount)) = &info.quick_consumable {
+            let (text, color) = if *amount > 0 {
+                (
+                    self.voxygen_i18n
+                        .get("hud.quick_consumable")
+                        .replace("{item}", name)
+                        .replace("{amount}", &amount.to_string()),
+                    TEXT_COLOR,
+                )
+            } else {
+                (
+                    self.voxygen_i18n
+                        .get("hud.quick_consumable_empty")
+                        .replace("{item}", name),
+                    CRITICAL_HP_COLOR,
+                )
+            };
+            Text::new(&text)
+                .color(TEXT_BG)
+                .mid_bottom_with_margin_on(ui_widgets.window, 200.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.quick_consumable_bg, ui_widgets);
+            Text::new(&text)
+                .color(color)
+                .top_left_with_margins_on(self.ids.quick_consumable_bg, -1.0, -1.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.quick_consumable_txt, ui_widgets);
+        }
+
+        // Gear of the inspected entity
+        if let Some(gear) = &info.inspected_gear {
+            let i18n = &self.voxygen_i18n;
+            let mut lines = vec![i18n.get("hud.inspect.title").replace("{name}", &gear.name)];
+            if gear.slots.is_empty() {
+                lines.push(i18n.get("hud.inspect.nothing_equipped").to_owned());
+            }
+            lines.extend(
+                gear.slots
+                    .iter()
+                    .map(|(slot, item)| format!("{}: {}", i18n.get(slot), item)),
+            );
+            let text = lines.join("\n");
+            Text::new(&text)
+                .color(TEXT_BG)
+                .mid_right_with_margin_on(ui_widgets.window, 10.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.inspect_bg, ui_widgets);
+            Text::new(&text)
+                .color(TEXT_COLOR)
+                .top_left_with_margins_on(self.ids.inspect_bg, -1.0, -1.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.inspect_txt, ui_widgets);
+        }
+
+        // Block under the cursor
+        if let Some(inspected_block) = info.inspected_block {
+            let i18n = &self.voxygen_i18n;
+            let text = match inspected_block {
+                Some((pos, block)) => {
+                    let yes_no = |b: bool| i18n.get(if b { "common.yes" } else { "common.no" });
+                    let color = block.get_color().map_or_else(
+                        || i18n.get("common.none").to_owned(),
+                        |col| format!("{}, {}, {}", col.r, col.g, col.b),
+                    );
+                    [
+                        format!("{}: {:?}", i18n.get("hud.inspect_block.kind"), block.kind()),
+                        format!(
+                            "{}: {}, {}, {}",
+                            i18n.get("hud.inspect_block.position"),
+                            pos.x,
+                            pos.y,
+                            pos.z
+                        ),
+                        format!("{}: {}", i18n.get("hud.inspect_block.color"), color),
+                        format!(
+                            "{}: {}",
+                            i18n.get("hud.inspect_block.collectible"),
+                            yes_no(block.is_collectible())
+                        ),
+                        format!(
+                            "{}: {}",
+                            i18n.get("hud.inspect_block.filled"),
+                            yes_no(block.kind().is_filled())
+                        ),
+                    ]
+                    .join("\n")
+                },
+                None => i18n.get("hud.inspect_block.nothing_targeted").to_owned(),
+            };
+            Text::new(&text)
+                .color(TEXT_BG)
+                .mid_left_with_margin_on(ui_widgets.window, 10.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.inspect_block_bg, ui_widgets);
+            Text::new(&text)
+                .color(TEXT_COLOR)
+                .top_left_with_margins_on(self.ids.inspect_block_bg, -1.0, -1.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .set(self.ids.inspect_block_txt, ui_widgets);
+        }
+
+        // Confirmation before closing the game
+        if self.show.close_confirmation {
+            Rectangle::fill_with([500.0, 150.0], color::BLACK.alpha(0.8))
+                .middle_of(ui_widgets.window)
+                .set(self.ids.close_confirm_bg, ui_widgets);
+            Text::new(&self.voxygen_i18n.get("hud.close_confirmation"))
+                .mid_top_with_margin_on(self.ids.close_confirm_bg, 20.0)
+                .w(460.0)
+                .center_justify()
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(18))
+                .color(TEXT_COLOR)
+                .set(self.ids.close_confirm_txt, ui_widgets);
+            if Button::image(self.imgs.button)
+                .w_h(200.0, 45.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .bottom_left_with_margins_on(self.ids.close_confirm_bg, 15.0, 35.0)
+                .label(&self.voxygen_i18n.get("hud.close_confirmation.logout"))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_font_size(self.fonts.cyri.scale(18))
+                .label_color(TEXT_COLOR)
+                .label_y(conrod_core::position::Relative::Scalar(2.0))
+                .set(self.ids.close_confirm_logout, ui_widgets)
+                .was_clicked()
+            {
+                self.show.close_confirmation = false;
+                events.push(Event::ConfirmClose);
+            }
+            if Button::image(self.imgs.button)
+                .w_h(200.0, 45.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .bottom_right_with_margins_on(self.ids.close_confirm_bg, 15.0, 35.0)
+                .label(&self.voxygen_i18n.get("common.cancel"))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_font_size(self.fonts.cyri.scale(18))
+                .label_color(TEXT_COLOR)
+                .label_y(conrod_core::position::Relative::Scalar(2.0))
+                .set(self.ids.close_confirm_cancel, ui_widgets)
+                .was_clicked()
+            {
+                self.show.close_confirmation = false;
+                self.show.want_grab = true;
+                events.push(Event::CancelClose);
+            }
+        }
+
+        // Confirmation before leaving the group
+        if let Some(is_leader) = self.show.leave_group_confirmation {
+            let key = if is_leader {
+                "hud.group.leave_confirmation.leader"
+            } else {
+                "hud.group.leave_confirmation"
+            };
+            Rectangle::fill_with([500.0, 150.0], color::BLACK.alpha(0.8))
+                .middle_of(ui_widgets.window)
+                .set(self.ids.leave_group_confirm_bg, ui_widgets);
+            Text::new(&self.voxygen_i18n.get(key))
+                .mid_top_with_margin_on(self.ids.leave_group_confirm_bg, 20.0)
+                .w(460.0)
+                .center_justify()
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(18))
+                .color(TEXT_COLOR)
+                .set(self.ids.leave_group_confirm_txt, ui_widgets);
+            if Button::image(self.imgs.button)
+                .w_h(200.0, 45.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .bottom_left_with_margins_on(self.ids.leave_group_confirm_bg, 15.0, 35.0)
+                .label(&self.voxygen_i18n.get("hud.group.leave"))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_font_size(self.fonts.cyri.scale(18))
+                .label_color(TEXT_COLOR)
+                .label_y(conrod_core::position::Relative::Scalar(2.0))
+                .set(self.ids.leave_group_confirm_leave, ui_widgets)
+                .was_clicked()
+            {
+                self.show.leave_group_confirmation = None;
+                events.push(Event::ConfirmLeaveGroup);
+            }
+            if Button::image(self.imgs.button)
+                .w_h(200.0, 45.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .bottom_right_with_margins_on(self.ids.leave_group_confirm_bg, 15.0, 35.0)
+                .label(&self.voxygen_i18n.get("common.cancel"))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_font_size(self.fonts.cyri.scale(18))
+                .label_color(TEXT_COLOR)
+                .label_y(conrod_core::position::Relative::Scalar(2.0))
+                .set(self.ids.leave_group_confirm_cancel, ui_widgets)
+                .was_clicked()
+            {
+                self.show.leave_group_confirmation = None;
+                events.push(Event::CancelLeaveGroup);
+            }
+        }
+
+        // Drop every stack of the selected item, asking first if there are many
+        if let Some(slot) = self.drop_all_slot.take() {
+            let inventories = client.inventories();
+            if let Some((inventory, item)) = inventories
+                .get(client.entity())
+                .and_then(|inventory| Some((inventory, inventory.get(slot)?)))
+            {
+                let item_def_id = item.item_definition_id();
+                let stacks = inventory
+                    .slots()
+                    .iter()
+                    .flatten()
+                    .filter(|i| i.item_definition_id() == item_def_id)
+                    .count();
+                if stacks > DROP_ALL_CONFIRM_STACKS {
+                    self.show.drop_all_confirmation =
+                        Some((item_def_id.to_owned(), item.name().to_owned(), stacks));
+                } else {
+                    events.push(Event::DropAllOfType(item_def_id.to_owned()));
+                }
+            }
+        }
+        if let Some((item_def_id, name, stacks)) = &self.show.drop_all_confirmation {
+            Rectangle::fill_with([500.0, 150.0], color::BLACK.alpha(0.8))
+                .middle_of(ui_widgets.window)
+                .set(self.ids.drop_all_confirm_bg, ui_widgets);
+            Text::new(
+                &self
+                    .voxygen_i18n
+                    .get("hud.bag.drop_all_confirmation")
+                    .replace("{stacks}", &stacks.to_string())
+                    .replace("{item}", name),
+            )
+            .mid_top_with_margin_on(self.ids.drop_all_confirm_bg, 20.0)
+            .w(460.0)
+            .center_justify()
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(18))
+            .color(TEXT_COLOR)
+            .set(self.ids.drop_all_confirm_txt, ui_widgets);
+            if Button::image(self.imgs.button)
+                .w_h(200.0, 45.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .bottom_left_with_margins_on(self.ids.drop_all_confirm_bg, 15.0, 35.0)
+                .label(&self.voxygen_i18n.get("hud.bag.drop_all"))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_font_size(self.fonts.cyri.scale(18))
+                .label_color(TEXT_COLOR)
+                .label_y(conrod_core::position::Relative::Scalar(2.0))
+                .set(self.ids.drop_all_confirm_drop, ui_widgets)
+                .was_clicked()
+            {
+                events.push(Event::DropAllOfType(item_def_id.clone()));
+                self.show.drop_all_confirmation = None;
+            } else if Button::image(self.imgs.button)
+                .w_h(200.0, 45.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .bottom_right_with_margins_on(self.ids.drop_all_confirm_bg, 15.0, 35.0)
+                .label(&self.voxygen_i18n.get("common.cancel"))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_font_size(self.fonts.cyri.scale(18))
+                .label_color(TEXT_COLOR)
+                .label_y(conrod_core::position::Relative::Scalar(2.0))
+                .set(self.ids.drop_all_confirm_cancel, ui_widgets)
+                .was_clicked()
+            {
+                self.show.drop_all_confirmation = None;
+            }
+        }
+
+        // Summary of the session before leaving the game
+        if self.show.logout_summary {
+            let stats = info.session_stats;
+            let i18n = &self.voxygen_i18n;
+            let text = [
+                format!(
+                    "{}: {}",
+                    i18n.get("hud.session_stats.play_time"),
+                    format_play_time(stats.play_time)
+                ),
+                format!(
+                    "{}: {}",
+                    i18n.get("hud.session_stats.items_collected"),
+                    stats.items_collected
+                ),
+                format!(
+                    "{}: {}",
+                    i18n.get("hud.session_stats.blocks_placed"),
+                    stats.blocks_placed
+                ),
+            ]
+            .join("\n");
+            Rectangle::fill_with([500.0, 200.0], color::BLACK.alpha(0.8))
+                .middle_of(ui_widgets.window)
+                .set(self.ids.logout_summary_bg, ui_widgets);
+            Text::new(&format!("{}\n\n{}", i18n.get("hud.logout_summary"), text))
+                .mid_top_with_margin_on(self.ids.logout_summary_bg, 20.0)
+                .w(460.0)
+                .center_justify()
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(18))
+                .color(TEXT_COLOR)
+                .set(self.ids.logout_summary_txt, ui_widgets);
+            if Button::image(self.imgs.button)
+                .w_h(200.0, 45.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .mid_bottom_with_margin_on(self.ids.logout_summary_bg, 15.0)
+                .label(&i18n.get("common.okay"))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_font_size(self.fonts.cyri.scale(18))
+                .label_color(TEXT_COLOR)
+                .label_y(conrod_core::position::Relative::Scalar(2.0))
+                .set(self.ids.logout_summary_okay, ui_widgets)
+                .was_clicked()
+            {
+                self.show.logout_summary = false;
+                events.push(Event::CloseLogoutSummary);
+            }
+        }
+
         // Maintain slot manager
         for event in self.slot_manager.maintain(ui_widgets) {
             use comp::slot::Slot;
@@ -2331,6 +3293,50 @@ impl Hud {
         self.new_notifications.push_back(msg);
     }
 
+    /// Show a banner counting down the seconds until we are disconnected. Each
+    /// new notification restarts the countdown from the given time.
+    pub fn disconnect_countdown(&mut self, time: u64) {
+        self.disconnect_countdown = Some((time, Instant::now()));
+    }
+
+    pub fn clear_disconnect_countdown(&mut self) { self.disconnect_countdown = None; }
+
+    pub fn afk_logout_countdown(&mut self, remaining: Option<u64>) {
+        self.afk_logout_countdown = remaining;
+    }
+
+    /// Show the do not disturb badge with the number of held back
+    /// notifications, or hide it with `None`.
+    pub fn dnd_queued(&mut self, queued: Option<usize>) { self.dnd_queued = queued; }
+
+    pub fn hotbar(&self) -> &HotbarState { &self.hotbar }
+
+    /// Whether a panel covering much of the screen, such as the inventory or
+    /// the map, is open.
+    pub fn large_panel_open(&self) -> bool {
+        self.show.bag || self.show.crafting || self.show.map || self.show.social || self.show.spell
+    }
+
+    /// Show or hide the summary of the session shown before leaving the game.
+    pub fn logout_summary(&mut self, show: bool) {
+        self.show.logout_summary = show;
+        if show {
+            self.show.want_grab = false;
+        }
+    }
+
+    /// Show or hide the confirmation before leaving the group. `Some` holds
+    /// whether the player leads the group.
+    pub fn confirm_leave_group(&mut self, is_leader: Option<bool>) {
+        self.show.leave_group_confirmation = is_leader;
+    }
+
+    /// Ask the player whether to log out before the game is closed.
+    pub fn confirm_close(&mut self) {
+        self.show.close_confirmation = true;
+        self.show.want_grab = false;
+    }
+
     pub fn scale_change(&mut self, scale_change: ScaleChange) -> ScaleMode {
         let scale_mode = match scale_change {
             ScaleChange::Adjust(scale) => ScaleMode::Absolute(scale),
@@ -2461,10 +3467,38 @@ impl Hud {
                         !global_state.settings.gameplay.toggle_debug;
                     true
                 },
+                GameInput::ToggleSessionStats if state => {
+                    self.show.session_stats = !self.show.session_stats;
+                    true
+                },
+                GameInput::CycleCoordinateDisplay if state => {
+                    let mode = &mut global_state.settings.gameplay.coordinate_display_mode;
+                    *mode = match mode {
+                        CoordinateDisplayMode::Absolute => CoordinateDisplayMode::Chunk,
+                        CoordinateDisplayMode::Chunk => CoordinateDisplayMode::Region,
+                        CoordinateDisplayMode::Region => CoordinateDisplayMode::Absolute,
+                    };
+                    true
+                },
                 GameInput::ToggleIngameUi if state => {
                     self.show.ingame = !self.show.ingame;
                     true
                 },
+                // Only items in the inventory are picked, so equipped gear is never dropped
+                GameInput::DropAllOfType if state => {
+                    if let Some(slots::SlotKind::Inventory(i)) = self.slot_manager.selected() {
+                        self.drop_all_slot = Some(i.0);
+                        self.slot_manager.idle();
+                    }
+                    true
+                },
+                // Goes through the same event as the settings window so it's saved, only
+                // the master switch is flipped and the batching settings are left alone
+                GameInput::ToggleSct if state => {
+                    self.events
+                        .push(Event::Sct(!global_state.settings.gameplay.sct));
+                    true
+                },
                 // Skillbar
                 GameInput::Slot1 => {
                     handle_slot(
@@ -2649,15 +3683,60 @@ impl Hud {
     pub fn render(&self, renderer: &mut Renderer, globals: &Consts<Globals>) {
         span!(_guard, "render", "Hud::render");
         // Don't show anything if the UI is toggled off.
-        if self.show.ui {
+        if self.show.ui && !self.hidden_for_screenshot {
             self.ui.render(renderer, Some(globals));
         }
     }
 
     pub fn free_look(&mut self, free_look: bool) { self.show.free_look = free_look; }
 
+    pub fn hide_for_screenshot(&mut self, hide: bool) { self.hidden_for_screenshot = hide; }
+
     pub fn auto_walk(&mut self, auto_walk: bool) { self.show.auto_walk = auto_walk; }
+
+    pub fn walking(&mut self, walking: bool) { self.show.walking = walking; }
+
+    pub fn multiple_interactions(&mut self, multiple_interactions: bool) {
+        self.show.multiple_interactions = multiple_interactions;
+    }
+
+    pub fn replay_state(&mut self, replay_state: ReplayState) {
+        self.show.replay_state = replay_state;
+    }
 }
+/// Format the player's position for the debug overlay. Chunk and region
+/// positions use floor division, so negative coordinates stay in the right
+/// chunk or region.
+fn format_coordinates(pos: Vec3<f32>, mode: CoordinateDisplayMode) -> String {
+    let block = pos.map(|e| e.floor() as i32);
+    let relative = |size: Vec2<u32>| {
+        let size = size.map(|e| e as i32);
+        (
+            block.xy().map2(size, i32::div_euclid),
+            block.xy().map2(size, i32::rem_euclid),
+        )
+    };
+    match mode {
+        CoordinateDisplayMode::Absolute => {
+            format!("Coordinates: ({:.0}, {:.0}, {:.0})", pos.x, pos.y, pos.z)
+        },
+        CoordinateDisplayMode::Chunk => {
+            let (chunk, local) = relative(TerrainChunkSize::RECT_SIZE);
+            format!(
+                "Chunk: ({}, {}) Block: ({}, {}, {})",
+                chunk.x, chunk.y, local.x, local.y, block.z,
+            )
+        },
+        CoordinateDisplayMode::Region => {
+            let (region, local) = relative(Vec2::broadcast(REGION_SIZE));
+            format!(
+                "Region: ({}, {}) Block: ({}, {}, {})",
+                region.x, region.y, local.x, local.y, block.z,
+            )
+        },
+    }
+}
+
 // Get item qualities of equipped items and assign a tooltip title/frame color
 pub fn get_quality_col<I: ItemDesc>(item: &I) -> Color {
     match item.quality() {
@@ -2671,3 +3750,9 @@ pub fn get_quality_col<I: ItemDesc>(item: &I) -> Color {
         Quality::Debug => QUALITY_DEBUG,
     }
 }
+
+/// Format a play time in seconds as hours, minutes and seconds.
+fn format_play_time(secs: f64) -> String {
+    let secs = secs as u64;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
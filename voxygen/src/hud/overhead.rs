@@ -65,6 +65,7 @@ pub struct Overhead<'a> {
     bubble: Option<&'a SpeechBubble>,
     own_level: u32,
     in_group: bool,
+    name_scale: f32,
     settings: &'a GameplaySettings,
     pulse: f32,
     voxygen_i18n: &'a std::sync::Arc<VoxygenLocalization>,
@@ -82,6 +83,7 @@ impl<'a> Overhead<'a> {
         bubble: Option<&'a SpeechBubble>,
         own_level: u32,
         in_group: bool,
+        name_scale: f32,
         settings: &'a GameplaySettings,
         pulse: f32,
         voxygen_i18n: &'a std::sync::Arc<VoxygenLocalization>,
@@ -93,6 +95,7 @@ impl<'a> Overhead<'a> {
             bubble,
             own_level,
             in_group,
+            name_scale,
             settings,
             pulse,
             voxygen_i18n,
@@ -173,6 +176,7 @@ impl<'a> Widget for Overhead<'a> {
                 MANA_BAR_Y + 32.0
             };
             let font_size = if hp_percentage.abs() > 99.9 { 24 } else { 20 };
+            let font_size = (font_size as f32 * self.name_scale).round() as u32;
             // Show K for numbers above 10^3 and truncate them
             // Show M for numbers above 10^6 and truncate them
             let health_cur_txt = match health_current as u32 {
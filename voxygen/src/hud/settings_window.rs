@@ -43,6 +43,8 @@ widget_ids! {
         controls_texts[],
         controls_buttons[],
         reset_controls_button,
+        export_controls_button,
+        import_controls_button,
         controls_alignment_rectangle,
         button_help,
         button_help2,
@@ -206,6 +208,15 @@ widget_ids! {
         auto_walk_behavior_list,
         stop_auto_walk_on_input_button,
         stop_auto_walk_on_input_label,
+        freefly_accel_text,
+        freefly_accel_slider,
+        freefly_accel_value,
+        freefly_damping_text,
+        freefly_damping_slider,
+        freefly_damping_value,
+        freefly_max_speed_text,
+        freefly_max_speed_slider,
+        freefly_max_speed_value,
     }
 }
 
@@ -226,6 +237,8 @@ pub struct SettingsWindow<'a> {
     fonts: &'a ConrodVoxygenFonts,
     localized_strings: &'a std::sync::Arc<VoxygenLocalization>,
     fps: f32,
+    fov: u16,
+    gamma: f32,
     #[conrod(common_builder)]
     common: widget::CommonBuilder,
 }
@@ -238,6 +251,8 @@ impl<'a> SettingsWindow<'a> {
         fonts: &'a ConrodVoxygenFonts,
         localized_strings: &'a std::sync::Arc<VoxygenLocalization>,
         fps: f32,
+        fov: u16,
+        gamma: f32,
     ) -> Self {
         Self {
             global_state,
@@ -246,6 +261,8 @@ impl<'a> SettingsWindow<'a> {
             fonts,
             localized_strings,
             fps,
+            fov,
+            gamma,
             common: widget::CommonBuilder::default(),
         }
     }
@@ -297,9 +314,14 @@ pub enum Event {
     ChangeLanguage(Box<LanguageMetadata>),
     ChangeBinding(GameInput),
     ResetBindings,
+    ExportBindings,
+    ImportBindings,
     ChangeFreeLookBehavior(PressBehavior),
     ChangeAutoWalkBehavior(PressBehavior),
     ChangeStopAutoWalkOnInput(bool),
+    AdjustFreeflyAccel(f32),
+    AdjustFreeflyDamping(f32),
+    AdjustFreeflyMaxSpeed(f32),
 }
 
 pub enum ScaleChange {
@@ -1529,6 +1551,104 @@ impl<'a> Widget for SettingsWindow<'a> {
             .graphics_for(state.ids.stop_auto_walk_on_input_button)
             .color(TEXT_COLOR)
             .set(state.ids.stop_auto_walk_on_input_label, ui);
+
+            let freefly = self.global_state.settings.gameplay.freefly;
+
+            // Freefly Acceleration
+            Text::new(&self.localized_strings.get("hud.settings.freefly_accel"))
+                .down_from(state.ids.free_look_behavior_list, 10.0)
+                .font_size(self.fonts.cyri.scale(14))
+                .font_id(self.fonts.cyri.conrod_id)
+                .color(TEXT_COLOR)
+                .set(state.ids.freefly_accel_text, ui);
+
+            if let Some(new_val) = ImageSlider::continuous(
+                freefly.accel,
+                10.0,
+                1000.0,
+                self.imgs.slider_indicator,
+                self.imgs.slider,
+            )
+            .w_h(550.0, 22.0)
+            .down_from(state.ids.freefly_accel_text, 10.0)
+            .track_breadth(30.0)
+            .slider_length(10.0)
+            .pad_track((5.0, 5.0))
+            .set(state.ids.freefly_accel_slider, ui)
+            {
+                events.push(Event::AdjustFreeflyAccel(new_val));
+            }
+
+            Text::new(&format!("{:.0}", freefly.accel))
+                .right_from(state.ids.freefly_accel_slider, 8.0)
+                .font_size(self.fonts.cyri.scale(14))
+                .font_id(self.fonts.cyri.conrod_id)
+                .color(TEXT_COLOR)
+                .set(state.ids.freefly_accel_value, ui);
+
+            // Freefly Damping
+            Text::new(&self.localized_strings.get("hud.settings.freefly_damping"))
+                .down_from(state.ids.freefly_accel_slider, 10.0)
+                .font_size(self.fonts.cyri.scale(14))
+                .font_id(self.fonts.cyri.conrod_id)
+                .color(TEXT_COLOR)
+                .set(state.ids.freefly_damping_text, ui);
+
+            if let Some(new_val) = ImageSlider::continuous(
+                freefly.damping,
+                0.0,
+                500.0,
+                self.imgs.slider_indicator,
+                self.imgs.slider,
+            )
+            .w_h(550.0, 22.0)
+            .down_from(state.ids.freefly_damping_text, 10.0)
+            .track_breadth(30.0)
+            .slider_length(10.0)
+            .pad_track((5.0, 5.0))
+            .set(state.ids.freefly_damping_slider, ui)
+            {
+                events.push(Event::AdjustFreeflyDamping(new_val));
+            }
+
+            Text::new(&format!("{:.0}", freefly.damping))
+                .right_from(state.ids.freefly_damping_slider, 8.0)
+                .font_size(self.fonts.cyri.scale(14))
+                .font_id(self.fonts.cyri.conrod_id)
+                .color(TEXT_COLOR)
+                .set(state.ids.freefly_damping_value, ui);
+
+            // Freefly Max Speed
+            Text::new(&self.localized_strings.get("hud.settings.freefly_max_speed"))
+                .down_from(state.ids.freefly_damping_slider, 10.0)
+                .font_size(self.fonts.cyri.scale(14))
+                .font_id(self.fonts.cyri.conrod_id)
+                .color(TEXT_COLOR)
+                .set(state.ids.freefly_max_speed_text, ui);
+
+            if let Some(new_val) = ImageSlider::continuous(
+                freefly.max_speed,
+                1.0,
+                500.0,
+                self.imgs.slider_indicator,
+                self.imgs.slider,
+            )
+            .w_h(550.0, 22.0)
+            .down_from(state.ids.freefly_max_speed_text, 10.0)
+            .track_breadth(30.0)
+            .slider_length(10.0)
+            .pad_track((5.0, 5.0))
+            .set(state.ids.freefly_max_speed_slider, ui)
+            {
+                events.push(Event::AdjustFreeflyMaxSpeed(new_val));
+            }
+
+            Text::new(&format!("{:.0}", freefly.max_speed))
+                .right_from(state.ids.freefly_max_speed_slider, 8.0)
+                .font_size(self.fonts.cyri.scale(14))
+                .font_id(self.fonts.cyri.conrod_id)
+                .color(TEXT_COLOR)
+                .set(state.ids.freefly_max_speed_value, ui);
         }
 
         // 3) Controls Tab --------------------------------
@@ -1653,7 +1773,38 @@ impl<'a> Widget for SettingsWindow<'a> {
                 if button_widget.was_clicked() {
                     events.push(Event::ResetBindings);
                 }
-                previous_element_id = Some(state.ids.reset_controls_button)
+
+                if Button::new()
+                    .label(self.localized_strings.get("hud.settings.export_keybinds"))
+                    .label_color(TEXT_COLOR)
+                    .label_font_id(self.fonts.cyri.conrod_id)
+                    .label_font_size(self.fonts.cyri.scale(18))
+                    .down_from(state.ids.reset_controls_button, 10.0)
+                    .w(200.0)
+                    .rgba(0.0, 0.0, 0.0, 0.0)
+                    .border_rgba(0.0, 0.0, 0.0, 255.0)
+                    .label_y(Relative::Scalar(3.0))
+                    .set(state.ids.export_controls_button, ui)
+                    .was_clicked()
+                {
+                    events.push(Event::ExportBindings);
+                }
+                if Button::new()
+                    .label(self.localized_strings.get("hud.settings.import_keybinds"))
+                    .label_color(TEXT_COLOR)
+                    .label_font_id(self.fonts.cyri.conrod_id)
+                    .label_font_size(self.fonts.cyri.scale(18))
+                    .down_from(state.ids.export_controls_button, 10.0)
+                    .w(200.0)
+                    .rgba(0.0, 0.0, 0.0, 0.0)
+                    .border_rgba(0.0, 0.0, 0.0, 255.0)
+                    .label_y(Relative::Scalar(3.0))
+                    .set(state.ids.import_controls_button, ui)
+                    .was_clicked()
+                {
+                    events.push(Event::ImportBindings);
+                }
+                previous_element_id = Some(state.ids.import_controls_button);
             }
             // Add an empty text widget to simulate some bottom margin, because conrod sucks
             if let Some(prev_id) = previous_element_id {
@@ -1782,7 +1933,7 @@ impl<'a> Widget for SettingsWindow<'a> {
                 .set(state.ids.fov_text, ui);
 
             if let Some(new_val) = ImageSlider::discrete(
-                self.global_state.settings.graphics.fov,
+                self.fov,
                 30,
                 120,
                 self.imgs.slider_indicator,
@@ -1798,7 +1949,7 @@ impl<'a> Widget for SettingsWindow<'a> {
                 events.push(Event::AdjustFOV(new_val));
             }
 
-            Text::new(&format!("{}", self.global_state.settings.graphics.fov))
+            Text::new(&format!("{}", self.fov))
                 .right_from(state.ids.fov_slider, 8.0)
                 .font_size(self.fonts.cyri.scale(14))
                 .font_id(self.fonts.cyri.conrod_id)
@@ -1852,7 +2003,7 @@ impl<'a> Widget for SettingsWindow<'a> {
                 .set(state.ids.gamma_text, ui);
 
             if let Some(new_val) = ImageSlider::discrete(
-                (self.global_state.settings.graphics.gamma.log2() * 8.0).round() as i32,
+                (self.gamma.log2() * 8.0).round() as i32,
                 8,
                 -8,
                 self.imgs.slider_indicator,
@@ -1868,7 +2019,7 @@ impl<'a> Widget for SettingsWindow<'a> {
                 events.push(Event::AdjustGamma(2.0f32.powf(new_val as f32 / 8.0)));
             }
 
-            Text::new(&format!("{:.2}", self.global_state.settings.graphics.gamma))
+            Text::new(&format!("{:.2}", self.gamma))
                 .right_from(state.ids.gamma_slider, 8.0)
                 .font_size(self.fonts.cyri.scale(14))
                 .font_id(self.fonts.cyri.conrod_id)
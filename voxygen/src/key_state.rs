@@ -17,6 +17,9 @@ pub struct KeyState {
     pub swap_loadout: bool,
     pub respawn: bool,
     pub collect: bool,
+    pub build_modifier: bool,
+    pub freefly_boost: bool,
+    pub freefly_precision: bool,
     pub analog_matrix: Vec2<f32>,
 }
 
@@ -39,6 +42,9 @@ impl Default for KeyState {
             swap_loadout: false,
             respawn: false,
             collect: false,
+            build_modifier: false,
+            freefly_boost: false,
+            freefly_precision: false,
             analog_matrix: Vec2::zero(),
         }
     }
@@ -7,7 +7,11 @@
 
 #[macro_use]
 pub mod ui;
+pub mod action_log;
 pub mod audio;
+pub mod benchmark;
+pub mod camera_path;
+pub mod chat_history;
 pub mod controller;
 mod ecs;
 pub mod error;
@@ -19,12 +23,15 @@ pub mod menu;
 pub mod mesh;
 pub mod profile;
 pub mod render;
+pub mod replay;
 pub mod run;
 pub mod scene;
 pub mod session;
 pub mod settings;
 #[cfg(feature = "singleplayer")]
 pub mod singleplayer;
+pub mod spectate;
+pub mod tts;
 pub mod window;
 
 // Reexports
@@ -34,6 +41,7 @@ pub use crate::error::Error;
 use crate::singleplayer::Singleplayer;
 use crate::{
     audio::AudioFrontend,
+    chat_history::ChatHistory,
     profile::Profile,
     render::Renderer,
     settings::Settings,
@@ -49,6 +57,9 @@ pub struct GlobalState {
     pub audio: AudioFrontend,
     pub info_message: Option<String>,
     pub clock: Clock,
+    /// Chat messages of the current server and character, kept across
+    /// reconnects
+    pub chat_history: ChatHistory,
     #[cfg(feature = "singleplayer")]
     pub singleplayer: Option<Singleplayer>,
     // TODO: redo this so that the watcher doesn't have to exist for reloading to occur
@@ -61,6 +72,7 @@ impl GlobalState {
     pub fn on_play_state_changed(&mut self) {
         self.window.grab_cursor(false);
         self.window.needs_refresh_resize();
+        self.window.set_delegate_screenshots(false);
     }
 
     pub fn maintain(&mut self, dt: f32) { self.audio.maintain(dt); }
@@ -75,6 +87,12 @@ impl GlobalState {
     #[cfg(not(feature = "singleplayer"))]
     pub fn paused(&self) -> bool { false }
 
+    #[cfg(feature = "singleplayer")]
+    pub fn is_singleplayer(&self) -> bool { self.singleplayer.is_some() }
+
+    #[cfg(not(feature = "singleplayer"))]
+    pub fn is_singleplayer(&self) -> bool { false }
+
     #[cfg(feature = "singleplayer")]
     pub fn unpause(&self) { self.singleplayer.as_ref().map(|s| s.pause(false)); }
 
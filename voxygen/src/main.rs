@@ -5,6 +5,7 @@
 
 use veloren_voxygen::{
     audio::{self, AudioFrontend},
+    chat_history::ChatHistory,
     i18n::{self, i18n_asset_key, VoxygenLocalization},
     logging,
     profile::Profile,
@@ -28,8 +29,10 @@ fn main() {
     // whether we create a log file or not.
     let mut settings = Settings::load();
     // Save settings to add new fields or create the file if it is not already there
-    if let Err(err) = settings.save_to_file() {
-        panic!("Failed to save settings: {:?}", err);
+    if !settings.disable_settings_autosave {
+        if let Err(err) = settings.save_to_file() {
+            panic!("Failed to save settings: {:?}", err);
+        }
     }
 
     // Init logging and hold the guards.
@@ -181,6 +184,7 @@ fn main() {
         settings,
         clock: Clock::start(),
         info_message: None,
+        chat_history: ChatHistory::default(),
         #[cfg(feature = "singleplayer")]
         singleplayer: None,
         localization_watcher,
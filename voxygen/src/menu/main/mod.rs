@@ -10,7 +10,7 @@ use crate::{
 };
 use client_init::{ClientInit, Error as InitError, Msg as InitMsg};
 use common::{assets::Asset, comp, span};
-use tracing::{error, warn};
+use tracing::error;
 use ui::{Event as MainMenuEvent, MainMenuUi};
 
 pub struct MainMenuState {
@@ -291,9 +291,7 @@ fn attempt_login(
     if !net_settings.servers.contains(&server_address) {
         net_settings.servers.push(server_address.clone());
     }
-    if let Err(e) = global_state.settings.save_to_file() {
-        warn!(?e, "Failed to save settings");
-    }
+    global_state.settings.save_to_file_warn();
 
     if comp::Player::alias_is_valid(&username) {
         // Don't try to connect if there is already a connection in progress.
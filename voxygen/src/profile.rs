@@ -1,5 +1,5 @@
 use crate::{hud, settings};
-use common::character::CharacterId;
+use common::{character::CharacterId, cmd::ChatCommand};
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
 use std::{fs, io::Write, path::PathBuf};
@@ -11,6 +11,16 @@ use tracing::warn;
 pub struct CharacterProfile {
     /// Array representing a character's hotbar.
     pub hotbar_slots: [Option<hud::HotbarSlotContents>; 10],
+    /// Overrides the global FOV graphics setting for this character.
+    pub fov: Option<u16>,
+    /// Overrides the global gamma graphics setting for this character.
+    pub gamma: Option<f32>,
+    /// Totals of the session stats of every session played with this
+    /// character, only kept when enabled in the gameplay settings.
+    pub lifetime_stats: SessionStats,
+    /// Chat channel the character last switched to, switched to again when
+    /// entering the game.
+    pub chat_channel: Option<ChatChannel>,
 }
 
 impl Default for CharacterProfile {
@@ -28,10 +38,74 @@ impl Default for CharacterProfile {
                 None,
                 None,
             ],
+            fov: None,
+            gamma: None,
+            lifetime_stats: SessionStats::default(),
+            chat_channel: None,
         }
     }
 }
 
+/// Chat channel messages go to when sent without a command. Tells aren't
+/// remembered, as they are only meant for the player they were sent to.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ChatChannel {
+    Say,
+    Group,
+    Faction,
+    Region,
+    World,
+}
+
+impl ChatChannel {
+    /// The channel chat `command` switches to, if it switches channels.
+    pub fn switched_to_by(command: ChatCommand) -> Option<Self> {
+        match command {
+            ChatCommand::Say => Some(Self::Say),
+            ChatCommand::Group => Some(Self::Group),
+            ChatCommand::Faction => Some(Self::Faction),
+            ChatCommand::Region => Some(Self::Region),
+            ChatCommand::World => Some(Self::World),
+            _ => None,
+        }
+    }
+
+    /// The chat command that switches to this channel.
+    pub fn command(self) -> ChatCommand {
+        match self {
+            Self::Say => ChatCommand::Say,
+            Self::Group => ChatCommand::Group,
+            Self::Faction => ChatCommand::Faction,
+            Self::Region => ChatCommand::Region,
+            Self::World => ChatCommand::World,
+        }
+    }
+}
+
+/// Counters of what the player did during a session.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionStats {
+    pub blocks_placed: u64,
+    pub blocks_removed: u64,
+    pub items_collected: u64,
+    /// Distance traveled in blocks.
+    pub distance_traveled: f64,
+    /// Time played in seconds.
+    pub play_time: f64,
+}
+
+impl SessionStats {
+    /// Add the counters of `other` to these ones.
+    pub fn merge(&mut self, other: &SessionStats) {
+        self.blocks_placed += other.blocks_placed;
+        self.blocks_removed += other.blocks_removed;
+        self.items_collected += other.items_collected;
+        self.distance_traveled += other.distance_traveled;
+        self.play_time += other.play_time;
+    }
+}
+
 /// Represents a server in the profile.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
@@ -154,6 +228,45 @@ impl Profile {
             .hotbar_slots = slots;
     }
 
+    /// Get the CharacterProfile for the requested character_id, if one exists.
+    ///
+    /// # Arguments
+    ///
+    /// * server - current server the character is on.
+    /// * character_id - id of the character.
+    pub fn get_character_profile(
+        &self,
+        server: &str,
+        character_id: CharacterId,
+    ) -> Option<&CharacterProfile> {
+        self.servers
+            .get(server)
+            .and_then(|server_profile| server_profile.characters.get(&character_id))
+    }
+
+    /// Get the CharacterProfile for the requested character_id for
+    /// modification.
+    ///
+    /// If the server or character does not exist then the appropriate fields
+    /// will be initialised.
+    ///
+    /// # Arguments
+    ///
+    /// * server - current server the character is on.
+    /// * character_id - id of the character.
+    pub fn get_character_profile_mut(
+        &mut self,
+        server: &str,
+        character_id: CharacterId,
+    ) -> &mut CharacterProfile {
+        self.servers
+            .entry(server.to_string())
+            .or_insert(ServerProfile::default())
+            .characters
+            .entry(character_id)
+            .or_insert(CharacterProfile::default())
+    }
+
     /// Save the current profile to disk.
     fn save_to_file(&self) -> std::io::Result<()> {
         let path = Profile::get_path();
@@ -221,4 +334,31 @@ mod tests {
         ];
         profile.set_hotbar_slots("TestServer", 12345, slots);
     }
+
+    #[test]
+    fn test_character_profile_overrides() {
+        let mut profile = Profile::default();
+        assert!(profile.get_character_profile("TestServer", 12345).is_none());
+
+        profile.get_character_profile_mut("TestServer", 12345).fov = Some(90);
+        let character = profile.get_character_profile("TestServer", 12345).unwrap();
+        assert_eq!(character.fov, Some(90));
+        assert_eq!(character.gamma, None);
+    }
+
+    #[test]
+    fn test_lifetime_stats_merge() {
+        let mut profile = Profile::default();
+        let session = SessionStats {
+            blocks_placed: 3,
+            items_collected: 2,
+            ..SessionStats::default()
+        };
+        let character = profile.get_character_profile_mut("TestServer", 12345);
+        character.lifetime_stats.merge(&session);
+        character.lifetime_stats.merge(&session);
+        assert_eq!(character.lifetime_stats.blocks_placed, 6);
+        assert_eq!(character.lifetime_stats.blocks_removed, 0);
+        assert_eq!(character.lifetime_stats.items_collected, 4);
+    }
 }
@@ -113,6 +113,12 @@ pub enum ParticleMode {
     EnergyNature = 14,
     FlameThrower = 15,
     FireShockwave = 16,
+    BlockGhost = 17,
+    ChunkBorder = 18,
+    RangeRing = 19,
+    DebugMarker = 20,
+    BuildGrid = 21,
+    LootBeam = 22,
 }
 
 impl ParticleMode {
@@ -154,6 +160,89 @@ impl Instance {
             inst_dir: (inst_pos2 - inst_pos).into_array(),
         }
     }
+
+    /// A translucent block used to preview block placement. The block color is
+    /// passed through `inst_dir`.
+    pub fn new_block_ghost(inst_time: f64, inst_pos: Vec3<f32>, col: Rgb<f32>) -> Self {
+        Self {
+            inst_time: inst_time as f32,
+            inst_lifespan: 0.0,
+            inst_entropy: 0.0,
+            inst_mode: ParticleMode::BlockGhost as i32,
+            inst_pos: inst_pos.into_array(),
+            inst_dir: col.into_array(),
+        }
+    }
+
+    /// An axis-aligned line centered on `inst_pos`, used to draw chunk
+    /// borders. The extent of the line is passed through `inst_dir`.
+    pub fn new_chunk_border(inst_time: f64, inst_pos: Vec3<f32>, extent: Vec3<f32>) -> Self {
+        Self {
+            inst_time: inst_time as f32,
+            inst_lifespan: 0.0,
+            inst_entropy: 0.0,
+            inst_mode: ParticleMode::ChunkBorder as i32,
+            inst_pos: inst_pos.into_array(),
+            inst_dir: extent.into_array(),
+        }
+    }
+
+    /// A segment of the ring on the ground that shows the interact range. The
+    /// opacity of the ring is passed through `inst_dir`.
+    pub fn new_range_ring(inst_time: f64, inst_pos: Vec3<f32>, opacity: f32) -> Self {
+        Self {
+            inst_time: inst_time as f32,
+            inst_lifespan: 0.0,
+            inst_entropy: 0.0,
+            inst_mode: ParticleMode::RangeRing as i32,
+            inst_pos: inst_pos.into_array(),
+            inst_dir: [opacity, 0.0, 0.0],
+        }
+    }
+
+    /// A cube of `size` used to draw debug information. The color is passed
+    /// through `inst_dir` and the size through `inst_entropy`.
+    pub fn new_debug_marker(inst_time: f64, inst_pos: Vec3<f32>, col: Rgb<f32>, size: f32) -> Self {
+        Self {
+            inst_time: inst_time as f32,
+            inst_lifespan: 0.0,
+            inst_entropy: size,
+            inst_mode: ParticleMode::DebugMarker as i32,
+            inst_pos: inst_pos.into_array(),
+            inst_dir: col.into_array(),
+        }
+    }
+
+    /// A line of the build grid, stretched along `extent`. The opacity is
+    /// passed through `inst_entropy`.
+    pub fn new_build_grid(
+        inst_time: f64,
+        inst_pos: Vec3<f32>,
+        extent: Vec3<f32>,
+        opacity: f32,
+    ) -> Self {
+        Self {
+            inst_time: inst_time as f32,
+            inst_lifespan: 0.0,
+            inst_entropy: opacity,
+            inst_mode: ParticleMode::BuildGrid as i32,
+            inst_pos: inst_pos.into_array(),
+            inst_dir: extent.into_array(),
+        }
+    }
+
+    /// A beam of light rising over a dropped item. The color is passed through
+    /// `inst_dir` and the opacity through `inst_entropy`.
+    pub fn new_loot_beam(inst_time: f64, inst_pos: Vec3<f32>, col: Rgb<f32>, opacity: f32) -> Self {
+        Self {
+            inst_time: inst_time as f32,
+            inst_lifespan: 0.0,
+            inst_entropy: opacity,
+            inst_mode: ParticleMode::LootBeam as i32,
+            inst_pos: inst_pos.into_array(),
+            inst_dir: col.into_array(),
+        }
+    }
 }
 
 impl Default for Instance {
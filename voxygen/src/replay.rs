@@ -0,0 +1,138 @@
+//! Recording and playback of player inputs, used to reproduce bugs.
+//!
+//! A recording is a stream of bincode-encoded [`ReplayFrame`]s, one per
+//! session tick, containing the controller inputs sent to the client along
+//! with the camera state at that time.
+
+use crate::{scene::camera::Camera, settings::voxygen_data_dir};
+use common::comp::ControllerInputs;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+};
+use tracing::{info, warn};
+use vek::*;
+
+/// The state of a single tick of a recording.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub inputs: ControllerInputs,
+    pub cam_ori: Vec3<f32>,
+    pub cam_dist: f32,
+}
+
+impl ReplayFrame {
+    pub fn new(inputs: &ControllerInputs, camera: &Camera) -> Self {
+        Self {
+            inputs: inputs.clone(),
+            cam_ori: camera.get_orientation(),
+            cam_dist: camera.get_distance(),
+        }
+    }
+
+    /// Apply the recorded camera state to `camera`.
+    pub fn apply_camera(&self, camera: &mut Camera) {
+        camera.set_orientation(self.cam_ori);
+        camera.set_ori_instant(self.cam_ori);
+        camera.set_distance(self.cam_dist);
+    }
+}
+
+/// Whether inputs are currently being recorded or played back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayState {
+    Idle,
+    Recording,
+    Playback,
+}
+
+pub enum Replay {
+    Idle,
+    Recording(BufWriter<fs::File>),
+    Playback(BufReader<fs::File>),
+}
+
+impl Default for Replay {
+    fn default() -> Self { Self::Idle }
+}
+
+impl Replay {
+    pub fn state(&self) -> ReplayState {
+        match self {
+            Replay::Idle => ReplayState::Idle,
+            Replay::Recording(_) => ReplayState::Recording,
+            Replay::Playback(_) => ReplayState::Playback,
+        }
+    }
+
+    /// Start recording to the replay file, replacing any previous recording.
+    pub fn start_recording(&mut self) {
+        let path = Self::get_replay_path();
+        let file = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::File::create(&path));
+        *self = match file {
+            Ok(file) => {
+                info!(?path, "Started recording inputs");
+                Replay::Recording(BufWriter::new(file))
+            },
+            Err(e) => {
+                warn!(?e, ?path, "Failed to create input recording file");
+                Replay::Idle
+            },
+        };
+    }
+
+    /// Start playing back the replay file.
+    pub fn start_playback(&mut self) {
+        let path = Self::get_replay_path();
+        *self = match fs::File::open(&path) {
+            Ok(file) => {
+                info!(?path, "Started input playback");
+                Replay::Playback(BufReader::new(file))
+            },
+            Err(e) => {
+                warn!(?e, ?path, "Failed to open input recording file");
+                Replay::Idle
+            },
+        };
+    }
+
+    /// Stop recording or playback. Dropping the writer flushes the recording.
+    pub fn stop(&mut self) { *self = Replay::Idle; }
+
+    /// Append a frame to the recording, if one is in progress.
+    pub fn record(&mut self, frame: &ReplayFrame) {
+        if let Replay::Recording(writer) = self {
+            if let Err(e) = bincode::serialize_into(writer, frame) {
+                warn!(?e, "Failed to write input recording, stopping");
+                self.stop();
+            }
+        }
+    }
+
+    /// Read the next frame of the playback, if one is in progress. Playback
+    /// stops once the end of the recording is reached.
+    pub fn next_frame(&mut self) -> Option<ReplayFrame> {
+        if let Replay::Playback(reader) = self {
+            match bincode::deserialize_from(reader) {
+                Ok(frame) => return Some(frame),
+                Err(_) => {
+                    info!("Reached end of input recording");
+                    self.stop();
+                },
+            }
+        }
+        None
+    }
+
+    pub fn get_replay_path() -> PathBuf {
+        let mut path = voxygen_data_dir();
+        path.push("replays");
+        path.push("input_recording.bin");
+        path
+    }
+}
@@ -10,6 +10,8 @@ const FIRST_PERSON_INTERP_TIME: f32 = 0.1;
 const THIRD_PERSON_INTERP_TIME: f32 = 0.1;
 const FREEFLY_INTERP_TIME: f32 = 0.0;
 const LERP_ORI_RATE: f32 = 15.0;
+/// How quickly roll is eased out with the horizon lock
+const HORIZON_LOCK_RATE: f32 = 5.0;
 pub const MIN_ZOOM: f32 = 0.1;
 
 // Possible TODO: Add more modes
@@ -42,6 +44,13 @@ pub struct Camera {
     fov: f32,
     aspect: f32,
     mode: CameraMode,
+    /// Seconds for the freefly camera rotation to catch up with input, 0
+    /// disables it
+    freefly_rotation_smoothing: f32,
+    /// Keep the roll level, leaving pitch and yaw alone
+    horizon_lock: bool,
+    /// Pitch added to the view on top of the orientation, without changing it
+    pitch_offset: f32,
 
     last_time: Option<f64>,
 
@@ -62,6 +71,9 @@ impl Camera {
             fov: 1.1,
             aspect,
             mode,
+            freefly_rotation_smoothing: 0.0,
+            horizon_lock: false,
+            pitch_offset: 0.0,
 
             last_time: None,
 
@@ -108,7 +120,7 @@ impl Camera {
         self.dependents.view_mat = Mat4::<f32>::identity()
             * Mat4::translation_3d(-Vec3::unit_z() * dist)
             * Mat4::rotation_z(self.ori.z)
-            * Mat4::rotation_x(self.ori.y)
+            * Mat4::rotation_x(self.ori.y + self.pitch_offset)
             * Mat4::rotation_y(self.ori.x)
             * Mat4::rotation_3d(PI / 2.0, -Vec4::unit_x())
             * Mat4::translation_3d(-self.focus.map(|e| e.fract()));
@@ -145,6 +157,11 @@ impl Camera {
         self.tgt_ori.z = (self.tgt_ori.z + delta.z).rem_euclid(2.0 * PI);
     }
 
+    /// Set a pitch to add to the view, e.g. for recoil. Unlike rotating the
+    /// camera this is transient and doesn't interfere with the player's own
+    /// input.
+    pub fn set_pitch_offset(&mut self, pitch_offset: f32) { self.pitch_offset = pitch_offset; }
+
     /// Set the orientation of the camera about its focus.
     pub fn set_orientation(&mut self, ori: Vec3<f32>) {
         // Wrap camera yaw
@@ -155,6 +172,13 @@ impl Camera {
         self.tgt_ori.z = ori.z.rem_euclid(2.0 * PI);
     }
 
+    /// Turn the camera about its focus to look along `dir`, keeping its roll.
+    pub fn look_towards(&mut self, dir: Vec3<f32>) {
+        if let Some(dir) = dir.try_normalized() {
+            self.set_orientation(Vec3::new(dir.x.atan2(dir.y), -dir.z.asin(), self.tgt_ori.z));
+        }
+    }
+
     /// Set the orientation of the camera about its focus without lerping.
     pub fn set_ori_instant(&mut self, ori: Vec3<f32>) {
         // Wrap camera yaw
@@ -239,15 +263,43 @@ impl Camera {
             Lerp::lerp(a, b + *offs, rate)
         };
 
-        if smoothing_enabled {
+        // Freefly has its own, usually much slower, smoothing for cinematic pans
+        let ori_rate = match self.mode {
+            CameraMode::Freefly if self.freefly_rotation_smoothing > 0.0 => {
+                Some(dt / self.freefly_rotation_smoothing)
+            },
+            _ if smoothing_enabled => Some(LERP_ORI_RATE * dt),
+            _ => None,
+        };
+
+        if self.horizon_lock {
+            self.tgt_ori.z = 0.0;
+        }
+        let roll = self.ori.z;
+
+        if let Some(rate) = ori_rate {
             self.set_ori_instant(Vec3::new(
-                lerp_angle(self.ori.x, self.tgt_ori.x, LERP_ORI_RATE * dt),
-                Lerp::lerp(self.ori.y, self.tgt_ori.y, LERP_ORI_RATE * dt),
-                lerp_angle(self.ori.z, self.tgt_ori.z, LERP_ORI_RATE * dt),
+                lerp_angle(self.ori.x, self.tgt_ori.x, rate),
+                Lerp::lerp(self.ori.y, self.tgt_ori.y, rate),
+                lerp_angle(self.ori.z, self.tgt_ori.z, rate),
             ));
+            // Snap to the target once close enough, so the camera doesn't keep creeping
+            // after input stops
+            if (self.ori - self.tgt_ori).map(f32::abs).reduce_partial_max() < 0.0001 {
+                self.set_ori_instant(self.tgt_ori);
+            }
         } else {
             self.set_ori_instant(self.tgt_ori)
         };
+
+        // Ease out the roll even without smoothing, so locking doesn't snap the view
+        if self.horizon_lock {
+            self.set_ori_instant(Vec3::new(
+                self.ori.x,
+                self.ori.y,
+                lerp_angle(roll, 0.0, (HORIZON_LOCK_RATE * dt).min(1.0)),
+            ));
+        }
     }
 
     pub fn interp_time(&self) -> f32 {
@@ -287,6 +339,15 @@ impl Camera {
         self.set_fov((fov as f32) * 0.01745329)
     }
 
+    /// Set how many seconds the freefly camera rotation takes to catch up with
+    /// input, 0 disables the smoothing.
+    pub fn set_freefly_rotation_smoothing(&mut self, smoothing: f32) {
+        self.freefly_rotation_smoothing = smoothing.max(0.0);
+    }
+
+    /// Set whether the roll of the camera is kept level with the horizon.
+    pub fn set_horizon_lock(&mut self, horizon_lock: bool) { self.horizon_lock = horizon_lock; }
+
     /// Set the mode of the camera.
     pub fn set_mode(&mut self, mode: CameraMode) {
         if self.mode != mode {
@@ -316,20 +377,14 @@ impl Camera {
         }
     }
 
-    /// Cycle the camera to its next valid mode. If is_admin is false then only
-    /// modes which are accessible without admin access will be cycled to.
-    pub fn next_mode(&mut self, is_admin: bool) {
-        self.set_mode(match self.mode {
+    /// Get the mode that follows the current one when cycling through modes.
+    /// This does not check whether the player is allowed to use that mode.
+    pub fn next_mode(&self) -> CameraMode {
+        match self.mode {
             CameraMode::ThirdPerson => CameraMode::FirstPerson,
-            CameraMode::FirstPerson => {
-                if is_admin {
-                    CameraMode::Freefly
-                } else {
-                    CameraMode::ThirdPerson
-                }
-            },
+            CameraMode::FirstPerson => CameraMode::Freefly,
             CameraMode::Freefly => CameraMode::ThirdPerson,
-        });
+        }
     }
 
     /// Return a unit vector in the forward direction for the current camera
@@ -26,8 +26,8 @@ use anim::{
 use common::{
     comp::{
         item::{ItemKind, ToolKind},
-        Body, CharacterState, Item, Last, LightAnimation, LightEmitter, Loadout, Ori, PhysicsState,
-        Pos, Scale, Stats, Vel,
+        Body, CharacterState, Group, Item, Last, LightAnimation, LightEmitter, Loadout, Ori,
+        PhysicsState, Pos, Scale, Stats, Vel,
     },
     span,
     state::{DeltaTime, State},
@@ -43,16 +43,68 @@ use core::{
 };
 use guillotiere::AtlasAllocator;
 use hashbrown::HashMap;
-use specs::{Entity as EcsEntity, Join, LazyUpdate, WorldExt};
+use specs::{Entity as EcsEntity, Join, LazyUpdate, ReadStorage, WorldExt};
 use treeculler::{BVol, BoundingSphere};
 use vek::*;
 
 const DAMAGE_FADE_COEFFICIENT: f64 = 5.0;
 const MOVING_THRESHOLD: f32 = 0.7;
 const MOVING_THRESHOLD_SQR: f32 = MOVING_THRESHOLD * MOVING_THRESHOLD;
+/// How far past a detail distance a figure has to be before it switches model,
+/// so figures moving about the threshold don't flicker between models.
+const LOD_HYSTERESIS: f32 = 4.0;
+
+/// camera data, figure LOD distances.
+pub type CameraData<'a> = (&'a Camera, FigureLod);
+
+/// Distances from the camera at which figures switch to less detailed models.
+/// The player and their group always keep the most detailed one.
+#[derive(Clone, Copy)]
+pub struct FigureLod {
+    pub mid_detail_distance: f32,
+    pub low_detail_distance: f32,
+}
 
-/// camera data, figure LOD render distance.
-pub type CameraData<'a> = (&'a Camera, f32);
+impl FigureLod {
+    /// Thresholds at `fractions` of the figure LOD render distance.
+    pub fn new(figure_lod_render_distance: f32, fractions: [f32; 2]) -> Self {
+        let [mid, low] = fractions.map(|f| f.max(0.0).min(1.0) * figure_lod_render_distance);
+        Self {
+            mid_detail_distance: mid.min(low),
+            low_detail_distance: low,
+        }
+    }
+
+    /// Detail level, 0 being the most detailed model, of a figure `dist` away
+    /// from the camera that was drawn at level `prev` so far.
+    pub fn level(&self, prev: Option<usize>, dist: f32) -> usize {
+        let level_at = |dist: f32| {
+            [self.mid_detail_distance, self.low_detail_distance]
+                .iter()
+                .filter(|threshold| dist > **threshold)
+                .count()
+        };
+        match prev {
+            Some(prev) => prev
+                .max(level_at(dist - LOD_HYSTERESIS))
+                .min(level_at(dist + LOD_HYSTERESIS)),
+            None => level_at(dist),
+        }
+    }
+}
+
+/// Whether `entity` is drawn and animated in full detail regardless of its
+/// distance, which is the case for the player and their group.
+fn has_full_detail(
+    groups: &ReadStorage<Group>,
+    player_entity: EcsEntity,
+    entity: EcsEntity,
+) -> bool {
+    entity == player_entity
+        || groups
+            .get(player_entity)
+            .map_or(false, |group| groups.get(entity) == Some(group))
+}
 
 /// Enough data to render a figure model.
 pub type FigureModelRef<'a> = (
@@ -527,8 +579,16 @@ impl FigureMgr {
         let character_state = character_state_storage.get(scene_data.player_entity);
 
         let focus_pos = anim::vek::Vec3::<f32>::from(camera.get_focus_pos());
+        let cam_pos = anim::vek::Vec3::<f32>::from(
+            camera.dependents().cam_pos + camera.get_focus_pos().map(|e| e.trunc()),
+        );
+        let figure_lod = FigureLod::new(
+            scene_data.figure_lod_render_distance,
+            scene_data.figure_detail_fractions,
+        );
 
         let mut update_buf = [Default::default(); anim::MAX_BONE_COUNT];
+        let groups = ecs.read_storage::<Group>();
 
         for (
             i,
@@ -591,17 +651,22 @@ impl FigureMgr {
             // goes further and further away, we start to 'skip' update ticks.
             // TODO: Investigate passing the velocity into the shader so we can at least
             // interpolate motion
-            const MIN_PERFECT_RATE_DIST: f32 = 50.0;
-
-            if (i as u64 + tick)
-                % (1 + ((pos.0.distance_squared(focus_pos).powf(0.25)
-                    - MIN_PERFECT_RATE_DIST.powf(0.5))
+            let full_detail = has_full_detail(&groups, scene_data.player_entity, entity);
+            let update_interval = if full_detail {
+                1
+            } else {
+                1 + ((pos.0.distance_squared(focus_pos).powf(0.25)
+                    - scene_data.figure_full_rate_distance.sqrt())
                 .max(0.0)
-                    / 3.0) as u64)
-                != 0
-            {
+                    / 3.0) as u64
+            };
+            if (i as u64 + tick) % update_interval != 0 {
                 continue;
             }
+            // Updates catch up on the skipped frames, so animations keep their speed and
+            // don't jump when an entity crosses into another update interval
+            let dt = dt * update_interval as f32;
+            let dt_lerp = (15.0 * dt).min(1.0);
 
             // Check whether we could have been shadowing last frame.
             let mut state = self.states.get_mut(body, &entity);
@@ -643,6 +708,11 @@ impl FigureMgr {
                     .coherent_test_against_frustum(frustum, meta.lpindex);
                 meta.visible = in_frustum;
                 meta.lpindex = lpindex;
+                meta.lod = if full_detail {
+                    Some(0)
+                } else {
+                    Some(figure_lod.level(meta.lod, pos.0.distance(cam_pos)))
+                };
                 if in_frustum {
                     /* // Update visible bounds.
                     visible_aabb.expand_to_contain(Aabb {
@@ -2723,13 +2793,15 @@ impl FigureMgr {
         &self,
         renderer: &mut Renderer,
         state: &State,
+        player_entity: EcsEntity,
         tick: u64,
         global: &GlobalModel,
         (is_daylight, _light_data): super::LightData,
-        (camera, figure_lod_render_distance): CameraData,
+        (camera, figure_lod): CameraData,
     ) {
         span!(_guard, "render_shadows", "FigureManager::render_shadows");
         let ecs = state.ecs();
+        let groups = ecs.read_storage::<Group>();
 
         if is_daylight && renderer.render_mode().shadow.is_map() {
             (
@@ -2754,7 +2826,8 @@ impl FigureMgr {
                     loadout,
                     false,
                     pos.0,
-                    figure_lod_render_distance,
+                    figure_lod,
+                    has_full_detail(&groups, player_entity, entity),
                     |state| state.can_shadow_sun(),
                 ) {
                     renderer.render_figure_shadow_directed(
@@ -2778,10 +2851,11 @@ impl FigureMgr {
         tick: u64,
         global: &GlobalModel,
         lod: &LodData,
-        (camera, figure_lod_render_distance): CameraData,
+        (camera, figure_lod): CameraData,
     ) {
         span!(_guard, "render", "FigureManager::render");
         let ecs = state.ecs();
+        let groups = ecs.read_storage::<Group>();
 
         let character_state_storage = state.read_storage::<common::comp::CharacterState>();
         let character_state = character_state_storage.get(player_entity);
@@ -2811,7 +2885,8 @@ impl FigureMgr {
                     loadout,
                     false,
                     pos.0,
-                    figure_lod_render_distance,
+                    figure_lod,
+                    has_full_detail(&groups, player_entity, entity),
                     |state| state.visible(),
                 ) {
                     renderer.render_figure(model, &col_lights, global, locals, bone_consts, lod);
@@ -2829,7 +2904,7 @@ impl FigureMgr {
         tick: u64,
         global: &GlobalModel,
         lod: &LodData,
-        (camera, figure_lod_render_distance): CameraData,
+        (camera, figure_lod): CameraData,
     ) {
         span!(_guard, "render_player", "FigureManager::render_player");
         let ecs = state.ecs();
@@ -2860,7 +2935,8 @@ impl FigureMgr {
                 loadout,
                 true,
                 pos.0,
-                figure_lod_render_distance,
+                figure_lod,
+                true,
                 |state| state.visible(),
             ) {
                 renderer.render_player(model, &col_lights, global, locals, bone_consts, lod);
@@ -2887,7 +2963,8 @@ impl FigureMgr {
         loadout: Option<&Loadout>,
         is_player: bool,
         pos: vek::Vec3<f32>,
-        figure_lod_render_distance: f32,
+        figure_lod: FigureLod,
+        full_detail: bool,
         filter_state: impl Fn(&FigureStateMeta) -> bool,
     ) -> Option<FigureModelRef> {
         let player_camera_mode = if is_player {
@@ -2932,7 +3009,7 @@ impl FigureMgr {
                 },
         } = self;
         let col_lights = &*col_lights_;
-        if let Some((locals, bone_consts, model_entry)) = match body {
+        if let Some((locals, bone_consts, lod, model_entry)) = match body {
             Body::Humanoid(body) => character_states
                 .get(&entity)
                 .filter(|state| filter_state(&*state))
@@ -2940,6 +3017,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         model_cache.get_model(
                             col_lights,
                             *body,
@@ -2957,6 +3035,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         quadruped_small_model_cache.get_model(
                             col_lights,
                             *body,
@@ -2974,6 +3053,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         quadruped_medium_model_cache.get_model(
                             col_lights,
                             *body,
@@ -2991,6 +3071,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         quadruped_low_model_cache.get_model(
                             col_lights,
                             *body,
@@ -3008,6 +3089,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         bird_medium_model_cache.get_model(
                             col_lights,
                             *body,
@@ -3025,6 +3107,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         fish_medium_model_cache.get_model(
                             col_lights,
                             *body,
@@ -3042,6 +3125,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         theropod_model_cache.get_model(
                             col_lights,
                             *body,
@@ -3059,6 +3143,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         dragon_model_cache.get_model(
                             col_lights,
                             *body,
@@ -3076,6 +3161,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         bird_small_model_cache.get_model(
                             col_lights,
                             *body,
@@ -3093,6 +3179,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         fish_small_model_cache.get_model(
                             col_lights,
                             *body,
@@ -3110,6 +3197,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         biped_large_model_cache.get_model(
                             col_lights,
                             *body,
@@ -3127,6 +3215,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         golem_model_cache.get_model(
                             col_lights,
                             *body,
@@ -3144,6 +3233,7 @@ impl FigureMgr {
                     (
                         state.locals(),
                         state.bone_consts(),
+                        state.lod,
                         object_model_cache.get_model(
                             col_lights,
                             *body,
@@ -3157,16 +3247,14 @@ impl FigureMgr {
         } {
             let model_entry = model_entry?;
 
-            let figure_low_detail_distance = figure_lod_render_distance * 0.75;
-            let figure_mid_detail_distance = figure_lod_render_distance * 0.5;
-
-            let model = if pos.distance_squared(cam_pos) > figure_low_detail_distance.powf(2.0) {
-                &model_entry.models[2]
-            } else if pos.distance_squared(cam_pos) > figure_mid_detail_distance.powf(2.0) {
-                &model_entry.models[1]
+            // The level is kept on the figure state so it only changes once the figure is
+            // clearly past a detail distance
+            let lod = if full_detail {
+                0
             } else {
-                &model_entry.models[0]
+                lod.unwrap_or_else(|| figure_lod.level(None, pos.distance(cam_pos)))
             };
+            let model = &model_entry.models[lod];
 
             Some((locals, bone_consts, model, col_lights_.texture(model_entry)))
         } else {
@@ -3289,6 +3377,9 @@ pub struct FigureStateMeta {
     state_time: f64,
     last_ori: anim::vek::Vec3<f32>,
     lpindex: u8,
+    /// Detail level of the model the figure is drawn with, see
+    /// `FigureLod::level`
+    lod: Option<usize>,
     can_shadow_sun: bool,
     visible: bool,
     last_pos: Option<anim::vek::Vec3<f32>>,
@@ -3333,6 +3424,7 @@ impl<S: Skeleton> FigureState<S> {
                 state_time: 0.0,
                 last_ori: anim::vek::Vec3::zero(),
                 lpindex: 0,
+                lod: None,
                 visible: false,
                 can_shadow_sun: false,
                 last_pos: None,
@@ -3434,3 +3526,29 @@ fn figure_bone_data_from_anim(
 ) -> &[FigureBoneData] {
     gfx::memory::cast_slice(mats)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn figure_lod_level_by_distance() {
+        let lod = FigureLod::new(100.0, [0.5, 0.75]);
+        assert_eq!(lod.level(None, 10.0), 0);
+        assert_eq!(lod.level(None, 60.0), 1);
+        assert_eq!(lod.level(None, 90.0), 2);
+    }
+
+    #[test]
+    fn figure_lod_level_keeps_model_near_threshold() {
+        let lod = FigureLod::new(100.0, [0.5, 0.75]);
+        // Just past the mid detail distance, either way
+        assert_eq!(lod.level(Some(0), 51.0), 0);
+        assert_eq!(lod.level(Some(1), 49.0), 1);
+        // Clearly past it
+        assert_eq!(lod.level(Some(0), 60.0), 1);
+        assert_eq!(lod.level(Some(1), 40.0), 0);
+        // Far enough to skip a level
+        assert_eq!(lod.level(Some(0), 90.0), 2);
+    }
+}
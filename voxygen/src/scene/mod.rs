@@ -8,7 +8,7 @@ pub mod terrain;
 
 pub use self::{
     camera::{Camera, CameraMode},
-    figure::FigureMgr,
+    figure::{FigureLod, FigureMgr},
     lod::Lod,
     particle::ParticleMgr,
     terrain::Terrain,
@@ -20,7 +20,7 @@ use crate::{
         PostProcessLocals, PostProcessPipeline, Renderer, Shadow, ShadowLocals, SkyboxLocals,
         SkyboxPipeline,
     },
-    settings::Settings,
+    settings::{OutcomeEffects, Settings, ShoulderSide},
     window::{AnalogGameInput, Event},
 };
 use client::Client;
@@ -30,7 +30,7 @@ use common::{
     outcome::Outcome,
     span,
     state::{DeltaTime, State},
-    terrain::{BlockKind, TerrainChunk},
+    terrain::{Block, BlockKind, TerrainChunk},
     vol::ReadVol,
 };
 use comp::item::Reagent;
@@ -40,6 +40,22 @@ use vek::*;
 
 // TODO: Don't hard-code this.
 const CURSOR_PAN_SCALE: f32 = 0.005;
+/// Turn rate of the camera in radians per second with the cursor at the edge
+/// of the window, for absolute mouse steering
+const CURSOR_STEER_RATE: f32 = 2.5;
+/// Cursor offsets from the center of the window below this don't steer
+const CURSOR_STEER_DEADZONE: f32 = 0.1;
+/// Sideways distance of the third person camera from the character when it
+/// is over a shoulder
+const SHOULDER_OFFSET: f32 = 0.8;
+/// How quickly the camera moves over when the shoulder is swapped, as the
+/// fraction of the remaining distance per second
+const SHOULDER_SWAP_RATE: f32 = 6.0;
+/// Most recoil the camera can build up from quick attacks, in radians
+const MAX_RECOIL: f32 = 0.15;
+/// How quickly the camera recovers from recoil, as the fraction of the
+/// remaining kick per second
+const RECOIL_RECOVERY_RATE: f32 = 12.0;
 
 const MAX_LIGHT_COUNT: usize = 31;
 const MAX_SHADOW_COUNT: usize = 24;
@@ -80,6 +96,12 @@ pub struct Scene {
     data: GlobalModel,
     camera: Camera,
     camera_input_state: Vec2<f32>,
+    cursor_steer_state: Vec2<f32>,
+    /// Current sideways offset of the third person camera, moving towards
+    /// the chosen shoulder
+    shoulder_offset: f32,
+    /// Upward kick of the camera from attacking, recovering over time
+    recoil: f32,
     event_lights: Vec<EventLight>,
 
     skybox: Skybox,
@@ -100,6 +122,26 @@ pub struct Scene {
     music_mgr: MusicMgr,
 }
 
+/// The ray used to pick what is under the crosshair, for debugging.
+#[derive(Clone, Copy, Debug)]
+pub struct TargetingRay {
+    pub start: Vec3<f32>,
+    pub end: Vec3<f32>,
+    /// Where the ray hits terrain, if it does
+    pub terrain_hit: Option<Vec3<f32>>,
+    /// Center and radius of the sphere of the entity picked, if any
+    pub entity_sphere: Option<(Vec3<f32>, f32)>,
+}
+
+/// A beam of light over a dropped item, to make rare loot easy to spot.
+#[derive(Clone, Copy, Debug)]
+pub struct LootBeam {
+    pub pos: Vec3<f32>,
+    pub col: Rgb<f32>,
+    /// Fades out with the distance from the player
+    pub opacity: f32,
+}
+
 pub struct SceneData<'a> {
     pub state: &'a State,
     pub player_entity: specs::Entity,
@@ -113,8 +155,47 @@ pub struct SceneData<'a> {
     pub mouse_smoothing: bool,
     pub sprite_render_distance: f32,
     pub particles_enabled: bool,
+    pub max_particles_per_outcome: Option<u32>,
     pub figure_lod_render_distance: f32,
+    /// Fractions of `figure_lod_render_distance` beyond which figures use their
+    /// medium and low detail models
+    pub figure_detail_fractions: [f32; 2],
+    /// Distance up to which figures are animated every frame, further away
+    /// they are updated less and less often
+    pub figure_full_rate_distance: f32,
+    /// Whether the player's own lantern adds to the lighting
+    pub own_lantern_light: bool,
+    /// Whether newly loaded chunks fade in
+    pub chunk_fade_in: bool,
     pub is_aiming: bool,
+    /// Where blocks would be placed in build mode, and the block to preview
+    /// there
+    pub build_ghost: Option<(&'a [Vec3<i32>], Block)>,
+    /// Whether to draw the borders of the chunks around the player
+    pub show_chunk_borders: bool,
+    /// Radius of the ring showing the interact range around the player, if it
+    /// should be drawn
+    pub interact_range: Option<f32>,
+    /// Whether ambient effects are held back, e.g. while a large HUD panel is
+    /// open
+    pub world_dimmed: bool,
+    /// The targeting ray to draw, if enabled
+    pub targeting_ray: Option<TargetingRay>,
+    /// Block to draw the build grid around, if it should be drawn
+    pub build_grid: Option<Vec3<i32>>,
+    /// Beams to draw over rare dropped items
+    pub loot_beams: &'a [LootBeam],
+    /// Whether the player's hands and weapon are drawn in first person
+    pub show_first_person_model: bool,
+    /// Seconds for the freefly camera rotation to catch up with input, 0
+    /// disables it
+    pub freefly_rotation_smoothing: f32,
+    /// Whether the camera roll is kept level with the horizon
+    pub horizon_lock: bool,
+    /// Shoulder the third person camera is over
+    pub shoulder_side: ShoulderSide,
+    /// How much sounds behind terrain are muffled, 0 disables it
+    pub sfx_occlusion: f32,
 }
 
 impl<'a> SceneData<'a> {
@@ -277,6 +358,9 @@ impl Scene {
             },
             camera: Camera::new(resolution.x / resolution.y, CameraMode::ThirdPerson),
             camera_input_state: Vec2::zero(),
+            cursor_steer_state: Vec2::zero(),
+            shoulder_offset: 0.0,
+            recoil: 0.0,
             event_lights: Vec::new(),
 
             skybox: Skybox {
@@ -323,6 +407,15 @@ impl Scene {
     /// Get a mutable reference to the scene's camera.
     pub fn camera_mut(&mut self) -> &mut Camera { &mut self.camera }
 
+    /// Current sideways offset of the third person camera from the player,
+    /// positive to the right.
+    pub fn shoulder_offset(&self) -> f32 { self.shoulder_offset }
+
+    /// Kick the camera up by `amount` radians, e.g. when attacking.
+    pub fn add_recoil(&mut self, amount: f32) {
+        self.recoil = (self.recoil + amount).min(MAX_RECOIL);
+    }
+
     /// Set the block position that the player is interacting with
     pub fn set_select_pos(&mut self, pos: Option<Vec3<i32>>) { self.select_pos = pos; }
 
@@ -344,6 +437,17 @@ impl Scene {
                 self.camera.rotate_by(Vec3::from(delta) * CURSOR_PAN_SCALE);
                 true
             },
+            // With absolute steering, the cursor offset sets how fast the camera turns
+            Event::CursorSteer(offset) => {
+                self.cursor_steer_state = offset.map(|e| {
+                    if e.abs() < CURSOR_STEER_DEADZONE {
+                        0.0
+                    } else {
+                        (e - CURSOR_STEER_DEADZONE * e.signum()) / (1.0 - CURSOR_STEER_DEADZONE)
+                    }
+                });
+                true
+            },
             // Zoom the camera when a zoom event occurs
             Event::Zoom(delta) => {
                 // when zooming in the distance the camera travelles should be based on the
@@ -380,10 +484,25 @@ impl Scene {
         outcome: &Outcome,
         scene_data: &SceneData,
         audio: &mut AudioFrontend,
+        effects: OutcomeEffects,
     ) {
         span!(_guard, "handle_outcome", "Scene::handle_outcome");
-        self.particle_mgr.handle_outcome(&outcome, &scene_data);
-        self.sfx_mgr.handle_outcome(&outcome, audio);
+        if effects.visual {
+            self.particle_mgr.handle_outcome(&outcome, &scene_data);
+        }
+        if effects.sound {
+            self.sfx_mgr.handle_outcome(
+                &outcome,
+                audio,
+                scene_data.state,
+                scene_data.player_entity,
+                scene_data.sfx_occlusion,
+            );
+        }
+        // Only visual effects are left
+        if !effects.visual {
+            return;
+        }
 
         match outcome {
             Outcome::Explosion {
@@ -484,6 +603,11 @@ impl Scene {
         self.camera
             .rotate_by(Vec3::from([0.0, self.camera_input_state.y, 0.0]));
 
+        // Add the absolute mouse steering to camera
+        self.camera.rotate_by(Vec3::from(
+            self.cursor_steer_state * CURSOR_STEER_RATE * ecs.fetch::<DeltaTime>().0,
+        ));
+
         // Alter camera position to match player.
         let tilt = self.camera.get_orientation().y;
         let dist = self.camera.get_distance();
@@ -503,16 +627,42 @@ impl Scene {
             CameraMode::Freefly => 0.0,
         };
 
+        // Move over to the chosen shoulder gradually rather than snapping
+        let shoulder_target = scene_data.shoulder_side.sign() * SHOULDER_OFFSET;
+        self.shoulder_offset = Lerp::lerp(
+            self.shoulder_offset,
+            shoulder_target,
+            (SHOULDER_SWAP_RATE * scene_data.state.get_delta_time()).min(1.0),
+        );
+
         match self.camera.get_mode() {
-            CameraMode::FirstPerson | CameraMode::ThirdPerson => {
+            CameraMode::FirstPerson => {
                 self.camera.set_focus_pos(
                     player_pos + Vec3::unit_z() * (up - tilt.min(0.0).sin() * dist * 0.6),
                 );
             },
+            CameraMode::ThirdPerson => {
+                self.camera.set_focus_pos(
+                    player_pos
+                        + Vec3::unit_z() * (up - tilt.min(0.0).sin() * dist * 0.6)
+                        + Vec3::from(self.camera.right_xy()) * self.shoulder_offset,
+                );
+            },
             CameraMode::Freefly => {},
         };
 
+        // Recover from recoil smoothly
+        self.recoil = Lerp::lerp(
+            self.recoil,
+            0.0,
+            (RECOIL_RECOVERY_RATE * scene_data.state.get_delta_time()).min(1.0),
+        );
+        self.camera.set_pitch_offset(-self.recoil);
+
         // Tick camera for interpolation.
+        self.camera
+            .set_freefly_rotation_smoothing(scene_data.freefly_rotation_smoothing);
+        self.camera.set_horizon_lock(scene_data.horizon_lock);
         self.camera.update(
             scene_data.state.get_time(),
             scene_data.state.get_delta_time(),
@@ -537,6 +687,7 @@ impl Scene {
         lights.clear();
         lights.extend(
             (
+                &scene_data.state.ecs().entities(),
                 &scene_data.state.ecs().read_storage::<comp::Pos>(),
                 scene_data.state.ecs().read_storage::<comp::Ori>().maybe(),
                 scene_data
@@ -550,13 +701,14 @@ impl Scene {
                     .read_storage::<comp::LightAnimation>(),
             )
                 .join()
-                .filter(|(pos, _, _, light_anim)| {
-                    light_anim.col != Rgb::zero()
+                .filter(|(entity, pos, _, _, light_anim)| {
+                    (scene_data.own_lantern_light || *entity != scene_data.player_entity)
+                        && light_anim.col != Rgb::zero()
                         && light_anim.strength > 0.0
                         && (pos.0.distance_squared(player_pos) as f32)
                             < loaded_distance.powf(2.0) + LIGHT_DIST_RADIUS
                 })
-                .map(|(pos, ori, interpolated, light_anim)| {
+                .map(|(_, pos, ori, interpolated, light_anim)| {
                     // Use interpolated values if they are available
                     let (pos, ori) =
                         interpolated.map_or((pos.0, ori.map(|o| o.0)), |i| (i.pos, Some(i.ori)));
@@ -983,6 +1135,7 @@ impl Scene {
             scene_data.state,
             scene_data.player_entity,
             &self.camera,
+            scene_data.sfx_occlusion,
         );
         self.music_mgr.maintain(audio, scene_data.state);
     }
@@ -1004,7 +1157,11 @@ impl Scene {
 
         let global = &self.data;
         let light_data = (is_daylight, &*self.light_data);
-        let camera_data = (&self.camera, scene_data.figure_lod_render_distance);
+        let figure_lod = FigureLod::new(
+            scene_data.figure_lod_render_distance,
+            scene_data.figure_detail_fractions,
+        );
+        let camera_data = (&self.camera, figure_lod);
 
         // would instead have this as an extension.
         if renderer.render_mode().shadow.is_map() && (is_daylight || !light_data.1.is_empty()) {
@@ -1018,8 +1175,15 @@ impl Scene {
                 .render_shadows(renderer, global, light_data, focus_pos);
 
             // Render figure shadows.
-            self.figure_mgr
-                .render_shadows(renderer, state, tick, global, light_data, camera_data);
+            self.figure_mgr.render_shadows(
+                renderer,
+                state,
+                player_entity,
+                tick,
+                global,
+                light_data,
+                camera_data,
+            );
 
             if is_daylight {
                 // Flush shadows.
@@ -1028,15 +1192,17 @@ impl Scene {
         }
         let lod = self.lod.get_data();
 
-        self.figure_mgr.render_player(
-            renderer,
-            state,
-            player_entity,
-            tick,
-            global,
-            lod,
-            camera_data,
-        );
+        if self.camera.get_mode() != CameraMode::FirstPerson || scene_data.show_first_person_model {
+            self.figure_mgr.render_player(
+                renderer,
+                state,
+                player_entity,
+                tick,
+                global,
+                lod,
+                camera_data,
+            );
+        }
 
         // Render terrain and figures.
         self.terrain.render(renderer, global, lod, focus_pos);
@@ -32,6 +32,26 @@ pub struct ParticleMgr {
     /// keep track of timings
     scheduler: HeartbeatScheduler,
 
+    /// preview of where blocks will be placed in build mode
+    build_ghosts: Vec<ParticleInstance>,
+
+    /// lines along the borders of the chunks around the player
+    chunk_borders: Vec<ParticleInstance>,
+
+    /// ring on the ground around the player showing the interact range
+    range_ring: Vec<ParticleInstance>,
+    range_ring_opacity: f32,
+    range_ring_radius: f32,
+
+    /// dots along the targeting ray and markers where it hits
+    targeting_ray: Vec<ParticleInstance>,
+
+    /// grid aligned to the blocks around the cursor in build mode
+    build_grid: Vec<ParticleInstance>,
+
+    /// beams of light over rare dropped items
+    loot_beams: Vec<ParticleInstance>,
+
     /// GPU Instance Buffer
     instances: Instances<ParticleInstance>,
 
@@ -44,6 +64,14 @@ impl ParticleMgr {
         Self {
             particles: Vec::new(),
             scheduler: HeartbeatScheduler::new(),
+            build_ghosts: Vec::new(),
+            chunk_borders: Vec::new(),
+            range_ring: Vec::new(),
+            range_ring_opacity: 0.0,
+            range_ring_radius: 0.0,
+            targeting_ray: Vec::new(),
+            build_grid: Vec::new(),
+            loot_beams: Vec::new(),
             instances: default_instances(renderer),
             model_cache: default_cache(renderer),
         }
@@ -63,10 +91,11 @@ impl ParticleMgr {
                 reagent,
             } => {
                 if *is_attack {
+                    let count = (200.0 * power.abs()) as usize;
+                    let count = (count as f32 * outcome_particle_scale(count, scene_data)) as usize;
                     if *power < 0.0 {
-                        self.particles.resize_with(
-                            self.particles.len() + (200.0 * power.abs()) as usize,
-                            || {
+                        self.particles
+                            .resize_with(self.particles.len() + count, || {
                                 Particle::new(
                                     Duration::from_secs(1),
                                     time,
@@ -74,12 +103,10 @@ impl ParticleMgr {
                                     *pos + Vec3::<f32>::zero()
                                         .map(|_| rng.gen_range(-radius, radius)),
                                 )
-                            },
-                        );
+                            });
                     } else {
-                        self.particles.resize_with(
-                            self.particles.len() + (200.0 * power.abs()) as usize,
-                            || {
+                        self.particles
+                            .resize_with(self.particles.len() + count, || {
                                 Particle::new(
                                     Duration::from_secs(1),
                                     time,
@@ -87,12 +114,17 @@ impl ParticleMgr {
                                     *pos + Vec3::<f32>::zero()
                                         .map(|_| rng.gen_range(-radius, radius)),
                                 )
-                            },
-                        );
+                            });
                     }
                 } else {
+                    let (sparks, smoke) = if reagent.is_some() {
+                        (300, 100)
+                    } else {
+                        (150, 200)
+                    };
+                    let scale = outcome_particle_scale(sparks + smoke, scene_data);
                     self.particles.resize_with(
-                        self.particles.len() + if reagent.is_some() { 300 } else { 150 },
+                        self.particles.len() + (sparks as f32 * scale) as usize,
                         || {
                             Particle::new(
                                 Duration::from_millis(if reagent.is_some() { 1000 } else { 250 }),
@@ -111,7 +143,7 @@ impl ParticleMgr {
                     );
 
                     self.particles.resize_with(
-                        self.particles.len() + if reagent.is_some() { 100 } else { 200 },
+                        self.particles.len() + (smoke as f32 * scale) as usize,
                         || {
                             Particle::new(
                                 Duration::from_secs(4),
@@ -146,16 +178,42 @@ impl ParticleMgr {
             self.maintain_body_particles(scene_data);
             self.maintain_boost_particles(scene_data);
             self.maintain_beam_particles(scene_data);
-            self.maintain_block_particles(scene_data, terrain);
+            // Ambient particles of the surroundings are distracting while browsing a
+            // panel, but those of combat keep spawning
+            if !scene_data.world_dimmed {
+                self.maintain_block_particles(scene_data, terrain);
+            }
             self.maintain_shockwave_particles(scene_data);
+
+            self.build_ghosts.clear();
+            if let Some((cells, block)) = scene_data.build_ghost {
+                let col = block
+                    .get_color()
+                    .unwrap_or_else(|| Rgb::broadcast(255))
+                    .map(|e| e as f32 / 255.0);
+                self.build_ghosts.extend(cells.iter().map(|pos| {
+                    ParticleInstance::new_block_ghost(
+                        scene_data.state.get_time(),
+                        pos.map(|e| e as f32 + 0.5),
+                        col,
+                    )
+                }));
+            }
         } else {
             // remove all particle lifespans
             self.particles.clear();
+            self.build_ghosts.clear();
 
             // remove all timings
             self.scheduler.clear();
         }
 
+        self.maintain_chunk_borders(scene_data);
+        self.maintain_range_ring(scene_data);
+        self.maintain_targeting_ray(scene_data);
+        self.maintain_build_grid(scene_data);
+        self.maintain_loot_beams(scene_data);
+
         self.upload_particles(renderer);
     }
 
@@ -612,12 +670,213 @@ impl ParticleMgr {
         }
     }
 
+    fn maintain_chunk_borders(&mut self, scene_data: &SceneData) {
+        /// How many chunks around the player's chunk have their borders drawn
+        const CHUNK_BORDER_RADIUS: i32 = 1;
+        /// How far the vertical lines reach above and below the player
+        const CHUNK_BORDER_HEIGHT: f32 = 32.0;
+
+        self.chunk_borders.clear();
+        if !scene_data.show_chunk_borders {
+            return;
+        }
+        let player_pos = match scene_data
+            .state
+            .read_storage::<Pos>()
+            .get(scene_data.player_entity)
+        {
+            Some(pos) => pos.0,
+            None => return,
+        };
+
+        let time = scene_data.state.get_time();
+        let chunk_size = TerrainChunk::RECT_SIZE.map(|e| e as f32);
+        let player_chunk = player_pos
+            .xy()
+            .map2(chunk_size, |e, sz| (e / sz).floor() as i32);
+        let min = (player_chunk - CHUNK_BORDER_RADIUS).map(|e| e as f32) * chunk_size;
+        let max = (player_chunk + CHUNK_BORDER_RADIUS + 1).map(|e| e as f32) * chunk_size;
+        let center = (min + max) / 2.0;
+        let z = player_pos.z.floor();
+
+        for i in 0..=(CHUNK_BORDER_RADIUS * 2 + 1) {
+            let x = min.x + i as f32 * chunk_size.x;
+            let y = min.y + i as f32 * chunk_size.y;
+
+            // Horizontal lines along the borders at the player's feet
+            self.chunk_borders.push(ParticleInstance::new_chunk_border(
+                time,
+                Vec3::new(x, center.y, z),
+                Vec3::new(0.0, max.y - min.y, 0.0),
+            ));
+            self.chunk_borders.push(ParticleInstance::new_chunk_border(
+                time,
+                Vec3::new(center.x, y, z),
+                Vec3::new(max.x - min.x, 0.0, 0.0),
+            ));
+
+            // Vertical lines at the chunk corners
+            for j in 0..=(CHUNK_BORDER_RADIUS * 2 + 1) {
+                self.chunk_borders.push(ParticleInstance::new_chunk_border(
+                    time,
+                    Vec3::new(x, min.y + j as f32 * chunk_size.y, z),
+                    Vec3::new(0.0, 0.0, CHUNK_BORDER_HEIGHT * 2.0),
+                ));
+            }
+        }
+    }
+
+    fn maintain_range_ring(&mut self, scene_data: &SceneData) {
+        /// Number of tiles the ring is made of
+        const RANGE_RING_SEGMENTS: usize = 48;
+        /// How quickly the ring fades in or out, in opacity per second
+        const RANGE_RING_FADE_RATE: f32 = 4.0;
+
+        self.range_ring.clear();
+        let fade = RANGE_RING_FADE_RATE * scene_data.state.get_delta_time();
+        self.range_ring_opacity = if scene_data.interact_range.is_some() {
+            (self.range_ring_opacity + fade).min(1.0)
+        } else {
+            (self.range_ring_opacity - fade).max(0.0)
+        };
+        // Keep the last radius while fading out
+        if let Some(radius) = scene_data.interact_range {
+            self.range_ring_radius = radius;
+        }
+        if self.range_ring_opacity <= 0.0 {
+            return;
+        }
+        let player_pos = match scene_data
+            .state
+            .read_storage::<Pos>()
+            .get(scene_data.player_entity)
+        {
+            Some(pos) => pos.0,
+            None => return,
+        };
+
+        let time = scene_data.state.get_time();
+        for i in 0..RANGE_RING_SEGMENTS {
+            let angle = i as f32 / RANGE_RING_SEGMENTS as f32 * 2.0 * PI;
+            let offset = Vec2::new(angle.cos(), angle.sin()) * self.range_ring_radius;
+            self.range_ring.push(ParticleInstance::new_range_ring(
+                time,
+                player_pos + Vec3::from(offset) + Vec3::unit_z() * 0.05,
+                self.range_ring_opacity,
+            ));
+        }
+    }
+
+    fn maintain_targeting_ray(&mut self, scene_data: &SceneData) {
+        /// Distance between the dots drawn along the ray
+        const RAY_DOT_SPACING: f32 = 0.5;
+
+        self.targeting_ray.clear();
+        let ray = match scene_data.targeting_ray {
+            Some(ray) => ray,
+            None => return,
+        };
+
+        let time = scene_data.state.get_time();
+        let len = ray.start.distance(ray.end);
+        let dots = (len / RAY_DOT_SPACING) as usize;
+        for i in 0..dots {
+            self.targeting_ray.push(ParticleInstance::new_debug_marker(
+                time,
+                Lerp::lerp(ray.start, ray.end, i as f32 / dots as f32),
+                Rgb::new(1.0, 1.0, 0.2),
+                0.05,
+            ));
+        }
+        if let Some(hit) = ray.terrain_hit {
+            self.targeting_ray.push(ParticleInstance::new_debug_marker(
+                time,
+                hit,
+                Rgb::new(1.0, 0.2, 0.2),
+                0.3,
+            ));
+        }
+        if let Some((center, radius)) = ray.entity_sphere {
+            // The center, and the top and bottom of the sphere to show its size
+            for offset in &[0.0, radius, -radius] {
+                self.targeting_ray.push(ParticleInstance::new_debug_marker(
+                    time,
+                    center + Vec3::unit_z() * *offset,
+                    Rgb::new(0.2, 1.0, 0.2),
+                    0.3,
+                ));
+            }
+        }
+    }
+
+    fn maintain_build_grid(&mut self, scene_data: &SceneData) {
+        /// How many blocks around the cursor the grid reaches
+        const BUILD_GRID_RADIUS: i32 = 4;
+
+        self.build_grid.clear();
+        let center = match scene_data.build_grid {
+            Some(pos) => pos,
+            None => return,
+        };
+
+        let time = scene_data.state.get_time();
+        // Lie just above the top face of the block under the cursor
+        let z = center.z as f32 + 1.02;
+        for i in -BUILD_GRID_RADIUS..=BUILD_GRID_RADIUS + 1 {
+            for j in -BUILD_GRID_RADIUS..=BUILD_GRID_RADIUS {
+                // Fade each segment out by its distance from the cursor
+                let dist = Vec2::new(i as f32 - 0.5, j as f32).magnitude();
+                let opacity = 1.0 - dist / (BUILD_GRID_RADIUS as f32 + 1.0);
+                if opacity <= 0.0 {
+                    continue;
+                }
+                let (a, b) = ((center.x + i) as f32, (center.y + j) as f32 + 0.5);
+                self.build_grid.push(ParticleInstance::new_build_grid(
+                    time,
+                    Vec3::new(a, b, z),
+                    Vec3::unit_y(),
+                    opacity,
+                ));
+                let (a, b) = ((center.x + j) as f32 + 0.5, (center.y + i) as f32);
+                self.build_grid.push(ParticleInstance::new_build_grid(
+                    time,
+                    Vec3::new(a, b, z),
+                    Vec3::unit_x(),
+                    opacity,
+                ));
+            }
+        }
+    }
+
+    fn maintain_loot_beams(&mut self, scene_data: &SceneData) {
+        /// Height of the beams, as drawn by the shader
+        const LOOT_BEAM_HEIGHT: f32 = 6.0;
+
+        let time = scene_data.state.get_time();
+        self.loot_beams.clear();
+        self.loot_beams
+            .extend(scene_data.loot_beams.iter().map(|beam| {
+                ParticleInstance::new_loot_beam(
+                    time,
+                    beam.pos + Vec3::unit_z() * LOOT_BEAM_HEIGHT / 2.0,
+                    beam.col,
+                    beam.opacity,
+                )
+            }));
+    }
+
     fn upload_particles(&mut self, renderer: &mut Renderer) {
         span!(_guard, "upload_particles", "ParticleMgr::upload_particles");
         let all_cpu_instances = self
             .particles
             .iter()
             .map(|p| p.instance)
+            .chain(self.build_ghosts.iter().copied())
+            .chain(self.chunk_borders.iter().copied())
+            .chain(self.range_ring.iter().copied())
+            .chain(self.targeting_ray.iter().copied())
+            .chain(self.build_grid.iter().copied())
+            .chain(self.loot_beams.iter().copied())
             .collect::<Vec<ParticleInstance>>();
 
         // TODO: optimise buffer writes
@@ -636,7 +895,13 @@ impl ParticleMgr {
         lod: &LodData,
     ) {
         span!(_guard, "render", "ParticleMgr::render");
-        if scene_data.particles_enabled {
+        if scene_data.particles_enabled
+            || scene_data.show_chunk_borders
+            || !self.range_ring.is_empty()
+            || !self.targeting_ray.is_empty()
+            || !self.build_grid.is_empty()
+            || !self.loot_beams.is_empty()
+        {
             let model = &self
                 .model_cache
                 .get(DEFAULT_MODEL_KEY)
@@ -651,6 +916,19 @@ impl ParticleMgr {
     pub fn particle_count_visible(&self) -> usize { self.instances.count() }
 }
 
+/// Fraction of the particles of an outcome to spawn so that its `total` stays
+/// within the per-outcome cap. Every burst of the outcome is scaled by the same
+/// fraction, so it still looks like the full effect, just sparser.
+fn outcome_particle_scale(total: usize, scene_data: &SceneData) -> f32 {
+    if !scene_data.particles_enabled {
+        return 0.0;
+    }
+    match scene_data.max_particles_per_outcome {
+        Some(max) if total > max as usize => max as f32 / total as f32,
+        _ => 1.0,
+    }
+}
+
 fn default_instances(renderer: &mut Renderer) -> Instances<ParticleInstance> {
     let empty_vec = Vec::new();
 
@@ -35,6 +35,9 @@ use treeculler::{BVol, Frustum, AABB};
 use vek::*;
 
 const SPRITE_SCALE: Vec3<f32> = Vec3::new(1.0 / 11.0, 1.0 / 11.0, 1.0 / 11.0);
+/// Load time given to chunks that shouldn't fade in, far enough in the past
+/// that their fade is long over.
+const NO_FADE_LOAD_TIME: f32 = -1.0e4;
 
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 enum Visibility {
@@ -225,6 +228,10 @@ pub struct Terrain<V: RectRasterableVol> {
     mesh_send_tmp: channel::Sender<MeshWorkerResponse>,
     mesh_recv: channel::Receiver<MeshWorkerResponse>,
     mesh_todo: HashMap<Vec2<i32>, ChunkMeshState>,
+    /// Whether all chunks of the initial stream-in have been meshed. Only
+    /// chunks loaded after that fade in, so the world doesn't fade in as a
+    /// whole on connect.
+    stream_in_settled: bool,
 
     // GPU data
     sprite_data: Arc<HashMap<(SpriteKind, usize), Vec<SpriteData>>>,
@@ -376,6 +383,7 @@ impl<V: RectRasterableVol> Terrain<V> {
             mesh_send_tmp: send,
             mesh_recv: recv,
             mesh_todo: HashMap::default(),
+            stream_in_settled: false,
             sprite_data: Arc::new(sprite_data),
             sprite_col_lights,
             waves: renderer
@@ -678,11 +686,16 @@ impl<V: RectRasterableVol> Terrain<V> {
                 // data structure (convert the mesh to a model first of course).
                 Some(todo) if response.started_tick <= todo.started_tick => {
                     let started_tick = todo.started_tick;
+                    // Remeshed chunks keep fading in where they left off
                     let load_time = self
                         .chunks
                         .get(&response.pos)
                         .map(|chunk| chunk.load_time)
-                        .unwrap_or(current_time as f32);
+                        .unwrap_or(if scene_data.chunk_fade_in && self.stream_in_settled {
+                            current_time as f32
+                        } else {
+                            NO_FADE_LOAD_TIME
+                        });
                     // TODO: Allocate new atlas on allocation failure.
                     let (tex, tex_size) = response.col_lights_info;
                     let atlas = &mut self.atlas;
@@ -769,6 +782,9 @@ impl<V: RectRasterableVol> Terrain<V> {
                 None => {},
             }
         }
+        if !self.stream_in_settled && !self.chunks.is_empty() && self.mesh_todo.is_empty() {
+            self.stream_in_settled = true;
+        }
         drop(guard);
 
         // Construct view frustum
@@ -1,34 +1,57 @@
 use crate::{
+    action_log::ActionLog,
     audio::sfx::{SfxEvent, SfxEventItem},
+    benchmark::{Benchmark, BENCHMARK_VIEW_DISTANCE},
+    camera_path::CameraPath,
     ecs::MyEntity,
-    hud::{DebugInfo, Event as HudEvent, Hud, HudInfo, PressBehavior},
+    hud::{
+        DebugInfo, Event as HudEvent, HotbarSlotContents, Hud, HudInfo, InspectedGear,
+        PressBehavior,
+    },
     i18n::{i18n_asset_key, VoxygenLocalization},
     key_state::KeyState,
     menu::char_selection::CharSelectionState,
-    render::Renderer,
-    scene::{camera, CameraMode, Scene, SceneData},
-    settings::{AudioOutput, ControlSettings, Settings},
-    window::{AnalogGameInput, Event, GameInput},
+    profile::{ChatChannel, SessionStats},
+    render::{RenderError, RenderMode, Renderer, ShadowMapMode, ShadowMode},
+    replay::{Replay, ReplayFrame, ReplayState},
+    scene::{camera, CameraMode, LootBeam, Scene, SceneData, TargetingRay},
+    settings::{AudioOutput, ControlSettings, FreeflySettings, Settings},
+    spectate::{Spectate, SpectateStatus},
+    tts::ChatReader,
+    window::{AnalogGameInput, Event, GameInput, MouseSteering},
     Direction, Error, GlobalState, PlayState, PlayStateResult,
 };
 use client::{self, Client};
 use common::{
     assets::Asset,
+    cmd::ChatCommand,
     comp,
     comp::{
+        item::{ItemKind, Quality, ToolCategory},
         ChatMsg, ChatType, InventoryUpdateEvent, Pos, Vel, MAX_MOUNT_RANGE_SQR,
         MAX_PICKUP_RANGE_SQR,
     },
     event::EventBus,
+    msg::{BlockPlacementError, Notification},
     outcome::Outcome,
     span,
+    state::TimeOfDay,
+    sync::WorldSyncExt,
     terrain::{Block, BlockKind},
     util::Dir,
     vol::ReadVol,
 };
 use specs::{Join, WorldExt};
-use std::{cell::RefCell, rc::Rc, sync::Arc, time::Duration};
-use tracing::{error, info};
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{BTreeSet, VecDeque},
+    mem,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::{error, info, warn};
 use vek::*;
 
 /// The action to perform after a tick
@@ -39,6 +62,28 @@ enum TickAction {
     Disconnect,
 }
 
+/// Where the player goes once the summary shown before leaving the game is
+/// dismissed
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LeaveTo {
+    CharacterSelection,
+    MainMenu,
+}
+
+/// Something nearby that the player can interact with
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Interactable {
+    Block(Vec3<i32>),
+    Entity(specs::Entity),
+}
+
+/// A build action that can be repeated at the cursor
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BuildAction {
+    Place,
+    Break,
+}
+
 pub struct SessionState {
     scene: Scene,
     client: Rc<RefCell<Client>>,
@@ -50,11 +95,128 @@ pub struct SessionState {
     walk_forward_dir: Vec2<f32>,
     walk_right_dir: Vec2<f32>,
     freefly_vel: Vec3<f32>,
+    /// Scale of the freefly speed, easing towards that of the held modifiers
+    freefly_speed_mul: f32,
     free_look: bool,
+    /// Whether free look was latched on by a double tap
+    free_look_latched: bool,
+    /// When the free look key was last tapped, to catch double taps
+    last_free_look_tap: Option<Instant>,
+    /// The movement key last pressed and when, to catch double taps that roll
+    last_move_tap: Option<(GameInput, Instant)>,
+    /// The movement key holding down roll after a double tap
+    dodge_key: Option<GameInput>,
     auto_walk: bool,
+    /// Whether the character walks rather than runs
+    walking: bool,
     is_aiming: bool,
+    aim_camera_shift: bool,
     target_entity: Option<specs::Entity>,
     selected_entity: Option<(specs::Entity, std::time::Instant)>,
+    replay: Replay,
+    camera_path: CameraPath,
+    /// Whether the combat camera distance is in use rather than the peaceful
+    /// one
+    combat_camera: bool,
+    /// Whether the player was in combat last tick, to switch camera distance
+    /// when it changes
+    was_in_combat: bool,
+    /// When the player last zoomed or switched camera distance themselves
+    last_manual_zoom: Option<Instant>,
+    /// Where the player is walking to with click-to-move, and since when
+    move_destination: Option<(Vec3<f32>, Instant)>,
+    action_log: ActionLog,
+    chat_reader: ChatReader,
+    selected_interactable: Option<Interactable>,
+    input_compensation: Duration,
+    fov_override: Option<u16>,
+    gamma_override: Option<f32>,
+    /// Where blocks would be placed in build mode, and the block to preview
+    /// there
+    build_ghost: Option<(Vec<Vec3<i32>>, Block)>,
+    /// Whether the secondary input places a line of blocks rather than one
+    line_tool: bool,
+    /// Where the line being placed starts, while the secondary input is held
+    build_line_start: Option<Vec3<i32>>,
+    /// Whether the build grid is toggled on
+    build_grid: bool,
+    /// Block the build grid is drawn around, while building is allowed
+    build_grid_pos: Option<Vec3<i32>>,
+    /// The last build action taken this session, to repeat it elsewhere
+    last_build_action: Option<BuildAction>,
+    /// Whether the block under the cursor is shown in the HUD
+    inspect_block: bool,
+    /// The block under the cursor and its position, while inspecting blocks
+    inspected_block: Option<Option<(Vec3<i32>, Block)>>,
+    /// Whether all loaded players are tagged in the HUD, for admins
+    scout_overlay: bool,
+    /// Whether the coordinates of group members are shown
+    group_coordinates: bool,
+    session_stats: SessionStats,
+    placement_retry_pending: bool,
+    /// Where the last retried placement went, so its failure isn't retried
+    /// again. Placing another block starts afresh.
+    placement_retried_pos: Option<Vec3<i32>>,
+    /// The last render mode the renderer accepted, to revert to when applying
+    /// a new one fails
+    last_good_render_mode: RenderMode,
+    /// When the player last pressed or released an input, for the AFK logout
+    last_input: Instant,
+    /// Whether the window was focused last tick
+    focused: bool,
+    /// Smoothed heading shown on the compass, in degrees clockwise from north
+    compass_heading: f32,
+    /// Where the player first appeared in the session, the world spawn for a
+    /// character that was just loaded. Benchmarks are flown around it.
+    spawn_pos: Option<Vec3<f32>>,
+    benchmark: Option<Benchmark>,
+    spectate: Option<Spectate>,
+    buffered_primary: Option<BufferedInput>,
+    buffered_secondary: Option<BufferedInput>,
+    show_inspect: bool,
+    /// Notifications held back while do not disturb is on
+    dnd_notifications: Option<VecDeque<Notification>>,
+    /// Entity kept targeted and faced until the lock is released
+    locked_target: Option<specs::Entity>,
+    last_slot_sfx: Option<Instant>,
+    screenshot_pending: bool,
+    show_first_person_model: bool,
+    close_requested: bool,
+    quit_after_logout: bool,
+    /// Whether the view distance was last changed with the toggle key, to
+    /// tell the player if the server limits it
+    view_distance_toggled: bool,
+    /// Loot messages held back to be merged: the name of the item, how many
+    /// were picked up, and when the last one was
+    loot_batches: Vec<(String, u32, Instant)>,
+    /// When a pickup failed, while its message is held back in case a pickup
+    /// succeeds right after
+    pending_collect_fail: Option<Instant>,
+    /// Where to go once the session summary is dismissed, and when it was
+    /// shown
+    leaving: Option<(LeaveTo, Instant)>,
+    /// When leaving the group was asked to be confirmed
+    leave_group_requested: Option<Instant>,
+    /// Whether the ring showing the interact range is drawn
+    show_interact_range: bool,
+    /// Positions and colors of the beams drawn over rare dropped items
+    loot_beams: Vec<LootBeam>,
+    /// The ray used to pick what is under the crosshair, if it is drawn
+    targeting_ray: Option<TargetingRay>,
+    /// Hotbar slot of the consumable used with the quick use key, and its name
+    /// to tell the player what ran out
+    quick_consumable: Option<(usize, String)>,
+    last_quick_consume: Option<Instant>,
+    /// Hotbar slots picked for automatic use that were found empty and already
+    /// warned about
+    auto_consume_empty: BTreeSet<usize>,
+    /// While swimming, whether the weapon should be wielded again on leaving
+    /// the water
+    wield_after_swim: Option<bool>,
+    /// When the player died, while they are dead
+    died_at: Option<Instant>,
+    /// Whether a respawn was asked for since dying, by hand or on its own
+    respawn_requested: bool,
 }
 
 /// Represents an active game session (i.e., the one being played).
@@ -68,9 +230,21 @@ impl SessionState {
             &*client.borrow(),
             &global_state.settings,
         );
+        // Per-character overrides take precedence over the global graphics settings
+        let (fov_override, gamma_override) = {
+            let client = client.borrow();
+            client
+                .active_character_id
+                .and_then(|character_id| {
+                    global_state
+                        .profile
+                        .get_character_profile(&client.server_info.name, character_id)
+                })
+                .map_or((None, None), |character| (character.fov, character.gamma))
+        };
         scene
             .camera_mut()
-            .set_fov_deg(global_state.settings.graphics.fov);
+            .set_fov_deg(fov_override.unwrap_or(global_state.settings.graphics.fov));
         let hud = Hud::new(global_state, &client.borrow());
         let voxygen_i18n = VoxygenLocalization::load_expect(&i18n_asset_key(
             &global_state.settings.language.selected_language,
@@ -90,11 +264,355 @@ impl SessionState {
             walk_forward_dir,
             walk_right_dir,
             freefly_vel: Vec3::zero(),
+            freefly_speed_mul: 1.0,
             free_look: false,
+            free_look_latched: false,
+            last_free_look_tap: None,
+            last_move_tap: None,
+            dodge_key: None,
             auto_walk: false,
+            walking: false,
             is_aiming: false,
+            aim_camera_shift: false,
             target_entity: None,
             selected_entity: None,
+            replay: Replay::default(),
+            camera_path: CameraPath::new(global_state.settings.gameplay.camera_path_seconds),
+            combat_camera: false,
+            was_in_combat: false,
+            last_manual_zoom: None,
+            move_destination: None,
+            action_log: ActionLog::new(global_state.settings.gameplay.log_client_actions),
+            chat_reader: ChatReader::default(),
+            selected_interactable: None,
+            input_compensation: Duration::default(),
+            fov_override,
+            gamma_override,
+            build_ghost: None,
+            line_tool: false,
+            build_line_start: None,
+            build_grid: false,
+            build_grid_pos: None,
+            last_build_action: None,
+            inspect_block: false,
+            inspected_block: None,
+            scout_overlay: false,
+            group_coordinates: false,
+            session_stats: SessionStats::default(),
+            placement_retry_pending: false,
+            placement_retried_pos: None,
+            last_good_render_mode: global_state.window.renderer().render_mode().clone(),
+            last_input: Instant::now(),
+            focused: true,
+            compass_heading: 0.0,
+            spawn_pos: None,
+            benchmark: None,
+            spectate: None,
+            buffered_primary: None,
+            buffered_secondary: None,
+            show_inspect: false,
+            dnd_notifications: None,
+            locked_target: None,
+            last_slot_sfx: None,
+            screenshot_pending: false,
+            show_first_person_model: true,
+            close_requested: false,
+            quit_after_logout: false,
+            view_distance_toggled: false,
+            loot_batches: Vec::new(),
+            pending_collect_fail: None,
+            leaving: None,
+            leave_group_requested: None,
+            show_interact_range: false,
+            loot_beams: Vec::new(),
+            targeting_ray: None,
+            quick_consumable: None,
+            last_quick_consume: None,
+            auto_consume_empty: BTreeSet::new(),
+            wield_after_swim: None,
+            died_at: None,
+            respawn_requested: false,
+        }
+    }
+
+    fn use_slot(&mut self, slot: comp::slot::Slot) {
+        let mut client = self.client.borrow_mut();
+        // Debounce the activation sound so that mashing slots doesn't stack
+        // sounds on top of each other
+        let debounced = self
+            .last_slot_sfx
+            .map_or(false, |t| t.elapsed() < SLOT_SFX_DEBOUNCE);
+        if let (comp::slot::Slot::Inventory(i), false) = (slot, debounced) {
+            let sfx_event = client
+                .inventories()
+                .get(client.entity())
+                .and_then(|inv| inv.get(i))
+                .and_then(SfxEvent::from_used_item);
+            if let Some(sfx_event) = sfx_event {
+                client
+                    .state()
+                    .ecs()
+                    .read_resource::<EventBus<SfxEventItem>>()
+                    .emit_now(SfxEventItem::at_player_position(sfx_event));
+                self.last_slot_sfx = Some(Instant::now());
+            }
+        }
+        self.action_log.log(&client, "use_slot", slot);
+        client.use_slot(slot);
+    }
+
+    /// Use the consumables picked for when health or energy runs low, sharing
+    /// the cooldown of the quick use key. A slot found empty is warned about
+    /// once, until it holds a consumable again.
+    fn auto_consume(&mut self, settings: &Settings) {
+        let (health, energy) = {
+            let client = self.client.borrow();
+            let ecs = client.state().ecs();
+            let fraction = |current: u32, maximum: u32| current as f32 / maximum.max(1) as f32;
+            let health = ecs
+                .read_storage::<comp::Stats>()
+                .get(client.entity())
+                .filter(|stats| !stats.is_dead)
+                .map(|stats| fraction(stats.health.current(), stats.health.maximum()));
+            let energy = ecs
+                .read_storage::<comp::Energy>()
+                .get(client.entity())
+                .map(|energy| fraction(energy.current(), energy.maximum()));
+            (health, energy)
+        };
+        let gameplay = &settings.gameplay;
+        let low = [
+            (
+                gameplay.auto_consume_health_slot,
+                health,
+                gameplay.auto_consume_health_threshold,
+            ),
+            (
+                gameplay.auto_consume_energy_slot,
+                energy,
+                gameplay.auto_consume_energy_threshold,
+            ),
+        ];
+        for &(slot, fraction, threshold) in &low {
+            let slot = match slot.and_then(|slot| slot.checked_sub(1)) {
+                Some(slot) if fraction.map_or(false, |fraction| fraction < threshold) => slot,
+                _ => continue,
+            };
+            let contents = self.hud.hotbar().slots.get(slot).copied().flatten();
+            match hotbar_consumable(&self.client.borrow(), contents) {
+                Some((index, _, _)) => {
+                    self.auto_consume_empty.remove(&slot);
+                    let on_cooldown = self
+                        .last_quick_consume
+                        .map_or(false, |t| t.elapsed() < QUICK_CONSUME_COOLDOWN);
+                    if !on_cooldown {
+                        self.last_quick_consume = Some(Instant::now());
+                        self.use_slot(comp::slot::Slot::Inventory(index));
+                    }
+                },
+                None => {
+                    if self.auto_consume_empty.insert(slot) {
+                        self.hud.new_message(ChatMsg {
+                            chat_type: ChatType::CommandError,
+                            message: self
+                                .voxygen_i18n
+                                .get("hud.chat.auto_consume_empty")
+                                .replace("{slot}", &(slot + 1).to_string()),
+                        });
+                    }
+                },
+            }
+        }
+    }
+
+    /// Sheathe the weapon on starting to swim, and wield it again once out of
+    /// the water if it was sheathed for swimming. Dying forgets about it.
+    fn sheathe_while_swimming(&mut self, settings: &Settings) {
+        if !settings.gameplay.sheathe_while_swimming {
+            self.wield_after_swim = None;
+            return;
+        }
+        let (in_fluid, wielding, dead) = {
+            let client = self.client.borrow();
+            let ecs = client.state().ecs();
+            (
+                ecs.read_storage::<comp::PhysicsState>()
+                    .get(client.entity())
+                    .and_then(|p| p.in_fluid),
+                ecs.read_storage::<comp::CharacterState>()
+                    .get(client.entity())
+                    .map_or(false, |cs| cs.is_wield()),
+                ecs.read_storage::<comp::Stats>()
+                    .get(client.entity())
+                    .map_or(false, |stats| stats.is_dead),
+            )
+        };
+        if dead {
+            self.wield_after_swim = None;
+            return;
+        }
+        let (wield_after_swim, toggle) = swim_wield(self.wield_after_swim, in_fluid, wielding);
+        self.wield_after_swim = wield_after_swim;
+        if toggle {
+            self.toggle_wield();
+        }
+    }
+
+    /// Respawn once the player has been dead for the delay in the settings,
+    /// unless a respawn was already asked for since dying.
+    fn auto_respawn(&mut self, settings: &Settings) {
+        let dead = {
+            let client = self.client.borrow();
+            client
+                .state()
+                .ecs()
+                .read_storage::<comp::Stats>()
+                .get(client.entity())
+                .map_or(false, |stats| stats.is_dead)
+        };
+        if !dead {
+            self.died_at = None;
+            self.respawn_requested = false;
+            return;
+        }
+        let died_at = *self.died_at.get_or_insert_with(Instant::now);
+        let due = settings
+            .gameplay
+            .auto_respawn_delay_seconds
+            .map_or(false, |delay| {
+                died_at.elapsed() >= Duration::from_secs(u64::from(delay))
+            });
+        if due && !self.respawn_requested {
+            self.respawn_requested = true;
+            self.action_log.log(&self.client.borrow(), "respawn", ());
+            self.client.borrow_mut().respawn();
+        }
+    }
+
+    fn toggle_wield(&mut self) {
+        self.action_log
+            .log(&self.client.borrow(), "toggle_wield", ());
+        self.client.borrow_mut().toggle_wield();
+    }
+
+    /// Switch between the combat and peaceful camera distance as the player
+    /// wields or puts away their weapon, unless they recently zoomed by hand.
+    fn auto_combat_camera(&mut self, settings: &Settings) {
+        let in_combat = {
+            let client = self.client.borrow();
+            client
+                .state()
+                .ecs()
+                .read_storage::<comp::CharacterState>()
+                .get(client.entity())
+                .map_or(false, |cs| cs.is_wield())
+        };
+        let changed = in_combat != self.was_in_combat;
+        self.was_in_combat = in_combat;
+        let zoomed_recently = self
+            .last_manual_zoom
+            .map_or(false, |t| t.elapsed() < MANUAL_ZOOM_GRACE);
+        if settings.gameplay.auto_combat_camera && changed && !zoomed_recently {
+            self.combat_camera = in_combat;
+            self.apply_camera_distance(settings);
+        }
+    }
+
+    /// Move the camera to the distance of the selected preset. The camera
+    /// eases there on its own.
+    fn apply_camera_distance(&mut self, settings: &Settings) {
+        let camera = self.scene.camera_mut();
+        if camera.get_mode() == CameraMode::ThirdPerson {
+            camera.set_distance(if self.combat_camera {
+                settings.gameplay.combat_camera_distance
+            } else {
+                settings.gameplay.peaceful_camera_distance
+            });
+        }
+    }
+
+    /// Show a summary of the session before going to `to`. Involuntary
+    /// disconnects skip this and leave right away.
+    fn show_leave_summary(&mut self, to: LeaveTo) {
+        if self.leaving.is_none() {
+            self.leaving = Some((to, Instant::now()));
+            self.hud.logout_summary(true);
+        }
+    }
+
+    /// Leave the game once the session summary was shown.
+    fn leave(&mut self) {
+        if let Some((to, _)) = self.leaving.take() {
+            self.hud.logout_summary(false);
+            match to {
+                LeaveTo::CharacterSelection => self.client.borrow_mut().request_remove_character(),
+                LeaveTo::MainMenu => self.client.borrow_mut().request_logout(),
+            }
+        }
+    }
+
+    /// Add the stats of this session to the character's lifetime totals, if
+    /// enabled. Should only be called once, when the session ends.
+    fn save_lifetime_stats(&self, global_state: &mut GlobalState) {
+        if !global_state.settings.gameplay.save_lifetime_stats {
+            return;
+        }
+        let client = self.client.borrow();
+        if let Some(character_id) = client.active_character_id {
+            global_state
+                .profile
+                .get_character_profile_mut(&client.server_info.name, character_id)
+                .lifetime_stats
+                .merge(&self.session_stats);
+            global_state.profile.save_to_file_warn();
+        }
+    }
+
+    /// Switch to the chat channel the character last used. When not in a
+    /// group anymore, group chat falls back to saying things nearby. Faction
+    /// membership isn't known to the client, the server reports it instead.
+    fn restore_chat_channel(&mut self, global_state: &GlobalState) {
+        let mut client = self.client.borrow_mut();
+        let channel = client.active_character_id.and_then(|character_id| {
+            global_state
+                .profile
+                .get_character_profile(&client.server_info.name, character_id)
+                .and_then(|character| character.chat_channel)
+        });
+        let channel = match channel {
+            Some(ChatChannel::Group) if client.group_info().is_none() => {
+                self.hud.new_message(ChatMsg {
+                    chat_type: ChatType::CommandInfo,
+                    message: self
+                        .voxygen_i18n
+                        .get("hud.chat.chat_channel_no_group")
+                        .to_string(),
+                });
+                ChatChannel::Say
+            },
+            Some(channel) => channel,
+            None => return,
+        };
+        client.send_chat(format!("/{}", channel.command().keyword()));
+    }
+
+    /// Remember the channel `msg` switches chat to as the character's, if it is
+    /// a command that switches channels.
+    fn remember_chat_channel(&self, global_state: &mut GlobalState, msg: &str) {
+        let channel = msg
+            .strip_prefix('/')
+            .and_then(|cmd| cmd.split_whitespace().next())
+            .and_then(|keyword| keyword.parse::<ChatCommand>().ok())
+            .and_then(ChatChannel::switched_to_by);
+        let client = self.client.borrow();
+        if let (Some(channel), Some(character_id)) = (channel, client.active_character_id) {
+            let character = global_state
+                .profile
+                .get_character_profile_mut(&client.server_info.name, character_id);
+            if character.chat_channel != Some(channel) {
+                character.chat_channel = Some(channel);
+                global_state.profile.save_to_file_warn();
+            }
         }
     }
 
@@ -104,6 +622,145 @@ impl SessionState {
         self.key_state.auto_walk = false;
     }
 
+    /// Roll when a movement key is pressed twice within `window`, holding the
+    /// roll until that key is let go. Does nothing without a `window`.
+    fn double_tap_dodge(&mut self, input: GameInput, state: bool, window: Option<Duration>) {
+        if state {
+            match (self.last_move_tap.take(), window) {
+                (Some((last, tapped)), Some(window))
+                    if last == input && tapped.elapsed() <= window =>
+                {
+                    self.inputs.roll.set_state(true);
+                    self.dodge_key = Some(input);
+                },
+                _ => self.last_move_tap = Some((input, Instant::now())),
+            }
+        } else if self.dodge_key == Some(input) {
+            self.inputs.roll.set_state(false);
+            self.dodge_key = None;
+        }
+    }
+
+    /// Let go of every held input, as their releases won't arrive while the
+    /// window is in the background. Auto-walk is kept going if
+    /// `keep_auto_walk` is set.
+    fn release_held_inputs(&mut self, keep_auto_walk: bool) {
+        self.inputs = comp::ControllerInputs {
+            look_dir: self.inputs.look_dir,
+            ..Default::default()
+        };
+        self.key_state = KeyState {
+            auto_walk: self.key_state.auto_walk,
+            ..KeyState::default()
+        };
+        if !keep_auto_walk {
+            self.stop_auto_walk();
+        }
+    }
+
+    /// Whether the player may switch the camera to `mode`.
+    ///
+    /// Prevent accessing camera modes which aren't available in multiplayer
+    /// unless you are an admin. This is an easily bypassed clientside check.
+    /// The server should do its own filtering of which entities are sent to
+    /// clients to prevent abuse.
+    fn camera_mode_allowed(&self, mode: CameraMode) -> bool {
+        mode != CameraMode::Freefly || self.client.borrow().is_admin()
+    }
+
+    /// Switch the camera to `mode`, or back to third person if the player
+    /// isn't allowed to use it.
+    fn set_camera_mode(&mut self, mode: CameraMode) {
+        let mode = Some(mode)
+            .filter(|mode| self.camera_mode_allowed(*mode))
+            .unwrap_or(CameraMode::ThirdPerson);
+        self.scene.camera_mut().set_mode(mode);
+    }
+
+    /// Hand the camera back to the player where they were before the
+    /// benchmark and report the measured frame rates.
+    fn end_benchmark(&mut self, benchmark: Benchmark) {
+        let results = benchmark.finish();
+        self.scene
+            .camera_mut()
+            .set_mode(benchmark.prev_camera_mode());
+        let mut client = self.client.borrow_mut();
+        if let Some(view_distance) = benchmark.prev_view_distance() {
+            client.set_view_distance(view_distance);
+        }
+        if let Some(pos) = benchmark.prev_pos() {
+            client.send_chat(goto_command(pos));
+        }
+        self.hud.new_message(ChatMsg {
+            chat_type: ChatType::CommandInfo,
+            message: self
+                .voxygen_i18n
+                .get("hud.chat.benchmark_results")
+                .replace("{x}", &format!("{:.0}", results.origin.x))
+                .replace("{y}", &format!("{:.0}", results.origin.y))
+                .replace("{z}", &format!("{:.0}", results.origin.z))
+                .replace("{frames}", &results.frames.to_string())
+                .replace("{min}", &format!("{:.1}", results.min_fps))
+                .replace("{avg}", &format!("{:.1}", results.avg_fps))
+                .replace("{max}", &format!("{:.1}", results.max_fps)),
+        });
+    }
+
+    /// Handle a `/spectate [name]` chat command, which starts watching the
+    /// named player or stops watching without a name.
+    fn spectate_command(&mut self, name: &str) {
+        let name = name.trim();
+        let error = if !self.client.borrow().is_admin() {
+            Some("hud.chat.spectate_admin_only")
+        } else if name.is_empty() {
+            match self.spectate.take() {
+                Some(spectate) => {
+                    self.end_spectate(spectate, "hud.chat.spectate_stopped");
+                    None
+                },
+                None => Some("hud.chat.spectate_usage"),
+            }
+        } else {
+            if let Some(spectate) = self.spectate.take() {
+                self.scene
+                    .camera_mut()
+                    .set_mode(spectate.prev_camera_mode());
+            }
+            self.stop_auto_walk();
+            let camera = self.scene.camera_mut();
+            self.spectate = Some(Spectate::new(name.to_string(), camera.get_mode()));
+            camera.set_mode(CameraMode::Freefly);
+            self.hud.new_message(ChatMsg {
+                chat_type: ChatType::CommandInfo,
+                message: self
+                    .voxygen_i18n
+                    .get("hud.chat.spectate_searching")
+                    .replace("{name}", name),
+            });
+            None
+        };
+        if let Some(error) = error {
+            self.hud.new_message(ChatMsg {
+                chat_type: ChatType::CommandError,
+                message: self.voxygen_i18n.get(error).to_string(),
+            });
+        }
+    }
+
+    /// Hand the camera and character back to the player after spectating.
+    fn end_spectate(&mut self, spectate: Spectate, message_key: &str) {
+        self.scene
+            .camera_mut()
+            .set_mode(spectate.prev_camera_mode());
+        self.hud.new_message(ChatMsg {
+            chat_type: ChatType::CommandInfo,
+            message: self
+                .voxygen_i18n
+                .get(message_key)
+                .replace("{name}", spectate.name()),
+        });
+    }
+
     /// Tick the session (and the client attached to it).
     fn tick(
         &mut self,
@@ -114,11 +771,66 @@ impl SessionState {
         span!(_guard, "tick", "Session::tick");
         self.inputs.tick(dt);
 
+        let inputs = if global_state.settings.gameplay.prediction_compensation {
+            // Only pick up a new compensation while nothing is held, so the held
+            // durations seen by the server never jump backwards
+            if !any_input_pressed(&self.inputs) {
+                let max_compensation = Duration::from_millis(
+                    global_state
+                        .settings
+                        .gameplay
+                        .max_prediction_compensation_ms as u64,
+                );
+                let half_ping = Duration::from_secs_f64(
+                    self.client.borrow().get_ping_ms_rolling_avg().max(0.0) / 2000.0,
+                );
+                self.input_compensation = half_ping.min(max_compensation);
+            }
+            compensate_inputs(&self.inputs, self.input_compensation)
+        } else {
+            self.inputs.clone()
+        };
+
+        // Log out once the player has been away for too long, with a warning shortly
+        // before so they can cancel by pressing anything
+        const AFK_LOGOUT_WARNING: Duration = Duration::from_secs(60);
+        let afk_logout_minutes = global_state.settings.gameplay.afk_auto_logout_minutes;
+        if afk_logout_minutes > 0 {
+            let timeout = Duration::from_secs(afk_logout_minutes as u64 * 60);
+            let idle = self.last_input.elapsed();
+            if idle >= timeout {
+                info!("Logging out after being inactive for {:?}", idle);
+                self.hud.afk_logout_countdown(None);
+                self.last_input = Instant::now();
+                self.client.borrow_mut().request_logout();
+            } else {
+                self.hud.afk_logout_countdown(
+                    Some(timeout - idle)
+                        .filter(|remaining| *remaining <= AFK_LOGOUT_WARNING)
+                        .map(|remaining| remaining.as_secs()),
+                );
+            }
+        } else {
+            self.hud.afk_logout_countdown(None);
+        }
+
         let mut client = self.client.borrow_mut();
-        for event in client.tick(self.inputs.clone(), dt, crate::ecs::sys::add_local_systems)? {
+        if self.spawn_pos.is_none() {
+            self.spawn_pos = client
+                .state()
+                .read_storage::<Pos>()
+                .get(client.entity())
+                .map(|pos| pos.0);
+        }
+        for event in client.tick(inputs, dt, crate::ecs::sys::add_local_systems)? {
             match event {
                 client::Event::Chat(m) => {
-                    self.hud.new_message(m);
+                    if !global_state.chat_history.is_resent(&m) {
+                        self.chat_reader
+                            .read(&client, &global_state.settings.audio, &m);
+                        global_state.chat_history.record(m.clone());
+                        self.hud.new_message(m);
+                    }
                 },
                 client::Event::InventoryUpdated(inv_event) => {
                     let sfx_event = SfxEvent::from(&inv_event);
@@ -130,19 +842,57 @@ impl SessionState {
 
                     match inv_event {
                         InventoryUpdateEvent::CollectFailed => {
-                            self.hud.new_message(ChatMsg {
-                                message: self.voxygen_i18n.get("hud.chat.loot_fail").to_string(),
-                                chat_type: ChatType::CommandError,
-                            });
+                            // The event has no reason, but with a full inventory the failure
+                            // can't be a race with another pickup
+                            let full = client
+                                .inventories()
+                                .get(client.entity())
+                                .map_or(false, |inv| inv.is_full());
+                            if full {
+                                self.hud.new_message(ChatMsg {
+                                    message: self
+                                        .voxygen_i18n
+                                        .get("hud.chat.loot_fail")
+                                        .to_string(),
+                                    chat_type: ChatType::CommandError,
+                                });
+                            } else {
+                                self.pending_collect_fail.get_or_insert_with(Instant::now);
+                            }
                         },
                         InventoryUpdateEvent::Collected(item) => {
-                            self.hud.new_message(ChatMsg {
-                                message: self
-                                    .voxygen_i18n
-                                    .get("hud.chat.loot_msg")
-                                    .replace("{item}", item.name()),
-                                chat_type: ChatType::Loot,
-                            });
+                            self.pending_collect_fail = None;
+                            let gameplay = &global_state.settings.gameplay;
+                            // Rare items are always announced on their own
+                            let rare = item.quality() >= RARE_LOOT_QUALITY;
+                            let shown = item.quality() >= gameplay.loot_msg_min_quality;
+                            if rare || (shown && !gameplay.batch_loot_msgs) {
+                                let count = if gameplay.loot_msg_show_count {
+                                    item.amount()
+                                } else {
+                                    1
+                                };
+                                self.hud.new_message(ChatMsg {
+                                    message: loot_msg(&self.voxygen_i18n, item.name(), count),
+                                    chat_type: ChatType::Loot,
+                                });
+                            } else if shown {
+                                let batch = self
+                                    .loot_batches
+                                    .iter_mut()
+                                    .find(|(name, _, _)| name == item.name());
+                                match batch {
+                                    Some((_, count, last)) => {
+                                        *count += item.amount();
+                                        *last = Instant::now();
+                                    },
+                                    None => self.loot_batches.push((
+                                        item.name().to_string(),
+                                        item.amount(),
+                                        Instant::now(),
+                                    )),
+                                }
+                            }
                         },
                         _ => {},
                     };
@@ -161,6 +911,9 @@ impl SessionState {
                         chat_type: ChatType::CommandError,
                         message,
                     });
+                    if time > 0 {
+                        self.hud.disconnect_countdown(time);
+                    }
                 },
                 client::Event::Kicked(reason) => {
                     global_state.info_message = Some(format!(
@@ -170,17 +923,123 @@ impl SessionState {
                     ));
                     return Ok(TickAction::Disconnect);
                 },
-                client::Event::Notification(n) => {
-                    self.hud.new_notification(n);
+                client::Event::Notification(n) => match &mut self.dnd_notifications {
+                    Some(queue) => {
+                        if queue.len() >= MAX_DND_NOTIFICATIONS {
+                            queue.pop_front();
+                        }
+                        queue.push_back(n);
+                        self.hud.dnd_queued(Some(queue.len()));
+                    },
+                    None => self.hud.new_notification(n),
                 },
                 client::Event::SetViewDistance(vd) => {
-                    global_state.settings.graphics.view_distance = vd;
+                    let graphics = &mut global_state.settings.graphics;
+                    graphics.view_distance = vd;
+                    // The server only sends this to enforce its maximum
+                    graphics.high_view_distance = graphics.high_view_distance.min(vd);
+                    graphics.low_view_distance = graphics.low_view_distance.min(vd);
                     global_state.settings.save_to_file_warn();
+                    if mem::take(&mut self.view_distance_toggled) {
+                        self.hud.new_message(ChatMsg {
+                            chat_type: ChatType::CommandInfo,
+                            message: self
+                                .voxygen_i18n
+                                .get("hud.chat.view_distance_limited")
+                                .replace("{distance}", &vd.to_string()),
+                        });
+                    }
                 },
                 client::Event::Outcome(outcome) => outcomes.push(outcome),
+                client::Event::BlockPlacementFailed(pos, error) => {
+                    // Contention is transient, so try again once at the recomputed build
+                    // position. Anything else won't go away by retrying.
+                    if error == BlockPlacementError::Contended
+                        && global_state.settings.gameplay.retry_block_placement
+                        && self.placement_retried_pos != Some(pos)
+                    {
+                        self.placement_retry_pending = true;
+                    } else {
+                        self.placement_retried_pos = None;
+                        self.session_stats.blocks_placed =
+                            self.session_stats.blocks_placed.saturating_sub(1);
+                        let key = match error {
+                            BlockPlacementError::NoPermission => {
+                                "hud.chat.block_placement_no_permission"
+                            },
+                            BlockPlacementError::OutOfBounds => {
+                                "hud.chat.block_placement_out_of_bounds"
+                            },
+                            BlockPlacementError::Contended => "hud.chat.block_placement_contended",
+                        };
+                        self.hud.new_message(ChatMsg {
+                            message: self.voxygen_i18n.get(key).to_string(),
+                            chat_type: ChatType::CommandError,
+                        });
+                    }
+                },
+            }
+        }
+        self.chat_reader.maintain();
+
+        // Accept group invites from friends straight away, unless already in a group
+        if global_state
+            .settings
+            .gameplay
+            .auto_accept_group_invites_from_friends
+            && client.group_info().is_none()
+        {
+            let friend = client
+                .group_invite()
+                .and_then(|(inviter, _, _)| client.player_list.get(&inviter))
+                .map(|info| info.player_alias.clone())
+                .filter(|alias| {
+                    global_state
+                        .settings
+                        .gameplay
+                        .friends
+                        .iter()
+                        .any(|friend| friend.eq_ignore_ascii_case(alias))
+                });
+            if let Some(friend) = friend {
+                client.accept_group_invite();
+                self.hud.new_message(ChatMsg {
+                    message: self
+                        .voxygen_i18n
+                        .get("hud.group.auto_accepted_invite")
+                        .replace("{name}", &friend),
+                    chat_type: ChatType::CommandInfo,
+                });
             }
         }
 
+        // Post the failed pickup once no pickup succeeded for a while
+        let collect_fail_grace =
+            Duration::from_millis(global_state.settings.gameplay.collect_fail_grace_ms as u64);
+        if self
+            .pending_collect_fail
+            .map_or(false, |failed| failed.elapsed() >= collect_fail_grace)
+        {
+            self.pending_collect_fail = None;
+            self.hud.new_message(ChatMsg {
+                message: self.voxygen_i18n.get("hud.chat.loot_fail").to_string(),
+                chat_type: ChatType::CommandError,
+            });
+        }
+
+        // Post merged loot messages once no more of the item was picked up for a while
+        let (hud, voxygen_i18n) = (&mut self.hud, &self.voxygen_i18n);
+        self.loot_batches.retain(|(name, count, last)| {
+            if last.elapsed() < LOOT_BATCH_WINDOW {
+                return true;
+            }
+            hud.new_message(ChatMsg {
+                message: loot_msg(voxygen_i18n, name, *count),
+                chat_type: ChatType::Loot,
+            });
+            false
+        });
+
         Ok(TickAction::Continue)
     }
 
@@ -189,10 +1048,38 @@ impl SessionState {
 }
 
 impl PlayState for SessionState {
-    fn enter(&mut self, global_state: &mut GlobalState, _: Direction) {
+    fn enter(&mut self, global_state: &mut GlobalState, direction: Direction) {
         // Trap the cursor.
         global_state.window.grab_cursor(true);
 
+        // Bring back the chat from before a reconnect
+        if let Direction::Forwards = direction {
+            let client = self.client.borrow();
+            let previous = global_state
+                .chat_history
+                .resume(&client.server_info.name, client.active_character_id);
+            if !previous.is_empty() {
+                for msg in previous {
+                    self.hud.new_message(msg);
+                }
+                let marker = ChatMsg {
+                    chat_type: ChatType::CommandInfo,
+                    message: self.voxygen_i18n.get("hud.chat.reconnected").to_string(),
+                };
+                global_state.chat_history.record(marker.clone());
+                self.hud.new_message(marker);
+            }
+            if global_state.settings.disable_settings_autosave {
+                self.hud.new_message(ChatMsg {
+                    chat_type: ChatType::CommandInfo,
+                    message: self
+                        .voxygen_i18n
+                        .get("hud.chat.settings_autosave_disabled")
+                        .to_string(),
+                });
+            }
+        }
+
         self.client.borrow_mut().clear_terrain();
 
         // Send startup commands to the server
@@ -201,15 +1088,43 @@ impl PlayState for SessionState {
                 self.client.borrow_mut().send_chat(cmd.to_string());
             }
         }
+
+        if let Direction::Forwards = direction {
+            self.restore_chat_channel(global_state);
+        }
     }
 
     fn tick(&mut self, global_state: &mut GlobalState, events: Vec<Event>) -> PlayStateResult {
         span!(_guard, "tick", "<Session as PlayState>::tick");
+
+        // Swap to the bindings of the input device in use, once the player has
+        // stuck with it for a moment
+        if global_state.settings.controls_per_input_device {
+            let (device, since) = global_state.window.last_input_device();
+            if device != global_state.settings.input_device
+                && since.elapsed() >= INPUT_DEVICE_SWITCH_DELAY
+            {
+                global_state.settings.switch_input_device(device);
+                global_state
+                    .window
+                    .set_controller_settings(&global_state.settings.controller);
+                global_state.settings.save_to_file_warn();
+            }
+        }
         // NOTE: Not strictly necessary, but useful for hotloading translation changes.
         self.voxygen_i18n = VoxygenLocalization::load_expect(&i18n_asset_key(
             &global_state.settings.language.selected_language,
         ));
 
+        // The frame rendered without the HUD was captured at the start of this one
+        if self.screenshot_pending {
+            self.screenshot_pending = false;
+            self.hud.hide_for_screenshot(false);
+        }
+        global_state
+            .window
+            .set_delegate_screenshots(global_state.settings.gameplay.hide_hud_in_screenshots);
+
         // TODO: can this be a method on the session or are there borrowcheck issues?
         let (client_in_game, client_registered) = {
             let client = self.client.borrow();
@@ -238,7 +1153,7 @@ impl PlayState for SessionState {
             let focus_off = focus_pos.map(|e| e.trunc());
             let cam_pos = cam_pos + focus_off;
 
-            let (is_aiming, aim_dir_offset) = {
+            let (is_aiming, aim_camera_shift, aim_dir_offset) = {
                 let client = self.client.borrow();
                 let is_aiming = client
                     .state()
@@ -247,23 +1162,77 @@ impl PlayState for SessionState {
                     .map(|cs| cs.is_aimed())
                     .unwrap_or(false);
 
-                (
-                    is_aiming,
-                    if is_aiming && self.scene.camera().get_mode() == CameraMode::ThirdPerson {
-                        Vec3::unit_z() * 0.05
-                    } else {
-                        Vec3::zero()
-                    },
-                )
+                // Some weapon categories can be configured to not shift the camera while
+                // aiming. Missing categories default to shifting the camera.
+                let aim_camera_shift = is_aiming
+                    && client
+                        .state()
+                        .read_storage::<comp::Loadout>()
+                        .get(client.entity())
+                        .and_then(|loadout| loadout.active_item.as_ref())
+                        .and_then(|item_config| match item_config.item.kind() {
+                            ItemKind::Tool(tool) => Some(ToolCategory::from(&tool.kind)),
+                            _ => None,
+                        })
+                        .and_then(|category| {
+                            global_state
+                                .settings
+                                .gameplay
+                                .aim_camera_shift
+                                .get(&category)
+                                .copied()
+                        })
+                        .unwrap_or(true);
+
+                let third_person = self.scene.camera().get_mode() == CameraMode::ThirdPerson;
+                let mut aim_dir_offset = if aim_camera_shift && third_person {
+                    Vec3::unit_z() * 0.05
+                } else {
+                    Vec3::zero()
+                };
+                // Turn towards the side the camera is on, so that aim converges on the
+                // crosshair at a typical fighting distance
+                if third_person {
+                    aim_dir_offset += Vec3::from(self.scene.camera().right_xy())
+                        * (self.scene.shoulder_offset() / AIM_CONVERGENCE_DISTANCE);
+                }
+
+                (is_aiming, aim_camera_shift, aim_dir_offset)
             };
             self.is_aiming = is_aiming;
+            self.aim_camera_shift = aim_camera_shift;
 
             // Check to see whether we're aiming at anything
-            let (build_pos, select_pos, target_entity) =
-                under_cursor(&self.client.borrow(), cam_pos, cam_dir);
+            let range = interaction_range(&global_state.settings, &self.client.borrow());
+            let (build_pos, select_pos, target_entity, targeting_ray) =
+                under_cursor(&self.client.borrow(), cam_pos, cam_dir, range);
+            self.targeting_ray =
+                targeting_ray.filter(|_| show_targeting_ray(&global_state.settings));
             // Throw out distance info, it will be useful in the future
             self.target_entity = target_entity.map(|x| x.0);
 
+            // A locked target overrides whatever is under the cursor
+            let target_lock_dir = match self.locked_target {
+                Some(target) => match target_lock_dir(&self.client.borrow(), target) {
+                    Some(dir) => {
+                        self.target_entity = Some(target);
+                        Some(dir)
+                    },
+                    None => {
+                        self.locked_target = None;
+                        self.hud.new_message(ChatMsg {
+                            chat_type: ChatType::CommandInfo,
+                            message: self
+                                .voxygen_i18n
+                                .get("hud.chat.target_lock_lost")
+                                .to_string(),
+                        });
+                        None
+                    },
+                },
+                None => None,
+            };
+
             let can_build = self
                 .client
                 .borrow()
@@ -272,6 +1241,68 @@ impl PlayState for SessionState {
                 .get(self.client.borrow().entity())
                 .is_some();
 
+            // The roll key picks blocks while building, so double taps don't roll either
+            let dodge_window = (global_state.settings.gameplay.double_tap_dodge && !can_build)
+                .then(|| {
+                    Duration::from_millis(global_state.settings.gameplay.double_tap_dodge_ms as u64)
+                });
+
+            let attack_recovering = self
+                .client
+                .borrow()
+                .state()
+                .read_storage::<comp::CharacterState>()
+                .get(self.client.borrow().entity())
+                .map_or(false, |cs| cs.is_recovering());
+
+            // Retry a placement the server rejected because of contention. If there is
+            // nowhere to place it anymore, it failed after all.
+            if std::mem::take(&mut self.placement_retry_pending) {
+                if let Some(build_pos) = build_pos.filter(|_| can_build) {
+                    let mut client = self.client.borrow_mut();
+                    self.action_log
+                        .log(&client, "place_block", (build_pos, self.selected_block));
+                    client.place_block(build_pos, self.selected_block);
+                    self.placement_retried_pos = Some(build_pos);
+                } else {
+                    self.session_stats.blocks_placed =
+                        self.session_stats.blocks_placed.saturating_sub(1);
+                    self.hud.new_message(ChatMsg {
+                        message: self
+                            .voxygen_i18n
+                            .get("hud.chat.block_placement_contended")
+                            .to_string(),
+                        chat_type: ChatType::CommandError,
+                    });
+                }
+            }
+
+            // Preview where the selected block would be placed, or the whole line while
+            // one is being placed
+            if !can_build {
+                self.build_line_start = None;
+            }
+            self.build_ghost = build_pos
+                .filter(|_| can_build)
+                .map(|pos| match self.build_line_start {
+                    Some(start) => build_line(&self.client.borrow(), start, pos, range),
+                    None => vec![pos],
+                })
+                .map(|cells| (cells, self.selected_block));
+            self.build_grid_pos = select_pos.filter(|_| can_build && self.build_grid);
+            // Looked up every tick so the readout follows the cursor
+            self.inspected_block = self.inspect_block.then(|| {
+                let client = self.client.borrow();
+                select_pos.and_then(|pos| Some((pos, *client.state().terrain().get(pos).ok()?)))
+            });
+
+            // Show the interact range only while there is something in it, to avoid clutter
+            self.show_interact_range = global_state.settings.gameplay.show_interact_range
+                && !nearby_interactables(&self.client.borrow()).is_empty();
+
+            // Found again every tick, so beams disappear as soon as their item is picked up
+            self.loot_beams = loot_beams(&self.client.borrow(), &global_state.settings);
+
             // Only highlight collectables
             self.scene.set_select_pos(select_pos.filter(|sp| {
                 self.client
@@ -283,6 +1314,59 @@ impl PlayState for SessionState {
                     .unwrap_or(false)
             }));
 
+            // If an interactable was picked by cycling through them, highlight it instead
+            // of what is under the cursor. Drop it once it goes out of range.
+            if let Some(interactable) = self.selected_interactable {
+                if nearby_interactables(&self.client.borrow()).contains(&interactable) {
+                    match interactable {
+                        Interactable::Block(pos) => {
+                            self.scene.set_select_pos(Some(pos));
+                            self.target_entity = None;
+                        },
+                        Interactable::Entity(entity) => {
+                            self.scene.set_select_pos(None);
+                            self.target_entity = Some(entity);
+                        },
+                    }
+                } else {
+                    self.selected_interactable = None;
+                }
+            }
+
+            // Work out which binding acts when the target can be both mounted and looted,
+            // so that a single key press never does both
+            let multiple_interactions = self.target_entity.map_or(false, |entity| {
+                entity_interactions(&self.client.borrow(), entity) == (true, true)
+            });
+            self.hud.multiple_interactions(multiple_interactions);
+            let interact_prefers_mount = global_state.settings.gameplay.interact_prefers_mount;
+            let shared_binding = global_state.settings.controls.get_binding(GameInput::Mount)
+                == global_state
+                    .settings
+                    .controls
+                    .get_binding(GameInput::Interact);
+            let mount_defers_to_interact =
+                multiple_interactions && shared_binding && !interact_prefers_mount;
+            let interact_defers_to_mount =
+                multiple_interactions && shared_binding && interact_prefers_mount;
+            // Camera kick when attacking, in radians
+            let recoil = if global_state.paused() {
+                0.0
+            } else {
+                global_state
+                    .settings
+                    .gameplay
+                    .camera_recoil
+                    .max(0.0)
+                    .to_radians()
+            };
+
+            let focused = global_state.window.is_focused();
+            if self.focused && !focused {
+                self.release_held_inputs(global_state.settings.gameplay.auto_walk_while_unfocused);
+            }
+            self.focused = focused;
+
             // Handle window events.
             for event in events {
                 // Pass all events to the ui first.
@@ -290,32 +1374,144 @@ impl PlayState for SessionState {
                     continue;
                 }
 
+                // Gamepads keep sending input while the window is in the background, none
+                // of it should act on the character
+                if !focused && matches!(event, Event::InputUpdate(..) | Event::AnalogGameInput(_)) {
+                    continue;
+                }
+
+                // Player input means the player is back, clear any pending disconnect
+                // warning
+                if let Event::InputUpdate(_, true) = event {
+                    self.hud.clear_disconnect_countdown();
+                }
+                if let Event::InputUpdate(_, _) = event {
+                    self.last_input = Instant::now();
+                }
+                // Attacking needs the character to face where the camera looks
+                if let Event::InputUpdate(
+                    GameInput::Primary | GameInput::Secondary | GameInput::Ability3,
+                    true,
+                ) = event
+                {
+                    if self.free_look_latched
+                        && global_state.settings.gameplay.release_free_look_on_attack
+                    {
+                        self.free_look_latched = false;
+                        self.free_look = false;
+                        self.hud.free_look(false);
+                    }
+                }
+
                 match event {
                     Event::Close => {
-                        return PlayStateResult::Shutdown;
+                        // Closing again while asked, e.g. when the OS forces it, always quits
+                        // so players can't get stuck in the game
+                        if !global_state.settings.gameplay.confirm_on_close || self.close_requested
+                        {
+                            self.save_lifetime_stats(global_state);
+                            return PlayStateResult::Shutdown;
+                        }
+                        self.close_requested = true;
+                        self.hud.confirm_close();
                     },
                     Event::InputUpdate(GameInput::Primary, state) => {
+                        if state && !can_build && recoil > 0.0 {
+                            self.scene.add_recoil(recoil);
+                        }
                         // If we can build, use LMB to break blocks, if not, use it to attack
                         let mut client = self.client.borrow_mut();
                         if state && can_build {
-                            if let Some(select_pos) = select_pos {
+                            // In protected build mode the modifier has to be held to break
+                            // blocks, so misclicks don't destroy anything
+                            let protected = global_state.settings.gameplay.protected_build
+                                && !self.key_state.build_modifier;
+                            if let Some(select_pos) = select_pos.filter(|_| !protected) {
+                                self.action_log.log(&client, "remove_block", select_pos);
                                 client.remove_block(select_pos);
+                                self.session_stats.blocks_removed += 1;
+                                self.last_build_action = Some(BuildAction::Break);
                             }
-                        } else {
+                        } else if let Some(select_pos) = select_pos.filter(|_| {
+                            state
+                                && global_state.settings.gameplay.click_to_move
+                                && !is_wielding(&client)
+                        }) {
+                            // Walk on top of the clicked block
+                            let destination = select_pos.map(|e| e as f32 + 0.5) + Vec3::unit_z();
+                            self.move_destination = Some((destination, Instant::now()));
+                        } else if !buffer_input(
+                            &mut self.buffered_primary,
+                            state,
+                            attack_recovering,
+                            global_state.settings.gameplay.attack_input_buffer_ms,
+                        ) {
                             self.inputs.primary.set_state(state);
                         }
                     },
 
                     Event::InputUpdate(GameInput::Secondary, state) => {
                         self.inputs.secondary.set_state(false); // To be changed later on
+                        if state && !can_build && recoil > 0.0 {
+                            self.scene.add_recoil(recoil);
+                        }
 
                         let mut client = self.client.borrow_mut();
 
                         if state && can_build {
-                            if let Some(build_pos) = build_pos {
-                                client.place_block(build_pos, self.selected_block);
+                            let protected = global_state.settings.gameplay.protected_build
+                                && global_state.settings.gameplay.protected_build_placing
+                                && !self.key_state.build_modifier;
+                            if let Some(build_pos) = build_pos.filter(|_| !protected) {
+                                // Eyedropped blocks can be ones the server won't accept, but
+                                // admins may place anything
+                                let kind = self.selected_block.kind();
+                                if !is_placeable(kind) && !client.is_admin() {
+                                    self.hud.new_message(ChatMsg {
+                                        chat_type: ChatType::CommandError,
+                                        message: self
+                                            .voxygen_i18n
+                                            .get("hud.chat.block_not_placeable")
+                                            .replace("{block}", &kind.to_string()),
+                                    });
+                                } else if self.line_tool {
+                                    // Placed once the input is released
+                                    self.build_line_start = Some(build_pos);
+                                } else {
+                                    self.action_log.log(
+                                        &client,
+                                        "place_block",
+                                        (build_pos, self.selected_block),
+                                    );
+                                    client.place_block(build_pos, self.selected_block);
+                                    self.session_stats.blocks_placed += 1;
+                                    self.placement_retried_pos = None;
+                                    self.last_build_action = Some(BuildAction::Place);
+                                }
                             }
-                        } else {
+                        } else if let Some(start) = self.build_line_start.take() {
+                            // The block may have been swapped since the line was started
+                            let placeable =
+                                is_placeable(self.selected_block.kind()) || client.is_admin();
+                            if let Some(end) = build_pos.filter(|_| can_build && placeable) {
+                                for pos in build_line(&client, start, end, range) {
+                                    self.action_log.log(
+                                        &client,
+                                        "place_block",
+                                        (pos, self.selected_block),
+                                    );
+                                    client.place_block(pos, self.selected_block);
+                                    self.session_stats.blocks_placed += 1;
+                                }
+                                self.placement_retried_pos = None;
+                                self.last_build_action = Some(BuildAction::Place);
+                            }
+                        } else if !buffer_input(
+                            &mut self.buffered_secondary,
+                            state,
+                            attack_recovering,
+                            global_state.settings.gameplay.attack_input_buffer_ms,
+                        ) {
                             self.inputs.secondary.set_state(state);
                         }
                     },
@@ -340,9 +1536,20 @@ impl PlayState for SessionState {
                         self.stop_auto_walk();
                         self.key_state.respawn = state;
                         if state {
+                            self.respawn_requested = true;
+                            self.action_log.log(&self.client.borrow(), "respawn", ());
                             self.client.borrow_mut().respawn();
                         }
                     }
+                    Event::InputUpdate(GameInput::BuildModifier, state) => {
+                        self.key_state.build_modifier = state;
+                    },
+                    Event::InputUpdate(GameInput::FreeflyBoost, state) => {
+                        self.key_state.freefly_boost = state;
+                    },
+                    Event::InputUpdate(GameInput::FreeflyPrecision, state) => {
+                        self.key_state.freefly_precision = state;
+                    },
                     Event::InputUpdate(GameInput::Jump, state) => {
                         self.inputs.jump.set_state(state);
                     },
@@ -359,6 +1566,7 @@ impl PlayState for SessionState {
 
                         if state {
                             self.stop_auto_walk();
+                            self.action_log.log(&self.client.borrow(), "toggle_sit", ());
                             self.client.borrow_mut().toggle_sit();
                         }
                     }
@@ -368,6 +1576,8 @@ impl PlayState for SessionState {
                         self.key_state.toggle_dance = state;
                         if state {
                             self.stop_auto_walk();
+                            self.action_log
+                                .log(&self.client.borrow(), "toggle_dance", ());
                             self.client.borrow_mut().toggle_dance();
                         }
                     }
@@ -377,6 +1587,8 @@ impl PlayState for SessionState {
                         self.key_state.toggle_sneak = state;
                         if state {
                             self.stop_auto_walk();
+                            self.action_log
+                                .log(&self.client.borrow(), "toggle_sneak", ());
                             self.client.borrow_mut().toggle_sneak();
                         }
                     }
@@ -384,24 +1596,36 @@ impl PlayState for SessionState {
                         if state && global_state.settings.gameplay.stop_auto_walk_on_input {
                             self.stop_auto_walk();
                         }
+                        if state != self.key_state.up {
+                            self.double_tap_dodge(GameInput::MoveForward, state, dodge_window);
+                        }
                         self.key_state.up = state
                     },
                     Event::InputUpdate(GameInput::MoveBack, state) => {
                         if state && global_state.settings.gameplay.stop_auto_walk_on_input {
                             self.stop_auto_walk();
                         }
+                        if state != self.key_state.down {
+                            self.double_tap_dodge(GameInput::MoveBack, state, dodge_window);
+                        }
                         self.key_state.down = state
                     },
                     Event::InputUpdate(GameInput::MoveLeft, state) => {
                         if state && global_state.settings.gameplay.stop_auto_walk_on_input {
                             self.stop_auto_walk();
                         }
+                        if state != self.key_state.left {
+                            self.double_tap_dodge(GameInput::MoveLeft, state, dodge_window);
+                        }
                         self.key_state.left = state
                     },
                     Event::InputUpdate(GameInput::MoveRight, state) => {
                         if state && global_state.settings.gameplay.stop_auto_walk_on_input {
                             self.stop_auto_walk();
                         }
+                        if state != self.key_state.right {
+                            self.double_tap_dodge(GameInput::MoveRight, state, dodge_window);
+                        }
                         self.key_state.right = state
                     },
                     Event::InputUpdate(GameInput::Glide, state)
@@ -409,9 +1633,34 @@ impl PlayState for SessionState {
                     {
                         self.key_state.toggle_glide = state;
                         if state {
+                            self.action_log
+                                .log(&self.client.borrow(), "toggle_glide", ());
                             self.client.borrow_mut().toggle_glide();
                         }
                     }
+                    Event::InputUpdate(GameInput::StowGlider, true) => {
+                        // Only ever stows the glider, so spamming it can't deploy it again
+                        let is_gliding = {
+                            let client = self.client.borrow();
+                            client
+                                .state()
+                                .ecs()
+                                .read_storage::<comp::CharacterState>()
+                                .get(client.entity())
+                                .map_or(false, |cs| {
+                                    matches!(
+                                        cs,
+                                        comp::CharacterState::GlideWield
+                                            | comp::CharacterState::Glide
+                                    )
+                                })
+                        };
+                        if is_gliding {
+                            self.action_log
+                                .log(&self.client.borrow(), "toggle_glide", ());
+                            self.client.borrow_mut().toggle_glide();
+                        }
+                    },
                     Event::InputUpdate(GameInput::Climb, state) => {
                         self.key_state.climb_up = state;
                     },
@@ -426,7 +1675,12 @@ impl PlayState for SessionState {
                     {
                         self.key_state.toggle_wield = state;
                         if state {
-                            self.client.borrow_mut().toggle_wield();
+                            // Wielding by hand while swimming settles what to hold on leaving
+                            // the water
+                            if self.wield_after_swim.is_some() {
+                                self.wield_after_swim = Some(false);
+                            }
+                            self.toggle_wield();
                         }
                     }
                     Event::InputUpdate(GameInput::SwapLoadout, state)
@@ -434,21 +1688,34 @@ impl PlayState for SessionState {
                     {
                         self.key_state.swap_loadout = state;
                         if state {
+                            self.action_log
+                                .log(&self.client.borrow(), "swap_loadout", ());
                             self.client.borrow_mut().swap_loadout();
                         }
                     }
                     Event::InputUpdate(GameInput::ToggleLantern, true) => {
                         let mut client = self.client.borrow_mut();
                         if client.is_lantern_enabled() {
+                            self.action_log.log(&client, "disable_lantern", ());
                             client.disable_lantern();
                         } else {
+                            self.action_log.log(&client, "enable_lantern", ());
                             client.enable_lantern();
                         }
                     },
                     Event::InputUpdate(GameInput::Mount, true) => {
                         let mut client = self.client.borrow_mut();
                         if client.is_mounted() {
+                            self.action_log.log(&client, "unmount", ());
                             client.unmount();
+                        } else if mount_defers_to_interact {
+                            // Interact is bound to the same key and picks up
+                            // the target
+                        } else if let Some(target) =
+                            self.target_entity.filter(|_| multiple_interactions)
+                        {
+                            self.action_log.log(&client, "mount", target);
+                            client.mount(target);
                         } else {
                             let player_pos = client
                                 .state()
@@ -487,6 +1754,7 @@ impl PlayState for SessionState {
                                 }
 
                                 if let Some((mountee_entity, _)) = closest_mountable {
+                                    self.action_log.log(&client, "mount", mountee_entity);
                                     client.mount(mountee_entity);
                                 }
                             }
@@ -497,12 +1765,23 @@ impl PlayState for SessionState {
                     {
                         self.key_state.collect = state;
 
-                        if state {
+                        if state && multiple_interactions && interact_prefers_mount {
+                            // Mount the target rather than picking it up, unless Mount is
+                            // bound to the same key and already takes care of it
+                            if !interact_defers_to_mount {
+                                if let Some(target) = self.target_entity {
+                                    self.action_log.log(&self.client.borrow(), "mount", target);
+                                    self.client.borrow_mut().mount(target);
+                                }
+                            }
+                        } else if state {
                             let mut client = self.client.borrow_mut();
 
                             // Collect terrain sprites
                             if let Some(select_pos) = self.scene.select_pos() {
+                                self.action_log.log(&client, "collect_block", select_pos);
                                 client.collect_block(select_pos);
+                                self.session_stats.items_collected += 1;
                             }
 
                             // Collect lootable entities
@@ -531,27 +1810,167 @@ impl PlayState for SessionState {
                                 });
 
                                 if let Some(entity) = entity {
+                                    self.action_log.log(&client, "pick_up", entity);
                                     client.pick_up(entity);
+                                    self.session_stats.items_collected += 1;
                                 }
                             }
                         }
                     }
+                    Event::InputUpdate(GameInput::CycleInteractable, true) => {
+                        let interactables = nearby_interactables(&self.client.borrow());
+                        let next_index = self
+                            .selected_interactable
+                            .and_then(|i| interactables.iter().position(|other| *other == i))
+                            .map_or(0, |index| (index + 1) % interactables.len());
+                        self.selected_interactable = interactables.get(next_index).copied();
+                    },
                     /*Event::InputUpdate(GameInput::Charge, state) => {
                         self.inputs.charge.set_state(state);
                     },*/
-                    Event::InputUpdate(GameInput::FreeLook, state) => {
+                    // Only changes the local lighting, others still see the lantern
+                    Event::InputUpdate(GameInput::ToggleOwnLanternLight, true) => {
+                        let graphics = &mut global_state.settings.graphics;
+                        graphics.own_lantern_light = !graphics.own_lantern_light;
+                        global_state.settings.save_to_file_warn();
+                    },
+                    Event::InputUpdate(GameInput::SwapShoulder, true) => {
+                        let gameplay = &mut global_state.settings.gameplay;
+                        gameplay.camera_shoulder = gameplay.camera_shoulder.swapped();
+                        global_state.settings.save_to_file_warn();
+                    },
+                    Event::InputUpdate(GameInput::CycleQuickConsumable, true) => {
+                        let slots = self.hud.hotbar().slots;
+                        let client = self.client.borrow();
+                        let start = self.quick_consumable.as_ref().map_or(0, |(i, _)| i + 1);
+                        // Skip slots that are empty or hold something else
+                        self.quick_consumable = (start..start + slots.len())
+                            .map(|i| i % slots.len())
+                            .find_map(|i| {
+                                hotbar_consumable(&client, slots[i]).map(|(_, name, _)| (i, name))
+                            });
+                        if self.quick_consumable.is_none() {
+                            self.hud.new_message(ChatMsg {
+                                chat_type: ChatType::CommandError,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.quick_consume_none")
+                                    .to_string(),
+                            });
+                        }
+                    },
+                    Event::InputUpdate(GameInput::QuickConsume, true) => {
+                        let slots = self.hud.hotbar().slots;
+                        let on_cooldown = self
+                            .last_quick_consume
+                            .map_or(false, |t| t.elapsed() < QUICK_CONSUME_COOLDOWN);
+                        match &self.quick_consumable {
+                            None => self.hud.new_message(ChatMsg {
+                                chat_type: ChatType::CommandError,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.quick_consume_none")
+                                    .to_string(),
+                            }),
+                            Some(_) if on_cooldown => {},
+                            Some((slot, name)) => {
+                                let consumable =
+                                    hotbar_consumable(&self.client.borrow(), slots[*slot])
+                                        .filter(|(_, slot_name, _)| slot_name == name);
+                                match consumable {
+                                    Some((index, _, _)) => {
+                                        self.last_quick_consume = Some(Instant::now());
+                                        self.use_slot(comp::slot::Slot::Inventory(index));
+                                    },
+                                    None => self.hud.new_message(ChatMsg {
+                                        chat_type: ChatType::CommandError,
+                                        message: self
+                                            .voxygen_i18n
+                                            .get("hud.chat.quick_consume_empty")
+                                            .replace("{item}", name),
+                                    }),
+                                }
+                            },
+                        }
+                    },
+                    Event::InputUpdate(GameInput::ToggleLineTool, true) => {
+                        self.line_tool = !self.line_tool;
+                        self.build_line_start = None;
+                        let key = if self.line_tool {
+                            "hud.chat.line_tool_on"
+                        } else {
+                            "hud.chat.line_tool_off"
+                        };
+                        self.hud.new_message(ChatMsg {
+                            chat_type: ChatType::CommandInfo,
+                            message: self.voxygen_i18n.get(key).to_string(),
+                        });
+                    },
+                    Event::InputUpdate(GameInput::ToggleBuildGrid, true) => {
+                        self.build_grid = !self.build_grid;
+                    },
+                    Event::InputUpdate(GameInput::ToggleTargetLock, true) => {
+                        if self.locked_target.take().is_none() {
+                            match self.target_entity {
+                                Some(target) => {
+                                    self.locked_target = Some(target);
+                                    // Free look would fight the tracking
+                                    self.free_look = false;
+                                    self.free_look_latched = false;
+                                    self.hud.free_look(false);
+                                },
+                                None => self.hud.new_message(ChatMsg {
+                                    chat_type: ChatType::CommandError,
+                                    message: self
+                                        .voxygen_i18n
+                                        .get("hud.chat.target_lock_no_target")
+                                        .to_string(),
+                                }),
+                            }
+                        }
+                    },
+                    Event::InputUpdate(GameInput::FreeLook, state) => {
+                        // Suspended while a target is locked
+                        if self.locked_target.is_some() {
+                            continue;
+                        }
                         match (global_state.settings.gameplay.free_look_behavior, state) {
                             (PressBehavior::Toggle, true) => {
                                 self.free_look = !self.free_look;
                                 self.hud.free_look(self.free_look);
                             },
-                            (PressBehavior::Hold, state) => {
-                                self.free_look = state;
+                            (PressBehavior::Hold, true) => {
+                                let gameplay = &global_state.settings.gameplay;
+                                let double_tap_window =
+                                    Duration::from_millis(gameplay.free_look_double_tap_ms as u64);
+                                if self.free_look_latched {
+                                    // Tapping again lets go, and doesn't start a new double tap
+                                    self.free_look_latched = false;
+                                    self.last_free_look_tap = None;
+                                } else if gameplay.sticky_free_look
+                                    && self
+                                        .last_free_look_tap
+                                        .map_or(false, |tap| tap.elapsed() < double_tap_window)
+                                {
+                                    self.free_look_latched = true;
+                                    self.last_free_look_tap = None;
+                                } else {
+                                    self.last_free_look_tap = Some(Instant::now());
+                                }
+                                self.free_look = true;
+                                self.hud.free_look(self.free_look);
+                            },
+                            (PressBehavior::Hold, false) => {
+                                self.free_look = self.free_look_latched;
                                 self.hud.free_look(self.free_look);
                             },
                             _ => {},
                         };
                     },
+                    Event::InputUpdate(GameInput::ToggleWalk, true) => {
+                        self.walking = !self.walking;
+                        self.hud.walking(self.walking);
+                    },
                     Event::InputUpdate(GameInput::AutoWalk, state) => {
                         match (global_state.settings.gameplay.auto_walk_behavior, state) {
                             (PressBehavior::Toggle, true) => {
@@ -567,18 +1986,90 @@ impl PlayState for SessionState {
                             _ => {},
                         }
                     },
+                    Event::InputUpdate(GameInput::CycleShadowMode, true) => {
+                        let mut render_mode = global_state.settings.graphics.render_mode.clone();
+                        let (shadow, label) = match render_mode.shadow {
+                            ShadowMode::None => (
+                                ShadowMode::Cheap,
+                                "hud.settings.shadow_rendering_mode.cheap",
+                            ),
+                            ShadowMode::Cheap => (
+                                ShadowMode::Map(ShadowMapMode::default()),
+                                "hud.settings.shadow_rendering_mode.map",
+                            ),
+                            ShadowMode::Map(_) => {
+                                (ShadowMode::None, "hud.settings.shadow_rendering_mode.none")
+                            },
+                        };
+                        render_mode.shadow = shadow;
+                        let message = match apply_render_mode(
+                            global_state,
+                            &mut self.last_good_render_mode,
+                            render_mode,
+                        ) {
+                            Ok(()) => ChatMsg {
+                                message: format!(
+                                    "{}: {}",
+                                    self.voxygen_i18n.get("hud.settings.shadow_rendering_mode"),
+                                    self.voxygen_i18n.get(label)
+                                ),
+                                chat_type: ChatType::CommandInfo,
+                            },
+                            Err(_) => ChatMsg {
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.render_mode_failed")
+                                    .to_string(),
+                                chat_type: ChatType::CommandError,
+                            },
+                        };
+                        self.hud.new_message(message);
+                    },
+                    Event::InputUpdate(GameInput::ToggleMouseSteering, true) => {
+                        let mouse_steering = match global_state.window.mouse_steering() {
+                            MouseSteering::Relative => MouseSteering::Absolute,
+                            MouseSteering::Absolute => MouseSteering::Relative,
+                        };
+                        global_state.window.set_mouse_steering(mouse_steering);
+                        global_state.settings.gameplay.mouse_steering = mouse_steering;
+                        global_state.settings.save_to_file_warn();
+                    },
                     Event::InputUpdate(GameInput::CycleCamera, true) => {
-                        // Prevent accessing camera modes which aren't available in multiplayer
-                        // unless you are an admin. This is an easily bypassed clientside check.
-                        // The server should do its own filtering of which entities are sent to
-                        // clients to prevent abuse.
-                        let camera = self.scene.camera_mut();
-                        camera.next_mode(self.client.borrow().is_admin());
+                        // Modes the player isn't allowed to use are skipped over
+                        let next_mode = self.scene.camera().next_mode();
+                        self.set_camera_mode(next_mode);
                     },
                     Event::InputUpdate(GameInput::Select, state) => {
                         if !state {
                             self.selected_entity =
                                 self.target_entity.map(|e| (e, std::time::Instant::now()));
+                            // Turned once, the camera is free to move away again right after
+                            if global_state.settings.gameplay.select_turns_camera && !self.free_look
+                            {
+                                if let Some(dir) = self
+                                    .target_entity
+                                    .and_then(|e| target_lock_dir(&self.client.borrow(), e))
+                                {
+                                    self.scene.camera_mut().look_towards(dir);
+                                }
+                            }
+                        }
+                    },
+                    Event::InputUpdate(GameInput::TargetNearest, true) => {
+                        // Nothing in range keeps the current target
+                        let client = self.client.borrow();
+                        if let Some(target) = nearest_hostile(&client, cam_dir) {
+                            if self.selected_entity.map(|(e, _)| e) != Some(target) {
+                                client
+                                    .state()
+                                    .ecs()
+                                    .read_resource::<EventBus<SfxEventItem>>()
+                                    .emit_now(SfxEventItem::at_player_position(
+                                        SfxEvent::TargetAcquired,
+                                    ));
+                            }
+                            self.target_entity = Some(target);
+                            self.selected_entity = Some((target, std::time::Instant::now()));
                         }
                     },
                     Event::InputUpdate(GameInput::AcceptGroupInvite, true) => {
@@ -593,6 +2084,305 @@ impl PlayState for SessionState {
                             client.decline_group_invite();
                         }
                     },
+                    Event::InputUpdate(GameInput::ToggleInputRecording, true) => {
+                        if self.replay.state() == ReplayState::Recording {
+                            self.replay.stop();
+                        } else {
+                            self.replay.start_recording();
+                        }
+                        self.hud.replay_state(self.replay.state());
+                    },
+                    Event::InputUpdate(GameInput::ToggleCombatCamera, true) => {
+                        self.combat_camera = !self.combat_camera;
+                        self.last_manual_zoom = Some(Instant::now());
+                        self.apply_camera_distance(&global_state.settings);
+                    },
+                    Event::Zoom(delta) => {
+                        self.last_manual_zoom = Some(Instant::now());
+                        self.scene.handle_input_event(Event::Zoom(delta));
+                    },
+                    Event::InputUpdate(GameInput::DumpSettings, true) => {
+                        let settings = &global_state.settings;
+                        match settings.dump() {
+                            Ok(path) => {
+                                let graphics = &settings.graphics;
+                                let [width, height] = if graphics.fullscreen.enabled {
+                                    graphics.fullscreen.resolution
+                                } else {
+                                    graphics.window_size
+                                };
+                                self.hud.new_message(ChatMsg {
+                                    chat_type: ChatType::CommandInfo,
+                                    message: self
+                                        .voxygen_i18n
+                                        .get("hud.chat.settings_dumped")
+                                        .replace("{path}", &path.to_string_lossy())
+                                        .replace(
+                                            "{view_distance}",
+                                            &graphics.view_distance.to_string(),
+                                        )
+                                        .replace("{max_fps}", &graphics.max_fps.to_string())
+                                        .replace("{fov}", &graphics.fov.to_string())
+                                        .replace("{resolution}", &format!("{}x{}", width, height))
+                                        .replace("{aa}", &format!("{:?}", graphics.render_mode.aa)),
+                                });
+                            },
+                            Err(e) => {
+                                warn!(?e, "Failed to dump settings");
+                                self.hud.new_message(ChatMsg {
+                                    chat_type: ChatType::CommandError,
+                                    message: self
+                                        .voxygen_i18n
+                                        .get("hud.chat.settings_dump_failed")
+                                        .to_string(),
+                                });
+                            },
+                        }
+                    },
+                    Event::InputUpdate(GameInput::SaveCameraPath, true) => {
+                        let message = match self.camera_path.save() {
+                            Ok(path) => ChatMsg {
+                                chat_type: ChatType::CommandInfo,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.camera_path_saved")
+                                    .replace("{path}", &path.to_string_lossy()),
+                            },
+                            Err(e) => {
+                                warn!(?e, "Failed to save camera path");
+                                ChatMsg {
+                                    chat_type: ChatType::CommandError,
+                                    message: self
+                                        .voxygen_i18n
+                                        .get("hud.chat.camera_path_save_failed")
+                                        .to_string(),
+                                }
+                            },
+                        };
+                        self.hud.new_message(message);
+                    },
+                    Event::InputUpdate(GameInput::ToggleInputPlayback, true) => {
+                        if self.replay.state() == ReplayState::Playback {
+                            self.replay.stop();
+                        } else {
+                            self.stop_auto_walk();
+                            self.replay.start_playback();
+                        }
+                        self.hud.replay_state(self.replay.state());
+                    },
+                    Event::InputUpdate(GameInput::ToggleFirstPersonModel, true) => {
+                        // Only meaningful in first person, but the choice is kept when switching
+                        // back to it
+                        if self.scene.camera().get_mode() == CameraMode::FirstPerson {
+                            self.show_first_person_model = !self.show_first_person_model;
+                        }
+                    },
+                    Event::InputUpdate(GameInput::ToggleViewDistance, true) => {
+                        let mut client = self.client.borrow_mut();
+                        let graphics = &mut global_state.settings.graphics;
+                        let current = client.view_distance().unwrap_or(graphics.view_distance);
+                        let view_distance = if current > graphics.low_view_distance {
+                            graphics.high_view_distance = current;
+                            graphics.low_view_distance
+                        } else {
+                            graphics.high_view_distance
+                        };
+                        client.set_view_distance(view_distance);
+                        // The client clamps it as well
+                        let view_distance = client.view_distance().unwrap_or(view_distance);
+                        graphics.view_distance = view_distance;
+                        global_state.settings.save_to_file_warn();
+                        self.view_distance_toggled = true;
+                        // Terrain takes a while to stream in or out, so confirm the change
+                        self.hud.new_message(ChatMsg {
+                            chat_type: ChatType::CommandInfo,
+                            message: self
+                                .voxygen_i18n
+                                .get("hud.chat.view_distance_set")
+                                .replace("{distance}", &view_distance.to_string()),
+                        });
+                    },
+                    Event::InputUpdate(GameInput::ToggleDoNotDisturb, true) => {
+                        let message = match self.dnd_notifications.take() {
+                            Some(queue) => {
+                                self.hud.dnd_queued(None);
+                                let message = self
+                                    .voxygen_i18n
+                                    .get("hud.chat.do_not_disturb_off")
+                                    .replace("{count}", &queue.len().to_string());
+                                for n in queue {
+                                    self.hud.new_notification(n);
+                                }
+                                message
+                            },
+                            None => {
+                                self.dnd_notifications = Some(VecDeque::new());
+                                self.hud.dnd_queued(Some(0));
+                                self.voxygen_i18n
+                                    .get("hud.chat.do_not_disturb_on")
+                                    .to_string()
+                            },
+                        };
+                        self.hud.new_message(ChatMsg {
+                            chat_type: ChatType::CommandInfo,
+                            message,
+                        });
+                    },
+                    Event::InputUpdate(GameInput::ToggleInspect, true) => {
+                        self.show_inspect = !self.show_inspect;
+                    },
+                    // Leaves right away, skipping the summary shown on a normal logout
+                    Event::InputUpdate(GameInput::PanicDisconnect, true) => {
+                        if global_state.settings.gameplay.panic_disconnect_clears_chat {
+                            global_state.chat_history.clear();
+                        }
+                        // A paused singleplayer server wouldn't let us go
+                        #[cfg(feature = "singleplayer")]
+                        global_state.unpause();
+                        self.client.borrow_mut().request_logout();
+                        self.save_lifetime_stats(global_state);
+                        return PlayStateResult::Pop;
+                    },
+                    Event::InputUpdate(GameInput::RepeatBuild, true) if can_build => {
+                        let mut client = self.client.borrow_mut();
+                        match self.last_build_action {
+                            None => self.hud.new_message(ChatMsg {
+                                chat_type: ChatType::CommandInfo,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.repeat_build_none")
+                                    .to_string(),
+                            }),
+                            Some(BuildAction::Break) => {
+                                let protected = global_state.settings.gameplay.protected_build
+                                    && !self.key_state.build_modifier;
+                                if let Some(select_pos) = select_pos.filter(|_| !protected) {
+                                    self.action_log.log(&client, "remove_block", select_pos);
+                                    client.remove_block(select_pos);
+                                    self.session_stats.blocks_removed += 1;
+                                }
+                            },
+                            Some(BuildAction::Place) => {
+                                let protected = global_state.settings.gameplay.protected_build
+                                    && global_state.settings.gameplay.protected_build_placing
+                                    && !self.key_state.build_modifier;
+                                let kind = self.selected_block.kind();
+                                if let Some(build_pos) = build_pos.filter(|_| !protected) {
+                                    if !is_placeable(kind) && !client.is_admin() {
+                                        self.hud.new_message(ChatMsg {
+                                            chat_type: ChatType::CommandError,
+                                            message: self
+                                                .voxygen_i18n
+                                                .get("hud.chat.block_not_placeable")
+                                                .replace("{block}", &kind.to_string()),
+                                        });
+                                    } else {
+                                        self.action_log.log(
+                                            &client,
+                                            "place_block",
+                                            (build_pos, self.selected_block),
+                                        );
+                                        client.place_block(build_pos, self.selected_block);
+                                        self.session_stats.blocks_placed += 1;
+                                        self.placement_retried_pos = None;
+                                    }
+                                }
+                            },
+                        }
+                    }
+                    Event::InputUpdate(GameInput::ToggleScoutOverlay, true) => {
+                        // Seeing everyone through walls would be a cheat for everyone else
+                        if self.client.borrow().is_admin() {
+                            self.scout_overlay = !self.scout_overlay;
+                        } else {
+                            self.hud.new_message(ChatMsg {
+                                chat_type: ChatType::CommandError,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.scout_overlay_admin_only")
+                                    .to_string(),
+                            });
+                        }
+                    },
+                    Event::InputUpdate(GameInput::ToggleGroupCoordinates, true) => {
+                        self.group_coordinates = !self.group_coordinates;
+                    },
+                    Event::InputUpdate(GameInput::InspectBlock, true) => {
+                        self.inspect_block = !self.inspect_block;
+                    },
+                    Event::InputUpdate(GameInput::SaveSettings, true) => {
+                        let message = match global_state.settings.save_to_file() {
+                            Ok(()) => ChatMsg {
+                                chat_type: ChatType::CommandInfo,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.settings_saved")
+                                    .to_string(),
+                            },
+                            Err(e) => {
+                                warn!(?e, "Failed to save settings");
+                                ChatMsg {
+                                    chat_type: ChatType::CommandError,
+                                    message: self
+                                        .voxygen_i18n
+                                        .get("hud.chat.settings_save_failed")
+                                        .to_string(),
+                                }
+                            },
+                        };
+                        self.hud.new_message(message);
+                    },
+                    Event::InputUpdate(GameInput::Screenshot, true) => {
+                        // Render this frame without the HUD, it is captured at the start of the
+                        // next one. The "screenshot saved" message is only sent once the file
+                        // has been written, so it can't end up in the capture either.
+                        self.hud.hide_for_screenshot(true);
+                        self.screenshot_pending = true;
+                        global_state.window.request_screenshot();
+                    },
+                    Event::InputUpdate(GameInput::RunBenchmark, true) => {
+                        if let Some(benchmark) = self.benchmark.take() {
+                            self.end_benchmark(benchmark);
+                        } else if !global_state.is_singleplayer() {
+                            // Results from a remote server depend on the network and other
+                            // players, so they wouldn't be comparable
+                            self.hud.new_message(ChatMsg {
+                                chat_type: ChatType::CommandError,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.benchmark_singleplayer_only")
+                                    .to_string(),
+                            });
+                        } else {
+                            self.stop_auto_walk();
+                            let mut client = self.client.borrow_mut();
+                            let prev_pos = client
+                                .state()
+                                .read_storage::<Pos>()
+                                .get(client.entity())
+                                .map(|pos| pos.0);
+                            // Flown around the spawn so that runs are comparable, the player
+                            // is moved there for the duration (singleplayer is always admin)
+                            let origin = self.spawn_pos.or(prev_pos).unwrap_or_else(Vec3::zero);
+                            client.send_chat(goto_command(origin));
+                            let camera = self.scene.camera_mut();
+                            self.benchmark = Some(Benchmark::new(
+                                origin,
+                                prev_pos,
+                                camera.get_mode(),
+                                client.view_distance(),
+                            ));
+                            camera.set_mode(CameraMode::Freefly);
+                            client.set_view_distance(BENCHMARK_VIEW_DISTANCE);
+                            self.hud.new_message(ChatMsg {
+                                chat_type: ChatType::CommandInfo,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.benchmark_started")
+                                    .to_string(),
+                            });
+                        }
+                    },
                     Event::AnalogGameInput(input) => match input {
                         AnalogGameInput::MovementX(v) => {
                             self.key_state.analog_matrix.x = v;
@@ -618,14 +2408,32 @@ impl PlayState for SessionState {
                 }
             }
 
-            if !self.free_look {
+            let prev_look_dir = self.inputs.look_dir;
+            // The character stays put while spectating
+            if !self.free_look && self.spectate.is_none() {
                 self.walk_forward_dir = self.scene.camera().forward_xy();
                 self.walk_right_dir = self.scene.camera().right_xy();
-                self.inputs.look_dir = Dir::from_unnormalized(cam_dir + aim_dir_offset).unwrap();
+                self.inputs.look_dir = match target_lock_dir {
+                    Some(dir) => {
+                        let dt = global_state.clock.get_last_delta().as_secs_f32();
+                        Dir::slerp_to_vec3(
+                            self.inputs.look_dir,
+                            dir,
+                            (TARGET_LOCK_TRACKING * dt).min(1.0),
+                        )
+                    },
+                    None => Dir::from_unnormalized(cam_dir + aim_dir_offset).unwrap(),
+                };
             }
 
             // Get the current state of movement related inputs
-            let input_vec = self.key_state.dir_vec();
+            let mut input_vec = self.key_state.dir_vec();
+            // Keys always move at full strength, only the stick can drift
+            if self.key_state.analog_matrix != Vec2::zero()
+                && input_vec.magnitude() < global_state.settings.controller.min_move_magnitude
+            {
+                input_vec = Vec2::zero();
+            }
             let (axis_right, axis_up) = (input_vec[0], input_vec[1]);
 
             match self.scene.camera().get_mode() {
@@ -640,18 +2448,36 @@ impl PlayState for SessionState {
                 camera::CameraMode::Freefly => {
                     // Move the camera freely in 3d space. Apply acceleration so that
                     // the movement feels more natural and controlled.
-                    const FREEFLY_ACCEL: f32 = 120.0;
-                    const FREEFLY_DAMPING: f32 = 80.0;
-                    const FREEFLY_MAX_SPEED: f32 = 50.0;
+                    let FreeflySettings {
+                        accel,
+                        damping,
+                        max_speed,
+                    } = global_state.settings.gameplay.freefly.validated();
+                    // Speed scales while the boost or precision modifier is held
+                    const FREEFLY_BOOST: f32 = 5.0;
+                    const FREEFLY_PRECISION: f32 = 0.2;
+                    const FREEFLY_SPEED_MUL_RATE: f32 = 5.0;
 
                     let forward = self.scene.camera().forward();
                     let right = self.scene.camera().right();
                     let dir = right * axis_right + forward * axis_up;
 
                     let dt = global_state.clock.get_last_delta().as_secs_f32();
+                    let target_mul = if self.key_state.freefly_boost {
+                        FREEFLY_BOOST
+                    } else if self.key_state.freefly_precision {
+                        FREEFLY_PRECISION
+                    } else {
+                        1.0
+                    };
+                    self.freefly_speed_mul = f32::lerp(
+                        self.freefly_speed_mul,
+                        target_mul,
+                        (FREEFLY_SPEED_MUL_RATE * dt).min(1.0),
+                    );
                     if self.freefly_vel.magnitude_squared() > 0.01 {
-                        let new_vel = self.freefly_vel
-                            - self.freefly_vel.normalized() * (FREEFLY_DAMPING * dt);
+                        let new_vel =
+                            self.freefly_vel - self.freefly_vel.normalized() * (damping * dt);
                         if self.freefly_vel.dot(new_vel) > 0.0 {
                             self.freefly_vel = new_vel;
                         } else {
@@ -659,11 +2485,19 @@ impl PlayState for SessionState {
                         }
                     }
                     if dir.magnitude_squared() > 0.01 {
-                        self.freefly_vel += dir * (FREEFLY_ACCEL * dt);
-                        if self.freefly_vel.magnitude() > FREEFLY_MAX_SPEED {
-                            self.freefly_vel = self.freefly_vel.normalized() * FREEFLY_MAX_SPEED;
+                        // Above the max speed, e.g. after letting go of boost, only the
+                        // damping slows the camera down
+                        let scaled_max_speed =
+                            (max_speed * self.freefly_speed_mul).max(self.freefly_vel.magnitude());
+                        self.freefly_vel += dir * (accel * self.freefly_speed_mul * dt);
+                        if self.freefly_vel.magnitude() > scaled_max_speed {
+                            self.freefly_vel = self.freefly_vel.normalized() * scaled_max_speed;
                         }
                     }
+                    if self.freefly_vel.magnitude() > max_speed * FREEFLY_BOOST {
+                        self.freefly_vel =
+                            self.freefly_vel.normalized() * max_speed * FREEFLY_BOOST;
+                    }
 
                     let pos = self.scene.camera().get_focus_pos();
                     self.scene
@@ -675,12 +2509,210 @@ impl PlayState for SessionState {
                 },
             };
 
+            // Run at the selected creature while walking forward, until close enough to
+            // fight it
+            if global_state.settings.gameplay.auto_approach_target
+                && axis_up > 0.0
+                && !self.free_look
+                && self.spectate.is_none()
+                && self.scene.camera().get_mode() != camera::CameraMode::Freefly
+            {
+                if let Some(dir) = self
+                    .selected_entity
+                    .and_then(|(entity, _)| approach_dir(&self.client.borrow(), entity))
+                {
+                    self.inputs.move_dir = dir;
+                }
+            }
+
+            // Walk to where the player clicked until there, giving up if it takes too long.
+            // Any movement input takes over right away.
+            if let Some((destination, started)) = self.move_destination {
+                let dir = click_to_move_dir(&self.client.borrow(), destination);
+                match dir {
+                    Some(dir)
+                        if input_vec == Vec2::zero()
+                            && started.elapsed() < CLICK_TO_MOVE_TIMEOUT
+                            && self.spectate.is_none()
+                            && self.scene.camera().get_mode() != camera::CameraMode::Freefly =>
+                    {
+                        self.inputs.move_dir = dir
+                    },
+                    _ => self.move_destination = None,
+                }
+            }
+
+            // Walking only slows down movement that is already decided on
+            if self.walking {
+                self.inputs.move_dir *= global_state.settings.gameplay.walk_speed.max(0.0).min(1.0);
+            }
+
+            // Turn the character towards where they are walking rather than where the
+            // camera faces, keeping the last facing while standing still. Aiming and
+            // target lock take precedence.
+            if global_state.settings.gameplay.face_movement_direction
+                && !is_aiming
+                && target_lock_dir.is_none()
+                && !self.free_look
+                && self.spectate.is_none()
+                && self.scene.camera().get_mode() == camera::CameraMode::ThirdPerson
+            {
+                let facing = if self.inputs.move_dir.magnitude_squared() > 0.01 {
+                    Vec3::from(self.inputs.move_dir)
+                } else {
+                    Vec3::from(prev_look_dir.xy())
+                };
+                if facing.magnitude_squared() > 0.0 {
+                    let dt = global_state.clock.get_last_delta().as_secs_f32();
+                    self.inputs.look_dir = Dir::slerp_to_vec3(
+                        prev_look_dir,
+                        facing,
+                        (FACE_MOVEMENT_TRACKING * dt).min(1.0),
+                    );
+                }
+            }
+
+            // Fly the benchmark path, overriding any camera input
+            if let Some(benchmark) = &mut self.benchmark {
+                let dt = global_state.clock.get_last_delta().as_secs_f32();
+                match benchmark.step(dt) {
+                    Some((pos, ori)) => {
+                        let camera = self.scene.camera_mut();
+                        camera.set_focus_pos(pos);
+                        camera.set_orientation(ori);
+                        camera.set_ori_instant(ori);
+                    },
+                    None => {
+                        if let Some(benchmark) = self.benchmark.take() {
+                            self.end_benchmark(benchmark);
+                        }
+                    },
+                }
+            }
+
+            // Pass on attack presses buffered during the recovery of the last attack
+            let buffer_duration = Duration::from_millis(u64::from(
+                global_state.settings.gameplay.attack_input_buffer_ms,
+            ));
+            release_buffered_input(
+                &mut self.buffered_primary,
+                &mut self.inputs.primary,
+                attack_recovering,
+                buffer_duration,
+            );
+            release_buffered_input(
+                &mut self.buffered_secondary,
+                &mut self.inputs.secondary,
+                attack_recovering,
+                buffer_duration,
+            );
+
+            // Follow the spectated player, overriding any camera movement
+            if let Some(spectate) = &mut self.spectate {
+                let was_watching = spectate.is_watching();
+                let status = spectate.update(&self.client.borrow(), self.scene.camera().forward());
+                match status {
+                    SpectateStatus::Watching(focus_pos) => {
+                        let camera = self.scene.camera_mut();
+                        if camera.get_mode() != CameraMode::Freefly {
+                            camera.set_mode(CameraMode::Freefly);
+                        }
+                        camera.set_focus_pos(focus_pos);
+                        if !was_watching {
+                            self.hud.new_message(ChatMsg {
+                                chat_type: ChatType::CommandInfo,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.spectate_started")
+                                    .replace("{name}", spectate.name()),
+                            });
+                        }
+                    },
+                    SpectateStatus::Searching => {},
+                    SpectateStatus::Lost => {
+                        if let Some(spectate) = self.spectate.take() {
+                            let message_key = if was_watching {
+                                "hud.chat.spectate_lost"
+                            } else {
+                                "hud.chat.spectate_not_found"
+                            };
+                            self.end_spectate(spectate, message_key);
+                        }
+                    },
+                }
+                // Keep the character from acting on any held inputs
+                self.inputs = comp::ControllerInputs {
+                    look_dir: self.inputs.look_dir,
+                    ..Default::default()
+                };
+            }
+
             self.inputs.climb = self.key_state.climb();
 
+            // Update the session stats
+            {
+                let dt = global_state.clock.get_last_delta().as_secs_f64();
+                let client = self.client.borrow();
+                let speed = client
+                    .state()
+                    .read_storage::<comp::Vel>()
+                    .get(client.entity())
+                    .map_or(0.0, |vel| vel.0.magnitude() as f64);
+                self.session_stats.distance_traveled += speed * dt;
+                self.session_stats.play_time += dt;
+            }
+
             let mut outcomes = Vec::new();
 
+            // Nobody is waiting on the slot of a paused singleplayer game, so don't count
+            // the pause towards the AFK logout
+            if global_state.paused() {
+                self.last_input = Instant::now();
+                self.hud.afk_logout_countdown(None);
+            }
+
             // Runs if either in a multiplayer server or the singleplayer server is unpaused
             if !global_state.paused() {
+                // Record this tick's inputs, or replace them with recorded ones during playback
+                match self.replay.state() {
+                    ReplayState::Recording => self
+                        .replay
+                        .record(&ReplayFrame::new(&self.inputs, self.scene.camera())),
+                    ReplayState::Playback => match self.replay.next_frame() {
+                        Some(frame) => {
+                            frame.apply_camera(self.scene.camera_mut());
+                            self.inputs = frame.inputs;
+                        },
+                        // End of the recording, hand control back to the player
+                        None => {
+                            self.inputs = comp::ControllerInputs::default();
+                            self.hud.replay_state(ReplayState::Idle);
+                            self.hud.new_message(ChatMsg {
+                                chat_type: ChatType::CommandInfo,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.replay_finished")
+                                    .to_string(),
+                            });
+                        },
+                    },
+                    ReplayState::Idle => {},
+                }
+
+                {
+                    let client = self.client.borrow();
+                    self.camera_path.sample(
+                        client.state().get_time(),
+                        client.get_tick(),
+                        self.scene.camera(),
+                    );
+                }
+
+                self.auto_consume(&global_state.settings);
+                self.sheathe_while_swimming(&global_state.settings);
+                self.auto_combat_camera(&global_state.settings);
+                self.auto_respawn(&global_state.settings);
+
                 // Perform an in-game tick.
                 match self.tick(
                     global_state.clock.get_avg_delta(),
@@ -688,8 +2720,18 @@ impl PlayState for SessionState {
                     &mut outcomes,
                 ) {
                     Ok(TickAction::Continue) => {}, // Do nothing
-                    Ok(TickAction::Disconnect) => return PlayStateResult::Pop, // Go to main menu
+                    Ok(TickAction::Disconnect) => {
+                        self.save_lifetime_stats(global_state);
+                        if self.quit_after_logout {
+                            return PlayStateResult::Shutdown;
+                        }
+                        return PlayStateResult::Pop; // Go to main menu
+                    },
                     Err(err) => {
+                        self.save_lifetime_stats(global_state);
+                        if self.quit_after_logout {
+                            return PlayStateResult::Shutdown;
+                        }
                         global_state.info_message =
                             Some(self.voxygen_i18n.get("common.connection_lost").to_owned());
                         error!("[session] Failed to tick the scene: {:?}", err);
@@ -699,6 +2741,23 @@ impl PlayState for SessionState {
                 }
             }
 
+            // Drop an unanswered confirmation to leave the group, or one left behind by
+            // being kicked
+            if let Some(requested) = self.leave_group_requested {
+                if requested.elapsed() >= LEAVE_GROUP_CONFIRM_TIMEOUT
+                    || self.client.borrow().group_info().is_none()
+                {
+                    self.leave_group_requested = None;
+                    self.hud.confirm_leave_group(None);
+                }
+            }
+
+            // Don't hold up leaving if the summary isn't dismissed
+            if matches!(self.leaving, Some((_, shown)) if shown.elapsed() >= LEAVE_SUMMARY_TIMEOUT)
+            {
+                self.leave();
+            }
+
             // Recompute dependents just in case some input modified the camera
             self.scene
                 .camera_mut()
@@ -712,6 +2771,7 @@ impl PlayState for SessionState {
                 .toggle_debug
                 .then(|| DebugInfo {
                     tps: global_state.clock.get_tps(),
+                    server_tps: self.client.borrow().get_server_tick_rate(),
                     ping_ms: self.client.borrow().get_ping_ms_rolling_avg(),
                     coordinates: self
                         .client
@@ -748,6 +2808,53 @@ impl PlayState for SessionState {
                         as u32,
                 });
 
+            // The compass follows the character rather than the camera during free look
+            let facing = if self.free_look {
+                self.client
+                    .borrow()
+                    .state()
+                    .read_storage::<comp::Ori>()
+                    .get(self.client.borrow().entity())
+                    .map(|ori| ori.vec().xy())
+            } else {
+                Some(self.scene.camera().forward_xy())
+            };
+            if let Some(facing) = facing.filter(|f| f.magnitude_squared() > 0.001) {
+                // Turn towards the new heading along the shortest way around, so it
+                // doesn't jitter as the player turns
+                const COMPASS_SMOOTHING: f32 = 15.0;
+                let dt = global_state.clock.get_last_delta().as_secs_f32();
+                let heading = facing.x.atan2(facing.y).to_degrees();
+                let diff = (heading - self.compass_heading + 540.0).rem_euclid(360.0) - 180.0;
+                self.compass_heading = (self.compass_heading
+                    + diff * (dt * COMPASS_SMOOTHING).min(1.0))
+                .rem_euclid(360.0);
+            }
+
+            // Lifetime totals include the current session so they stay up to date
+            let lifetime_stats = if global_state.settings.gameplay.save_lifetime_stats {
+                let client = self.client.borrow();
+                client.active_character_id.map(|character_id| {
+                    let mut lifetime_stats = global_state
+                        .profile
+                        .get_character_profile(&client.server_info.name, character_id)
+                        .map_or_else(SessionStats::default, |character| character.lifetime_stats);
+                    lifetime_stats.merge(&self.session_stats);
+                    lifetime_stats
+                })
+            } else {
+                None
+            };
+
+            // Read every frame so the amount follows the inventory
+            let quick_consumable = self.quick_consumable.as_ref().map(|(slot, name)| {
+                let amount =
+                    hotbar_consumable(&self.client.borrow(), self.hud.hotbar().slots[*slot])
+                        .filter(|(_, slot_name, _)| slot_name == name)
+                        .map_or(0, |(_, _, amount)| amount);
+                (name.clone(), amount)
+            });
+
             // Extract HUD events ensuring the client borrow gets dropped.
             let mut hud_events = self.hud.maintain(
                 &self.client.borrow(),
@@ -763,6 +2870,50 @@ impl PlayState for SessionState {
                     ),
                     target_entity: self.target_entity,
                     selected_entity: self.selected_entity,
+                    fov: self
+                        .fov_override
+                        .unwrap_or(global_state.settings.graphics.fov),
+                    gamma: self
+                        .gamma_override
+                        .unwrap_or(global_state.settings.graphics.gamma),
+                    session_stats: self.session_stats,
+                    lifetime_stats,
+                    compass_heading: self.compass_heading,
+                    time_of_day: self
+                        .client
+                        .borrow()
+                        .state()
+                        .ecs()
+                        .try_fetch::<TimeOfDay>()
+                        .map(|time_of_day| time_of_day.0),
+                    aim_point: if is_aiming && global_state.settings.gameplay.crosshair_follows_aim
+                    {
+                        Some(aim_point_on_screen(
+                            &self.client.borrow(),
+                            self.scene.camera(),
+                            *self.inputs.look_dir,
+                        ))
+                    } else {
+                        None
+                    },
+                    // Read every frame so it follows the entity re-equipping
+                    inspected_gear: self
+                        .selected_entity
+                        .filter(|_| self.show_inspect)
+                        .and_then(|(entity, _)| inspected_gear(&self.client.borrow(), entity)),
+                    inspected_block: self.inspected_block,
+                    climb_stamina: climb_stamina(&self.client.borrow()),
+                    quick_consumable,
+                    scout_targets: if self.scout_overlay && self.client.borrow().is_admin() {
+                        scout_targets(&self.client.borrow())
+                    } else {
+                        Vec::new()
+                    },
+                    group_coordinates: if self.group_coordinates {
+                        group_coordinates(&self.client.borrow())
+                    } else {
+                        Vec::new()
+                    },
                 },
             );
 
@@ -777,16 +2928,36 @@ impl PlayState for SessionState {
             for event in hud_events {
                 match event {
                     HudEvent::SendMessage(msg) => {
-                        // TODO: Handle result
-                        self.client.borrow_mut().send_chat(msg);
+                        // Spectating is done locally rather than by the server
+                        match msg.strip_prefix("/spectate") {
+                            Some(name) if name.is_empty() || name.starts_with(' ') => {
+                                self.spectate_command(name)
+                            },
+                            // TODO: Handle result
+                            _ => {
+                                self.remember_chat_channel(global_state, &msg);
+                                self.client.borrow_mut().send_chat(msg)
+                            },
+                        }
                     },
                     HudEvent::CharacterSelection => {
-                        self.client.borrow_mut().request_remove_character()
+                        self.show_leave_summary(LeaveTo::CharacterSelection)
                     },
-                    HudEvent::Logout => self.client.borrow_mut().request_logout(),
+                    HudEvent::Logout => self.show_leave_summary(LeaveTo::MainMenu),
+                    HudEvent::CloseLogoutSummary => self.leave(),
                     HudEvent::Quit => {
+                        self.save_lifetime_stats(global_state);
                         return PlayStateResult::Shutdown;
                     },
+                    HudEvent::ConfirmClose => {
+                        // Unpause the game if we are on singleplayer so that we can logout
+                        #[cfg(feature = "singleplayer")]
+                        global_state.unpause();
+
+                        self.quit_after_logout = true;
+                        self.client.borrow_mut().request_logout();
+                    },
+                    HudEvent::CancelClose => self.close_requested = false,
                     HudEvent::AdjustMousePan(sensitivity) => {
                         global_state.window.pan_sensitivity = sensitivity;
                         global_state.settings.gameplay.pan_sensitivity = sensitivity;
@@ -841,6 +3012,7 @@ impl PlayState for SessionState {
                     },
                     HudEvent::AdjustViewDistance(view_distance) => {
                         self.client.borrow_mut().set_view_distance(view_distance);
+                        self.view_distance_toggled = false;
 
                         global_state.settings.graphics.view_distance = view_distance;
                         global_state.settings.save_to_file_warn();
@@ -920,15 +3092,36 @@ impl PlayState for SessionState {
                         global_state.settings.graphics.max_fps = fps;
                         global_state.settings.save_to_file_warn();
                     },
-                    HudEvent::UseSlot(x) => self.client.borrow_mut().use_slot(x),
-                    HudEvent::SwapSlots(a, b) => self.client.borrow_mut().swap_slots(a, b),
+                    HudEvent::UseSlot(x) => self.use_slot(x),
+                    HudEvent::SwapSlots(a, b) => {
+                        self.action_log
+                            .log(&self.client.borrow(), "swap_slots", (a, b));
+                        self.client.borrow_mut().swap_slots(a, b);
+                    },
                     HudEvent::DropSlot(x) => {
+                        drop_slot(&mut self.client.borrow_mut(), &mut self.action_log, x);
+                    },
+                    HudEvent::DropAllOfType(item_def_id) => {
                         let mut client = self.client.borrow_mut();
-                        client.drop_slot(x);
-                        if let comp::slot::Slot::Equip(equip_slot) = x {
-                            if let comp::slot::EquipSlot::Lantern = equip_slot {
-                                client.disable_lantern();
-                            }
+                        let slots = client
+                            .inventories()
+                            .get(client.entity())
+                            .map(|inventory| {
+                                inventory
+                                    .slots()
+                                    .iter()
+                                    .enumerate()
+                                    .filter(|(_, item)| {
+                                        item.as_ref().map_or(false, |item| {
+                                            item.item_definition_id() == item_def_id
+                                        })
+                                    })
+                                    .map(|(i, _)| comp::slot::Slot::Inventory(i))
+                                    .collect::<Vec<_>>()
+                            })
+                            .unwrap_or_default();
+                        for slot in slots {
+                            drop_slot(&mut client, &mut self.action_log, slot);
                         }
                     },
                     HudEvent::ChangeHotbarState(state) => {
@@ -949,8 +3142,26 @@ impl PlayState for SessionState {
                     },
                     HudEvent::Ability3(state) => self.inputs.ability3.set_state(state),
                     HudEvent::ChangeFOV(new_fov) => {
-                        global_state.settings.graphics.fov = new_fov;
-                        global_state.settings.save_to_file_warn();
+                        // Changes apply to the character's override if it has one
+                        if self.fov_override.is_some()
+                            || global_state.settings.graphics.per_character_fov_gamma
+                        {
+                            let client = self.client.borrow();
+                            if let Some(character_id) = client.active_character_id {
+                                global_state
+                                    .profile
+                                    .get_character_profile_mut(
+                                        &client.server_info.name,
+                                        character_id,
+                                    )
+                                    .fov = Some(new_fov);
+                                global_state.profile.save_to_file_warn();
+                                self.fov_override = Some(new_fov);
+                            }
+                        } else {
+                            global_state.settings.graphics.fov = new_fov;
+                            global_state.settings.save_to_file_warn();
+                        }
                         self.scene.camera_mut().set_fov_deg(new_fov);
                         self.scene
                             .camera_mut()
@@ -961,22 +3172,47 @@ impl PlayState for SessionState {
                         global_state.settings.save_to_file_warn();
                     },
                     HudEvent::ChangeGamma(new_gamma) => {
-                        global_state.settings.graphics.gamma = new_gamma;
-                        global_state.settings.save_to_file_warn();
+                        if self.gamma_override.is_some()
+                            || global_state.settings.graphics.per_character_fov_gamma
+                        {
+                            let client = self.client.borrow();
+                            if let Some(character_id) = client.active_character_id {
+                                global_state
+                                    .profile
+                                    .get_character_profile_mut(
+                                        &client.server_info.name,
+                                        character_id,
+                                    )
+                                    .gamma = Some(new_gamma);
+                                global_state.profile.save_to_file_warn();
+                                self.gamma_override = Some(new_gamma);
+                            }
+                        } else {
+                            global_state.settings.graphics.gamma = new_gamma;
+                            global_state.settings.save_to_file_warn();
+                        }
                     },
                     HudEvent::ChangeAmbiance(new_ambiance) => {
                         global_state.settings.graphics.ambiance = new_ambiance;
                         global_state.settings.save_to_file_warn();
                     },
                     HudEvent::ChangeRenderMode(new_render_mode) => {
-                        // Do this first so if it crashes the setting isn't saved :)
-                        global_state
-                            .window
-                            .renderer_mut()
-                            .set_render_mode((&*new_render_mode).clone())
-                            .unwrap();
-                        global_state.settings.graphics.render_mode = *new_render_mode;
-                        global_state.settings.save_to_file_warn();
+                        // The setting is only saved once the renderer accepts the new mode
+                        if apply_render_mode(
+                            global_state,
+                            &mut self.last_good_render_mode,
+                            *new_render_mode,
+                        )
+                        .is_err()
+                        {
+                            self.hud.new_message(ChatMsg {
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.render_mode_failed")
+                                    .to_string(),
+                                chat_type: ChatType::CommandError,
+                            });
+                        }
                     },
                     HudEvent::ChangeLanguage(new_language) => {
                         global_state.settings.language.selected_language =
@@ -1012,6 +3248,63 @@ impl PlayState for SessionState {
                         global_state.settings.controls = ControlSettings::default();
                         global_state.settings.save_to_file_warn();
                     },
+                    HudEvent::ExportBindings(path) => {
+                        let message = match global_state.settings.controls.export_bindings(&path) {
+                            Ok(()) => ChatMsg {
+                                chat_type: ChatType::CommandInfo,
+                                message: self
+                                    .voxygen_i18n
+                                    .get("hud.chat.keybinds_exported")
+                                    .replace("{path}", &path.to_string_lossy()),
+                            },
+                            Err(e) => {
+                                warn!(?e, ?path, "Failed to export keybindings");
+                                ChatMsg {
+                                    chat_type: ChatType::CommandError,
+                                    message: self
+                                        .voxygen_i18n
+                                        .get("hud.chat.keybinds_export_failed")
+                                        .to_string(),
+                                }
+                            },
+                        };
+                        self.hud.new_message(message);
+                    },
+                    HudEvent::ImportBindings(path) => {
+                        let controls = &mut global_state.settings.controls;
+                        // Keep a backup of the current bindings before overwriting them
+                        let result = controls
+                            .export_bindings(&ControlSettings::get_bindings_backup_path())
+                            .and_then(|_| controls.import_bindings(&path));
+                        let message = match result {
+                            Ok(conflicts) => {
+                                global_state.settings.save_to_file_warn();
+                                ChatMsg {
+                                    chat_type: ChatType::CommandInfo,
+                                    message: if conflicts.is_empty() {
+                                        self.voxygen_i18n
+                                            .get("hud.chat.keybinds_imported")
+                                            .to_string()
+                                    } else {
+                                        self.voxygen_i18n
+                                            .get("hud.chat.keybinds_imported_conflicts")
+                                            .replace("{count}", &conflicts.len().to_string())
+                                    },
+                                }
+                            },
+                            Err(e) => {
+                                warn!(?e, ?path, "Failed to import keybindings");
+                                ChatMsg {
+                                    chat_type: ChatType::CommandError,
+                                    message: self
+                                        .voxygen_i18n
+                                        .get("hud.chat.keybinds_import_failed")
+                                        .to_string(),
+                                }
+                            },
+                        };
+                        self.hud.new_message(message);
+                    },
                     HudEvent::ChangeFreeLookBehavior(behavior) => {
                         global_state.settings.gameplay.free_look_behavior = behavior;
                     },
@@ -1021,7 +3314,32 @@ impl PlayState for SessionState {
                     HudEvent::ChangeStopAutoWalkOnInput(state) => {
                         global_state.settings.gameplay.stop_auto_walk_on_input = state;
                     },
+                    HudEvent::AdjustFreeflyAccel(accel) => {
+                        let freefly = &mut global_state.settings.gameplay.freefly;
+                        *freefly = FreeflySettings { accel, ..*freefly }.validated();
+                        global_state.settings.save_to_file_warn();
+                    },
+                    HudEvent::AdjustFreeflyDamping(damping) => {
+                        let freefly = &mut global_state.settings.gameplay.freefly;
+                        *freefly = FreeflySettings {
+                            damping,
+                            ..*freefly
+                        }
+                        .validated();
+                        global_state.settings.save_to_file_warn();
+                    },
+                    HudEvent::AdjustFreeflyMaxSpeed(max_speed) => {
+                        let freefly = &mut global_state.settings.gameplay.freefly;
+                        *freefly = FreeflySettings {
+                            max_speed,
+                            ..*freefly
+                        }
+                        .validated();
+                        global_state.settings.save_to_file_warn();
+                    },
                     HudEvent::CraftRecipe(r) => {
+                        self.action_log
+                            .log(&self.client.borrow(), "craft_recipe", &r);
                         self.client.borrow_mut().craft_recipe(&r);
                     },
                     HudEvent::InviteMember(uid) => {
@@ -1037,8 +3355,23 @@ impl PlayState for SessionState {
                         self.client.borrow_mut().kick_from_group(uid);
                     },
                     HudEvent::LeaveGroup => {
-                        self.client.borrow_mut().leave_group();
+                        if global_state.settings.gameplay.confirm_leave_group {
+                            let client = self.client.borrow();
+                            let is_leader = client
+                                .group_info()
+                                .map_or(false, |(_, leader)| client.uid() == Some(leader));
+                            self.hud.confirm_leave_group(Some(is_leader));
+                            self.leave_group_requested = Some(Instant::now());
+                        } else {
+                            self.client.borrow_mut().leave_group();
+                        }
                     },
+                    HudEvent::ConfirmLeaveGroup => {
+                        if self.leave_group_requested.take().is_some() {
+                            self.client.borrow_mut().leave_group();
+                        }
+                    },
+                    HudEvent::CancelLeaveGroup => self.leave_group_requested = None,
                     HudEvent::AssignLeader(uid) => {
                         self.client.borrow_mut().assign_group_leader(uid);
                     },
@@ -1055,18 +3388,49 @@ impl PlayState for SessionState {
                     view_distance: client.view_distance().unwrap_or(1),
                     tick: client.get_tick(),
                     thread_pool: client.thread_pool(),
-                    gamma: global_state.settings.graphics.gamma,
+                    gamma: self
+                        .gamma_override
+                        .unwrap_or(global_state.settings.graphics.gamma),
                     ambiance: global_state.settings.graphics.ambiance,
                     mouse_smoothing: global_state.settings.gameplay.smooth_pan_enable,
                     sprite_render_distance: global_state.settings.graphics.sprite_render_distance
                         as f32,
                     particles_enabled: global_state.settings.graphics.particles_enabled,
+                    max_particles_per_outcome: global_state
+                        .settings
+                        .gameplay
+                        .max_particles_per_outcome,
                     figure_lod_render_distance: global_state
                         .settings
                         .graphics
                         .figure_lod_render_distance
                         as f32,
-                    is_aiming,
+                    figure_detail_fractions: global_state.settings.graphics.figure_detail_fractions,
+                    figure_full_rate_distance: global_state
+                        .settings
+                        .graphics
+                        .figure_full_rate_distance
+                        as f32,
+                    own_lantern_light: global_state.settings.graphics.own_lantern_light,
+                    chunk_fade_in: global_state.settings.graphics.chunk_fade_in,
+                    is_aiming: aim_camera_shift,
+                    build_ghost: self
+                        .build_ghost
+                        .as_ref()
+                        .map(|(cells, block)| (cells.as_slice(), *block)),
+                    show_chunk_borders: show_chunk_borders(&global_state.settings),
+                    interact_range: self
+                        .show_interact_range
+                        .then(|| MAX_PICKUP_RANGE_SQR.sqrt()),
+                    world_dimmed: world_dimmed(&global_state.settings, &self.hud),
+                    targeting_ray: self.targeting_ray,
+                    build_grid: self.build_grid_pos,
+                    loot_beams: &self.loot_beams,
+                    show_first_person_model: self.show_first_person_model,
+                    freefly_rotation_smoothing: freefly_rotation_smoothing(&global_state.settings),
+                    horizon_lock: global_state.settings.gameplay.horizon_lock,
+                    shoulder_side: global_state.settings.gameplay.camera_shoulder,
+                    sfx_occlusion: global_state.settings.audio.sfx_occlusion,
                 };
 
                 // Runs if either in a multiplayer server or the singleplayer server is unpaused
@@ -1079,8 +3443,19 @@ impl PlayState for SessionState {
 
                     // Process outcomes from client
                     for outcome in outcomes {
-                        self.scene
-                            .handle_outcome(&outcome, &scene_data, &mut global_state.audio);
+                        let effects = global_state
+                            .settings
+                            .gameplay
+                            .outcome_effects
+                            .get(&outcome.category())
+                            .copied()
+                            .unwrap_or_default();
+                        self.scene.handle_outcome(
+                            &outcome,
+                            &scene_data,
+                            &mut global_state.audio,
+                            effects,
+                        );
                     }
                 }
             }
@@ -1090,12 +3465,15 @@ impl PlayState for SessionState {
 
             PlayStateResult::Continue
         } else if client_registered && client_in_game.is_none() {
+            // The character was changed, so stats start over in the next session
+            self.save_lifetime_stats(global_state);
             PlayStateResult::Switch(Box::new(CharSelectionState::new(
                 global_state,
                 Rc::clone(&self.client),
             )))
         } else {
             error!("Client not in the expected state, exiting session play state");
+            self.save_lifetime_stats(global_state);
             PlayStateResult::Pop
         }
     }
@@ -1119,13 +3497,35 @@ impl PlayState for SessionState {
                 view_distance: client.view_distance().unwrap_or(1),
                 tick: client.get_tick(),
                 thread_pool: client.thread_pool(),
-                gamma: settings.graphics.gamma,
+                gamma: self.gamma_override.unwrap_or(settings.graphics.gamma),
                 ambiance: settings.graphics.ambiance,
                 mouse_smoothing: settings.gameplay.smooth_pan_enable,
                 sprite_render_distance: settings.graphics.sprite_render_distance as f32,
                 figure_lod_render_distance: settings.graphics.figure_lod_render_distance as f32,
+                figure_detail_fractions: settings.graphics.figure_detail_fractions,
+                figure_full_rate_distance: settings.graphics.figure_full_rate_distance as f32,
+                own_lantern_light: settings.graphics.own_lantern_light,
+                chunk_fade_in: settings.graphics.chunk_fade_in,
                 particles_enabled: settings.graphics.particles_enabled,
-                is_aiming: self.is_aiming,
+                max_particles_per_outcome: settings.gameplay.max_particles_per_outcome,
+                is_aiming: self.aim_camera_shift,
+                build_ghost: self
+                    .build_ghost
+                    .as_ref()
+                    .map(|(cells, block)| (cells.as_slice(), *block)),
+                show_chunk_borders: show_chunk_borders(settings),
+                interact_range: self
+                    .show_interact_range
+                    .then(|| MAX_PICKUP_RANGE_SQR.sqrt()),
+                world_dimmed: world_dimmed(settings, &self.hud),
+                targeting_ray: self.targeting_ray,
+                build_grid: self.build_grid_pos,
+                loot_beams: &self.loot_beams,
+                show_first_person_model: self.show_first_person_model,
+                freefly_rotation_smoothing: freefly_rotation_smoothing(settings),
+                horizon_lock: settings.gameplay.horizon_lock,
+                shoulder_side: settings.gameplay.camera_shoulder,
+                sfx_occlusion: settings.audio.sfx_occlusion,
             };
             self.scene.render(
                 renderer,
@@ -1142,16 +3542,292 @@ impl PlayState for SessionState {
 
 /// Max distance an entity can be "targeted"
 const MAX_TARGET_RANGE: f32 = 300.0;
-/// Calculate what the cursor is pointing at within the 3d scene
+/// Largest scale of the interaction range for players who aren't admins
+const MAX_PLAYER_INTERACTION_RANGE: f32 = 2.0;
+/// Creatures within this many blocks of each other count as equally close
+/// to the target nearest key, which then picks the one most in front
+const TARGET_NEAREST_TIE_DIST: f32 = 2.0;
+/// Minimum time between two slot activation sounds
+const SLOT_SFX_DEBOUNCE: Duration = Duration::from_millis(150);
+/// How long another input device has to be used for before its key bindings
+/// are swapped in, so that alternating between devices doesn't swap back and
+/// forth
+const INPUT_DEVICE_SWITCH_DELAY: Duration = Duration::from_secs(2);
+/// Distance at which aim from a character with the camera over their shoulder
+/// lines up with the crosshair
+const AIM_CONVERGENCE_DISTANCE: f32 = 20.0;
+/// How quickly the look direction turns towards a locked target, as the
+/// fraction of the remaining angle per second
+const TARGET_LOCK_TRACKING: f32 = 10.0;
+/// How quickly the character turns towards their walking direction when facing
+/// the movement direction, as the fraction of the remaining angle per second
+const FACE_MOVEMENT_TRACKING: f32 = 8.0;
+/// Most notifications held back while do not disturb is on, older ones are
+/// dropped
+const MAX_DND_NOTIFICATIONS: usize = 20;
+/// How long after the last pickup of an item merged loot messages are posted
+const LOOT_BATCH_WINDOW: Duration = Duration::from_secs(2);
+/// Lowest quality of items that are always announced, whatever the loot
+/// message settings
+const RARE_LOOT_QUALITY: Quality = Quality::High;
+/// Distance from the selected creature at which running at it stops, to leave
+/// positioning for melee to the player
+const APPROACH_MELEE_RANGE: f32 = 3.0;
+/// Deepest drop in blocks that running at the selected creature goes down
+const APPROACH_MAX_DROP: i32 = 3;
+/// How close to the click-to-move destination counts as having arrived
+const CLICK_TO_MOVE_ARRIVAL_RADIUS: f32 = 0.75;
+/// How long to walk towards a click-to-move destination before assuming it
+/// can't be reached
+const CLICK_TO_MOVE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Shortest time between two uses of the quick use key, so holding or mashing
+/// it doesn't use up a whole stack. Consumables don't have a cooldown of their
+/// own.
+const QUICK_CONSUME_COOLDOWN: Duration = Duration::from_millis(1000);
+/// How long after zooming by hand the camera distance isn't switched for
+/// combat
+const MANUAL_ZOOM_GRACE: Duration = Duration::from_secs(10);
+/// How long the summary shown before leaving the game stays up if it isn't
+/// dismissed
+const LEAVE_SUMMARY_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long the confirmation to leave the group waits for an answer before
+/// it's cancelled
+const LEAVE_GROUP_CONFIRM_TIMEOUT: Duration = Duration::from_secs(10);
+/// Most blocks placed at once with the line tool, so long lines don't flood
+/// the server
+const MAX_BUILD_LINE_LENGTH: usize = 32;
+/// How far away dropped items get a loot beam
+const LOOT_BEAM_RANGE: f32 = 64.0;
+/// Most loot beams drawn at once, the closest items get them
+const MAX_LOOT_BEAMS: usize = 16;
+/// Most players tagged by the scout overlay, the closest ones are
+const MAX_SCOUT_TARGETS: usize = 64;
+/// Depth of fluid above which the weapon is sheathed for swimming
+const SWIM_SHEATHE_DEPTH: f32 = 1.0;
+/// Depth of fluid below which the weapon is wielded again, well under
+/// `SWIM_SHEATHE_DEPTH` so wading at about that depth doesn't flip between them
+const SWIM_WIELD_DEPTH: f32 = 0.5;
+
+/// Chat command moving the player to `pos`, which needs admin rights.
+fn goto_command(pos: Vec3<f32>) -> String {
+    format!(
+        "/{} {} {} {}",
+        ChatCommand::Goto.keyword(),
+        pos.x,
+        pos.y,
+        pos.z
+    )
+}
+
+/// Drop the item in `slot`, turning the lantern off if it was the one dropped.
+fn drop_slot(client: &mut Client, action_log: &mut ActionLog, slot: comp::slot::Slot) {
+    action_log.log(client, "drop_slot", slot);
+    client.drop_slot(slot);
+    if let comp::slot::Slot::Equip(comp::slot::EquipSlot::Lantern) = slot {
+        client.disable_lantern();
+    }
+}
+
+/// Message for picking up `count` of the item called `name`. A single item
+/// gets its own wording, so languages can phrase it naturally.
+fn loot_msg(voxygen_i18n: &VoxygenLocalization, name: &str, count: u32) -> String {
+    let message = if count > 1 {
+        voxygen_i18n
+            .get("hud.chat.loot_batch_msg")
+            .replace("{count}", &count.to_string())
+    } else {
+        voxygen_i18n.get("hud.chat.loot_msg").to_string()
+    };
+    message.replace("{item}", name)
+}
+
+fn world_dimmed(settings: &Settings, hud: &Hud) -> bool {
+    settings.gameplay.dim_world_when_inventory_open && hud.large_panel_open()
+}
+
+/// The beams to draw over nearby dropped items of the quality set in the
+/// settings, closest first.
+fn loot_beams(client: &Client, settings: &Settings) -> Vec<LootBeam> {
+    let min_quality = match settings.gameplay.loot_beam_min_quality {
+        Some(quality) => quality,
+        None => return Vec::new(),
+    };
+    let player_pos = match client
+        .state()
+        .read_storage::<comp::Pos>()
+        .get(client.entity())
+    {
+        Some(pos) => pos.0,
+        None => return Vec::new(),
+    };
+
+    let ecs = client.state().ecs();
+    let mut beams = (
+        &ecs.read_storage::<comp::Pos>(),
+        &ecs.read_storage::<comp::Item>(),
+    )
+        .join()
+        .map(|(pos, item)| (pos.0, item.quality(), pos.0.distance(player_pos)))
+        .filter(|(_, quality, dist)| *quality >= min_quality && *dist < LOOT_BEAM_RANGE)
+        .map(|(pos, quality, dist)| LootBeam {
+            pos,
+            col: settings
+                .gameplay
+                .loot_beam_colors
+                .get(&quality)
+                .map_or(Rgb::broadcast(1.0), |col| {
+                    Rgb::new(col[0], col[1], col[2]).map(|e| e as f32 / 255.0)
+                }),
+            opacity: 1.0 - dist / LOOT_BEAM_RANGE,
+        })
+        .collect::<Vec<_>>();
+    // Closer beams are more opaque
+    beams.sort_unstable_by(|a, b| b.opacity.partial_cmp(&a.opacity).unwrap());
+    beams.truncate(MAX_LOOT_BEAMS);
+    beams
+}
+
+/// Names of the other group members, sorted and leaving out pets, with where
+/// they are and how far away. Members out of range of the client have no
+/// position.
+fn group_coordinates(client: &Client) -> Vec<(String, Option<(Vec3<f32>, f32)>)> {
+    let ecs = client.state().ecs();
+    let positions = ecs.read_storage::<comp::Pos>();
+    let player_pos = positions.get(client.entity()).map(|pos| pos.0);
+    let mut members = client
+        .group_members()
+        .iter()
+        .filter(|(_, role)| matches!(role, comp::group::Role::Member))
+        .map(|(uid, _)| {
+            let name = client.player_list.get(uid).map_or_else(
+                || format!("Player<{}>", uid),
+                |info| info.player_alias.clone(),
+            );
+            let pos = ecs
+                .entity_from_uid(uid.0)
+                .and_then(|entity| positions.get(entity))
+                .map(|pos| pos.0);
+            let located = pos
+                .zip(player_pos)
+                .map(|(pos, player_pos)| (pos, pos.distance(player_pos)));
+            (name, located)
+        })
+        .collect::<Vec<_>>();
+    members.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    members
+}
+
+/// Names of the other players loaded on the client and where to tag them,
+/// closest first. Players the server hides from the client aren't loaded, so
+/// they never show up.
+fn scout_targets(client: &Client) -> Vec<(String, Vec3<f32>)> {
+    let player_pos = match client
+        .state()
+        .read_storage::<comp::Pos>()
+        .get(client.entity())
+    {
+        Some(pos) => pos.0,
+        None => return Vec::new(),
+    };
+
+    let ecs = client.state().ecs();
+    let bodies = ecs.read_storage::<comp::Body>();
+    let mut targets = (
+        &ecs.entities(),
+        &ecs.read_storage::<comp::Pos>(),
+        &ecs.read_storage::<comp::Player>(),
+    )
+        .join()
+        .filter(|(entity, _, _)| *entity != client.entity())
+        .map(|(entity, pos, player)| {
+            // Tagged above their head
+            let height = bodies.get(entity).map_or(2.0, |body| body.height());
+            (
+                player.alias.clone(),
+                pos.0 + Vec3::unit_z() * (height + 1.0),
+            )
+        })
+        .collect::<Vec<_>>();
+    targets.sort_unstable_by(|(_, a), (_, b)| {
+        a.distance_squared(player_pos)
+            .partial_cmp(&b.distance_squared(player_pos))
+            .unwrap()
+    });
+    targets.truncate(MAX_SCOUT_TARGETS);
+    targets
+}
+
+fn show_targeting_ray(settings: &Settings) -> bool {
+    settings.gameplay.show_targeting_ray && settings.gameplay.toggle_debug
+}
+
+/// Chunk borders are a debugging aid, so they are only drawn alongside the
+/// debug overlay.
+fn show_chunk_borders(settings: &Settings) -> bool {
+    settings.gameplay.show_chunk_borders && settings.gameplay.toggle_debug
+}
+
+/// The freefly rotation smoothing to use. High pan sensitivities are used for
+/// quick scouting, so smoothing is skipped for them.
+fn freefly_rotation_smoothing(settings: &Settings) -> f32 {
+    const MAX_SMOOTHED_PAN_SENSITIVITY: u32 = 150;
+    if settings.gameplay.pan_sensitivity > MAX_SMOOTHED_PAN_SENSITIVITY {
+        0.0
+    } else {
+        settings.gameplay.freefly_rotation_smoothing
+    }
+}
+
+/// Apply `render_mode` and save it to the settings. If the renderer rejects it,
+/// `last_good` is restored and the settings are left untouched.
+fn apply_render_mode(
+    global_state: &mut GlobalState,
+    last_good: &mut RenderMode,
+    render_mode: RenderMode,
+) -> Result<(), RenderError> {
+    let renderer = global_state.window.renderer_mut();
+    if let Err(err) = renderer.set_render_mode(render_mode.clone()) {
+        error!(?err, ?render_mode, "Failed to apply render mode, reverting");
+        if let Err(err) = renderer.set_render_mode(last_good.clone()) {
+            error!(
+                ?err,
+                ?last_good,
+                "Failed to revert to the last working render mode"
+            );
+        }
+        return Err(err);
+    }
+    *last_good = render_mode.clone();
+    global_state.settings.graphics.render_mode = render_mode;
+    global_state.settings.save_to_file_warn();
+    Ok(())
+}
+
+/// Scale of the reach for the cursor from the settings. The server decides
+/// what actually is in reach, so this is limited on the client whatever
+/// server it is on.
+fn interaction_range(settings: &Settings, client: &Client) -> f32 {
+    let range = settings.gameplay.interaction_range.max(0.0);
+    if client.is_admin() {
+        range
+    } else {
+        range.min(MAX_PLAYER_INTERACTION_RANGE)
+    }
+}
+
+/// Calculate what the cursor is pointing at within the 3d scene, with the
+/// reach scaled by `range`
 #[allow(clippy::type_complexity)]
 fn under_cursor(
     client: &Client,
     cam_pos: Vec3<f32>,
     cam_dir: Vec3<f32>,
+    range: f32,
 ) -> (
     Option<Vec3<i32>>,
     Option<Vec3<i32>>,
     Option<(specs::Entity, f32)>,
+    Option<TargetingRay>,
 ) {
     // Choose a spot above the player's head for item distance checks
     let player_entity = client.entity();
@@ -1166,7 +3842,7 @@ fn under_cursor(
     let terrain = client.state().terrain();
 
     let cam_ray = terrain
-        .ray(cam_pos, cam_pos + cam_dir * 100.0)
+        .ray(cam_pos, cam_pos + cam_dir * (100.0 * range))
         .until(|block| block.is_filled() || block.is_collectible())
         .cast();
 
@@ -1175,7 +3851,7 @@ fn under_cursor(
     // The ray hit something, is it within range?
     let (build_pos, select_pos) = if matches!(cam_ray.1, Ok(Some(_)) if
         player_pos.distance_squared(cam_pos + cam_dir * cam_dist)
-        <= MAX_PICKUP_RANGE_SQR)
+        <= MAX_PICKUP_RANGE_SQR * range.powi(2))
     {
         (
             Some((cam_pos + cam_dir * (cam_dist - 0.01)).map(|e| e.floor() as i32)),
@@ -1190,10 +3866,11 @@ fn under_cursor(
     let ecs = client.state().ecs();
     // Don't cast through blocks
     // Could check for intersection with entity from last frame to narrow this down
+    let max_target_range = MAX_TARGET_RANGE * range;
     let cast_dist = if let Ok(Some(_)) = cam_ray.1 {
-        cam_dist.min(MAX_TARGET_RANGE)
+        cam_dist.min(max_target_range)
     } else {
-        MAX_TARGET_RANGE
+        max_target_range
     };
 
     // Need to raycast by distance to cam
@@ -1231,16 +3908,662 @@ fn under_cursor(
         end: cam_pos + cam_dir * cam_dist,
     };
     // TODO: fuzzy borders
-    let target_entity = nearby
+    let target = nearby
         .iter()
         .map(|(e, p, r, _)| (e, *p, r))
         // Find first one that intersects the ray segment
         .find(|(_, p, r)| seg_ray.projected_point(*p).distance_squared(*p) < r.powi(2))
-        .and_then(|(e, p, r)| {
-            let dist_to_player = p.distance(player_pos);
-            (dist_to_player - r < MAX_TARGET_RANGE).then_some((*e, dist_to_player))
-        });
+        .filter(|(_, p, r)| p.distance(player_pos) - *r < max_target_range);
+    let target_entity = target.map(|(e, p, _)| (*e, p.distance(player_pos)));
+
+    let targeting_ray = TargetingRay {
+        start: seg_ray.start,
+        end: seg_ray.end,
+        terrain_hit: matches!(cam_ray.1, Ok(Some(_))).then_some(seg_ray.end),
+        entity_sphere: target.map(|(_, p, r)| (p, *r)),
+    };
 
     // TODO: consider setting build/select to None when targeting an entity
-    (build_pos, select_pos, target_entity)
+    (build_pos, select_pos, target_entity, Some(targeting_ray))
+}
+
+/// Inventory index, name and amount of the consumable linked to a hotbar slot
+/// with `contents`, if it holds one.
+fn hotbar_consumable(
+    client: &Client,
+    contents: Option<HotbarSlotContents>,
+) -> Option<(usize, String, u32)> {
+    let index = match contents {
+        Some(HotbarSlotContents::Inventory(index)) => index,
+        _ => return None,
+    };
+    let inventories = client.inventories();
+    let item = inventories.get(client.entity())?.get(index)?;
+    matches!(item.kind(), ItemKind::Consumable { .. })
+        .then(|| (index, item.name().to_string(), item.amount()))
+}
+
+/// Whether players without admin rights may place blocks of `kind`. Fluids
+/// can't be placed, and rock is kept for the world's foundations.
+fn is_placeable(kind: BlockKind) -> bool {
+    matches!(
+        kind,
+        BlockKind::WeakRock
+            | BlockKind::Grass
+            | BlockKind::Earth
+            | BlockKind::Sand
+            | BlockKind::Wood
+            | BlockKind::Leaves
+            | BlockKind::Misc
+    )
+}
+
+/// Cells of the voxel line from `from` to `to` the line tool places blocks in.
+/// The line is cut off after `MAX_BUILD_LINE_LENGTH` cells, and cells that are
+/// out of build range, scaled by `range` as for the cursor, or already filled
+/// are skipped.
+fn build_line(client: &Client, from: Vec3<i32>, to: Vec3<i32>, range: f32) -> Vec<Vec3<i32>> {
+    let player_pos = match client
+        .state()
+        .read_storage::<comp::Pos>()
+        .get(client.entity())
+    {
+        // The same spot above the player's head used for the range of single blocks
+        Some(pos) => pos.0 + Vec3::unit_z() * 2.0,
+        None => return Vec::new(),
+    };
+    let terrain = client.state().terrain();
+
+    voxel_line(from, to, MAX_BUILD_LINE_LENGTH)
+        .into_iter()
+        .filter(|pos| {
+            player_pos.distance_squared(pos.map(|e| e as f32 + 0.5))
+                <= MAX_PICKUP_RANGE_SQR * range.powi(2)
+        })
+        .filter(|pos| terrain.get(*pos).map_or(false, |block| !block.is_filled()))
+        .collect()
+}
+
+/// Cells of the voxel line from `from` to `to`, using 3D Bresenham, cut off
+/// after `max_len` cells.
+fn voxel_line(from: Vec3<i32>, to: Vec3<i32>, max_len: usize) -> Vec<Vec3<i32>> {
+    let delta = to - from;
+    let step = delta.map(|e| e.signum());
+    let abs = delta.map(|e| e.abs());
+    let steps = abs.reduce_max();
+    // Errors of the two minor axes, scaled so they can stay integers
+    let mut err = abs.map(|e| 2 * e - steps);
+    let mut pos = from;
+    let mut cells = Vec::new();
+    for _ in 0..(steps as usize + 1).min(max_len) {
+        cells.push(pos);
+        for i in 0..3 {
+            if err[i] > 0 {
+                pos[i] += step[i];
+                err[i] -= 2 * steps;
+            }
+            err[i] += 2 * abs[i];
+        }
+    }
+    cells
+}
+
+/// Energy left as a fraction of the maximum, if the player is climbing.
+fn climb_stamina(client: &Client) -> Option<f32> {
+    let ecs = client.state().ecs();
+    let entity = client.entity();
+    let climbing = matches!(
+        ecs.read_storage::<comp::CharacterState>().get(entity),
+        Some(comp::CharacterState::Climb)
+    );
+    if !climbing {
+        return None;
+    }
+    ecs.read_storage::<comp::Energy>()
+        .get(entity)
+        .filter(|energy| energy.maximum() > 0)
+        .map(|energy| energy.current() as f32 / energy.maximum() as f32)
+}
+
+/// The closest living creature hostile to the player within targeting range.
+/// Of those about as close, the one most in front of the camera, which faces
+/// `cam_dir`, wins.
+fn nearest_hostile(client: &Client, cam_dir: Vec3<f32>) -> Option<specs::Entity> {
+    let ecs = client.state().ecs();
+    let positions = ecs.read_storage::<comp::Pos>();
+    let player_pos = positions.get(client.entity())?.0;
+    let player_alignment = comp::Alignment::Owned(client.uid()?);
+    (
+        &ecs.entities(),
+        &positions,
+        &ecs.read_storage::<comp::Stats>(),
+        &ecs.read_storage::<comp::Alignment>(),
+    )
+        .join()
+        .filter(|(entity, pos, stats, alignment)| {
+            *entity != client.entity()
+                && !stats.is_dead
+                && alignment.hostile_towards(player_alignment)
+                && pos.0.distance_squared(player_pos) < MAX_TARGET_RANGE.powi(2)
+        })
+        .map(|(entity, pos, _, _)| {
+            let offset = pos.0 - player_pos;
+            let closeness = (offset.magnitude() / TARGET_NEAREST_TIE_DIST) as i32;
+            let facing = offset.try_normalized().map_or(0.0, |dir| dir.dot(cam_dir));
+            (entity, closeness, facing)
+        })
+        .min_by(|(_, a_closeness, a_facing), (_, b_closeness, b_facing)| {
+            a_closeness
+                .cmp(b_closeness)
+                .then_with(|| b_facing.partial_cmp(a_facing).unwrap_or(Ordering::Equal))
+        })
+        .map(|(entity, _, _)| entity)
+}
+
+/// Direction from the player to a locked `target`, or `None` if the lock
+/// should be released because the target is gone, dead or out of range.
+fn target_lock_dir(client: &Client, target: specs::Entity) -> Option<Vec3<f32>> {
+    let ecs = client.state().ecs();
+    if ecs
+        .read_storage::<comp::Stats>()
+        .get(target)
+        .map_or(false, |stats| stats.is_dead)
+    {
+        return None;
+    }
+    let positions = ecs.read_storage::<comp::Pos>();
+    let bodies = ecs.read_storage::<comp::Body>();
+    let center = |entity| {
+        let pos = positions.get(entity)?.0;
+        Some(pos + Vec3::unit_z() * bodies.get(entity).map_or(0.0, |body| body.height() * 0.5))
+    };
+    let from = center(client.entity())?;
+    let to = center(target)?;
+    (from.distance_squared(to) < MAX_TARGET_RANGE.powi(2)).then_some(to - from)
+}
+
+/// Direction to run in to approach `target`, or `None` if the player should
+/// steer themselves: when the target isn't a living creature outside of the
+/// player's group, is within melee range, or there is a drop ahead.
+fn approach_dir(client: &Client, target: specs::Entity) -> Option<Vec2<f32>> {
+    let ecs = client.state().ecs();
+    let player = client.entity();
+    let alive = ecs
+        .read_storage::<comp::Stats>()
+        .get(target)
+        .map_or(false, |stats| !stats.is_dead);
+    let groups = ecs.read_storage::<comp::Group>();
+    let same_group = groups.get(target).is_some() && groups.get(target) == groups.get(player);
+    let is_player = ecs.read_storage::<comp::Player>().get(target).is_some();
+    if target == player || !alive || same_group || is_player {
+        return None;
+    }
+
+    let positions = ecs.read_storage::<comp::Pos>();
+    let from = positions.get(player)?.0;
+    let offset = (positions.get(target)?.0 - from).xy();
+    if offset.magnitude_squared() < APPROACH_MELEE_RANGE.powi(2) {
+        return None;
+    }
+    let dir = offset.normalized();
+
+    // Stop at the edge of a drop rather than running off it
+    let terrain = client.state().terrain();
+    let ahead = (from + Vec3::from(dir) * 1.5).map(|e| e.floor() as i32);
+    let ground_ahead = (1..=APPROACH_MAX_DROP).any(|depth| {
+        terrain
+            .get(ahead - Vec3::unit_z() * depth)
+            .map_or(false, |block| block.is_filled())
+    });
+    ground_ahead.then_some(dir)
+}
+
+/// Direction to walk in to reach the click-to-move `destination`, or `None`
+/// once close enough or when the player has drawn their weapon.
+fn click_to_move_dir(client: &Client, destination: Vec3<f32>) -> Option<Vec2<f32>> {
+    if is_wielding(client) {
+        return None;
+    }
+    let pos = client
+        .state()
+        .read_storage::<comp::Pos>()
+        .get(client.entity())?
+        .0;
+    let offset = (destination - pos).xy();
+    (offset.magnitude_squared() > CLICK_TO_MOVE_ARRIVAL_RADIUS.powi(2)).then(|| offset.normalized())
+}
+
+fn is_wielding(client: &Client) -> bool {
+    client
+        .state()
+        .read_storage::<comp::CharacterState>()
+        .get(client.entity())
+        .map_or(false, |cs| cs.is_wield())
+}
+
+/// Whether `entity` can be mounted, and whether it can be picked up
+fn entity_interactions(client: &Client, entity: specs::Entity) -> (bool, bool) {
+    let ecs = client.state().ecs();
+    let mountable = ecs
+        .read_storage::<comp::MountState>()
+        .get(entity)
+        .map_or(false, |ms| *ms == comp::MountState::Unmounted);
+    let lootable = ecs.read_storage::<comp::Item>().get(entity).is_some();
+    (mountable, lootable)
+}
+
+fn any_input_pressed(inputs: &comp::ControllerInputs) -> bool {
+    [
+        &inputs.primary,
+        &inputs.secondary,
+        &inputs.ability3,
+        &inputs.jump,
+        &inputs.roll,
+        &inputs.glide,
+        &inputs.wall_leap,
+        &inputs.charge,
+        &inputs.swimup,
+        &inputs.swimdown,
+    ]
+    .iter()
+    .any(|input| input.is_pressed())
+}
+
+/// Shift how long each pressed input has been held by `compensation`, so hold
+/// thresholds are reached on the server about as early as they would be
+/// without network latency
+fn compensate_inputs(
+    inputs: &comp::ControllerInputs,
+    compensation: Duration,
+) -> comp::ControllerInputs {
+    let mut inputs = inputs.clone();
+    for input in [
+        &mut inputs.primary,
+        &mut inputs.secondary,
+        &mut inputs.ability3,
+        &mut inputs.jump,
+        &mut inputs.roll,
+        &mut inputs.glide,
+        &mut inputs.wall_leap,
+        &mut inputs.charge,
+        &mut inputs.swimup,
+        &mut inputs.swimdown,
+    ]
+    .iter_mut()
+    .filter(|input| input.is_pressed())
+    {
+        input.inc_dur(compensation);
+    }
+    inputs
+}
+
+/// Project the point where a ray from the camera along `look_dir` lands onto
+/// the screen, in normalized device coordinates. Points behind the camera are
+/// pushed off the edge of the screen on the opposite side.
+fn aim_point_on_screen(client: &Client, camera: &camera::Camera, look_dir: Vec3<f32>) -> Vec2<f32> {
+    let camera::Dependents {
+        view_mat,
+        proj_mat,
+        cam_pos,
+        ..
+    } = camera.dependents();
+    let focus_off = camera.get_focus_pos().map(|e| e.trunc());
+    let cam_pos = cam_pos + focus_off;
+    let dist = client
+        .state()
+        .terrain()
+        .ray(cam_pos, cam_pos + look_dir * 100.0)
+        .until(|block| block.is_filled())
+        .cast()
+        .0;
+    let clip = proj_mat * view_mat * Vec4::from_point(cam_pos + look_dir * dist - focus_off);
+    let ndc = Vec2::new(clip.x, clip.y);
+    if clip.w > 0.0 {
+        ndc / clip.w
+    } else {
+        // Anything past the edge of the screen is clamped there
+        -ndc.try_normalized().unwrap_or_else(Vec2::unit_y) * 2.0
+    }
+}
+
+/// Summarize the gear of `entity`, unless it is the player or has no loadout
+/// synced to the client.
+fn inspected_gear(client: &Client, entity: specs::Entity) -> Option<InspectedGear> {
+    if entity == client.entity() {
+        return None;
+    }
+    let ecs = client.state().ecs();
+    let loadouts = ecs.read_storage::<comp::Loadout>();
+    let loadout = loadouts.get(entity)?;
+    let name = ecs
+        .read_storage::<comp::Stats>()
+        .get(entity)
+        .map_or_else(String::new, |stats| stats.name.clone());
+
+    let main_hand = loadout.active_item.as_ref().map(|config| &config.item);
+    let off_hand = loadout.second_item.as_ref().map(|config| &config.item);
+    let slots = [
+        ("hud.bag.mainhand", main_hand),
+        ("hud.bag.offhand", off_hand),
+        ("hud.bag.head", loadout.head.as_ref()),
+        ("hud.bag.neck", loadout.neck.as_ref()),
+        ("hud.bag.shoulders", loadout.shoulder.as_ref()),
+        ("hud.bag.chest", loadout.chest.as_ref()),
+        ("hud.bag.tabard", loadout.tabard.as_ref()),
+        ("hud.bag.hands", loadout.hand.as_ref()),
+        ("hud.bag.ring", loadout.ring.as_ref()),
+        ("hud.bag.belt", loadout.belt.as_ref()),
+        ("hud.bag.legs", loadout.pants.as_ref()),
+        ("hud.bag.feet", loadout.foot.as_ref()),
+        ("hud.bag.back", loadout.back.as_ref()),
+        ("hud.bag.lantern", loadout.lantern.as_ref()),
+        ("hud.bag.glider", loadout.glider.as_ref()),
+    ]
+    .iter()
+    .filter_map(|(slot, item)| item.map(|item| (*slot, item.name().to_owned())))
+    .collect();
+
+    Some(InspectedGear { name, slots })
+}
+
+/// An attack button press made while the last attack was recovering.
+#[derive(Clone, Copy)]
+enum BufferedInput {
+    /// Still held, so it starts the next attack by itself
+    Held(Instant),
+    /// Released again, the input is kept pressed until the next attack starts
+    Released(Instant),
+    /// Kept pressed for the frame the next attack can start in
+    Firing,
+}
+
+/// Buffer a change of an attack button to `state` if the last attack is
+/// `recovering`. Returns whether the change was buffered, otherwise it should
+/// be applied as usual.
+fn buffer_input(
+    buffered: &mut Option<BufferedInput>,
+    state: bool,
+    recovering: bool,
+    buffer_ms: u32,
+) -> bool {
+    match (*buffered, state) {
+        (Some(BufferedInput::Held(pressed)), false) => {
+            *buffered = Some(BufferedInput::Released(pressed));
+            true
+        },
+        (_, true) if recovering && buffer_ms > 0 => {
+            *buffered = Some(BufferedInput::Held(Instant::now()));
+            false
+        },
+        (Some(_), true) => {
+            *buffered = None;
+            false
+        },
+        _ => false,
+    }
+}
+
+/// Advance a buffered attack button press, pressing `input` for one frame once
+/// the attack can start, or dropping it if that takes longer than
+/// `buffer_duration`.
+fn release_buffered_input(
+    buffered: &mut Option<BufferedInput>,
+    input: &mut comp::Input,
+    recovering: bool,
+    buffer_duration: Duration,
+) {
+    *buffered = match *buffered {
+        Some(BufferedInput::Held(_)) if !recovering => None,
+        Some(BufferedInput::Released(pressed)) if pressed.elapsed() > buffer_duration => {
+            input.set_state(false);
+            None
+        },
+        Some(BufferedInput::Released(_)) if !recovering => Some(BufferedInput::Firing),
+        Some(BufferedInput::Firing) => {
+            input.set_state(false);
+            None
+        },
+        other => other,
+    };
+}
+
+/// Step the weapon sheathed for swimming, given whether it should be wielded
+/// after the swim (`None` while not swimming), the depth of the fluid the
+/// player is in and whether they wield it now. Returns the new state and
+/// whether wielding should be toggled.
+fn swim_wield(
+    wield_after_swim: Option<bool>,
+    in_fluid: Option<f32>,
+    wielding: bool,
+) -> (Option<bool>, bool) {
+    let depth = in_fluid.unwrap_or(0.0);
+    match wield_after_swim {
+        None if depth > SWIM_SHEATHE_DEPTH => (Some(wielding), wielding),
+        Some(wield) if depth < SWIM_WIELD_DEPTH => (None, wield && !wielding),
+        other => (other, false),
+    }
+}
+
+/// Find the collectible blocks and items within pickup range of the player,
+/// sorted from nearest to farthest
+fn nearby_interactables(client: &Client) -> Vec<Interactable> {
+    let player_pos = match client
+        .state()
+        .read_storage::<comp::Pos>()
+        .get(client.entity())
+    {
+        Some(pos) => pos.0,
+        None => return Vec::new(),
+    };
+    let terrain = client.state().terrain();
+    let radius = MAX_PICKUP_RANGE_SQR.sqrt().ceil() as i32;
+    let player_block = player_pos.map(|e| e.floor() as i32);
+
+    let mut interactables = Vec::new();
+    for x in -radius..=radius {
+        for y in -radius..=radius {
+            for z in -radius..=radius {
+                let pos = player_block + Vec3::new(x, y, z);
+                let dist_sqr = pos.map(|e| e as f32 + 0.5).distance_squared(player_pos);
+                if dist_sqr < MAX_PICKUP_RANGE_SQR
+                    && terrain
+                        .get(pos)
+                        .map(|b| b.is_collectible())
+                        .unwrap_or(false)
+                {
+                    interactables.push((Interactable::Block(pos), dist_sqr));
+                }
+            }
+        }
+    }
+
+    let ecs = client.state().ecs();
+    interactables.extend(
+        (
+            &ecs.entities(),
+            &ecs.read_storage::<comp::Pos>(),
+            &ecs.read_storage::<comp::Item>(),
+        )
+            .join()
+            .map(|(entity, pos, _)| (entity, pos.0.distance_squared(player_pos)))
+            .filter(|(_, dist_sqr)| *dist_sqr < MAX_PICKUP_RANGE_SQR)
+            .map(|(entity, dist_sqr)| (Interactable::Entity(entity), dist_sqr)),
+    );
+
+    interactables.sort_unstable_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    interactables.into_iter().map(|(i, _)| i).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swim_wield_sheathes_and_wields_again() {
+        assert_eq!(swim_wield(None, Some(1.5), true), (Some(true), true));
+        assert_eq!(
+            swim_wield(Some(true), Some(1.5), false),
+            (Some(true), false)
+        );
+        assert_eq!(swim_wield(Some(true), None, false), (None, true));
+    }
+
+    #[test]
+    fn swim_wield_stays_sheathed_while_wading() {
+        // Standing on the bottom doesn't count as out of the water
+        let mut state = None;
+        let mut wielding = true;
+        for _ in 0..4 {
+            let (next, toggle) = swim_wield(state, Some(1.2), wielding);
+            state = next;
+            wielding ^= toggle;
+        }
+        assert_eq!(state, Some(true));
+        assert!(!wielding);
+
+        // Between the two depths nothing changes
+        assert_eq!(swim_wield(state, Some(0.7), wielding), (Some(true), false));
+        assert_eq!(swim_wield(None, Some(0.7), true), (None, false));
+    }
+
+    #[test]
+    fn swim_wield_keeps_unwielded_weapon_sheathed() {
+        assert_eq!(swim_wield(None, Some(1.5), false), (Some(false), false));
+        assert_eq!(swim_wield(Some(false), Some(0.2), false), (None, false));
+    }
+
+    #[test]
+    fn voxel_line_along_axis() {
+        assert_eq!(
+            voxel_line(Vec3::new(0, 0, 0), Vec3::new(0, -3, 0), 32),
+            vec![
+                Vec3::new(0, 0, 0),
+                Vec3::new(0, -1, 0),
+                Vec3::new(0, -2, 0),
+                Vec3::new(0, -3, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn voxel_line_diagonal() {
+        assert_eq!(
+            voxel_line(Vec3::new(0, 0, 0), Vec3::new(2, 2, 2), 32),
+            vec![Vec3::new(0, 0, 0), Vec3::new(1, 1, 1), Vec3::new(2, 2, 2)]
+        );
+        assert_eq!(
+            voxel_line(Vec3::new(0, 0, 0), Vec3::new(4, 2, 0), 32),
+            vec![
+                Vec3::new(0, 0, 0),
+                Vec3::new(1, 0, 0),
+                Vec3::new(2, 1, 0),
+                Vec3::new(3, 1, 0),
+                Vec3::new(4, 2, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn voxel_line_single_cell() {
+        let pos = Vec3::new(5, -2, 7);
+        assert_eq!(voxel_line(pos, pos, 32), vec![pos]);
+    }
+
+    #[test]
+    fn voxel_line_cut_off_after_max_len() {
+        let cells = voxel_line(Vec3::new(0, 0, 0), Vec3::new(10, 0, 0), 4);
+        assert_eq!(cells.len(), 4);
+        assert_eq!(cells.last(), Some(&Vec3::new(3, 0, 0)));
+    }
+
+    const BUFFER_MS: u32 = 200;
+    const BUFFER_DURATION: Duration = Duration::from_millis(BUFFER_MS as u64);
+
+    /// Change the state of an attack button as the session does
+    fn set_attack(
+        buffered: &mut Option<BufferedInput>,
+        input: &mut comp::Input,
+        state: bool,
+        recovering: bool,
+    ) {
+        if !buffer_input(buffered, state, recovering, BUFFER_MS) {
+            input.set_state(state);
+        }
+    }
+
+    #[test]
+    fn buffered_press_held_through_recovery() {
+        let mut buffered = None;
+        let mut input = comp::Input::default();
+        set_attack(&mut buffered, &mut input, true, true);
+        assert!(matches!(buffered, Some(BufferedInput::Held(_))));
+        assert!(input.is_pressed());
+
+        release_buffered_input(&mut buffered, &mut input, true, BUFFER_DURATION);
+        assert!(matches!(buffered, Some(BufferedInput::Held(_))));
+
+        // Still held, so it starts the next attack by itself
+        release_buffered_input(&mut buffered, &mut input, false, BUFFER_DURATION);
+        assert!(buffered.is_none());
+        assert!(input.is_pressed());
+    }
+
+    #[test]
+    fn buffered_press_released_during_recovery_fires_once() {
+        let mut buffered = None;
+        let mut input = comp::Input::default();
+        set_attack(&mut buffered, &mut input, true, true);
+        set_attack(&mut buffered, &mut input, false, true);
+        assert!(matches!(buffered, Some(BufferedInput::Released(_))));
+        assert!(input.is_pressed());
+
+        release_buffered_input(&mut buffered, &mut input, true, BUFFER_DURATION);
+        assert!(matches!(buffered, Some(BufferedInput::Released(_))));
+        assert!(input.is_pressed());
+
+        release_buffered_input(&mut buffered, &mut input, false, BUFFER_DURATION);
+        assert!(matches!(buffered, Some(BufferedInput::Firing)));
+        assert!(input.is_pressed());
+
+        // Released again the frame after
+        release_buffered_input(&mut buffered, &mut input, false, BUFFER_DURATION);
+        assert!(buffered.is_none());
+        assert!(!input.is_pressed());
+    }
+
+    #[test]
+    fn buffered_press_expires() {
+        let mut buffered = None;
+        let mut input = comp::Input::default();
+        set_attack(&mut buffered, &mut input, true, true);
+        set_attack(&mut buffered, &mut input, false, true);
+        // Pretend the recovery outlasted the buffer
+        buffered = match buffered {
+            Some(BufferedInput::Released(pressed)) => Some(BufferedInput::Released(
+                pressed - BUFFER_DURATION - Duration::from_millis(1),
+            )),
+            other => other,
+        };
+
+        release_buffered_input(&mut buffered, &mut input, true, BUFFER_DURATION);
+        assert!(buffered.is_none());
+        assert!(!input.is_pressed());
+    }
+
+    #[test]
+    fn press_outside_recovery_not_buffered() {
+        let mut buffered = None;
+        let mut input = comp::Input::default();
+        set_attack(&mut buffered, &mut input, true, false);
+        assert!(buffered.is_none());
+        assert!(input.is_pressed());
+
+        set_attack(&mut buffered, &mut input, false, false);
+        assert!(buffered.is_none());
+        assert!(!input.is_pressed());
+
+        // A buffer of zero turns buffering off
+        assert!(!buffer_input(&mut buffered, true, true, 0));
+        assert!(buffered.is_none());
+    }
 }
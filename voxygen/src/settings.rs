@@ -1,14 +1,27 @@
 use crate::{
-    hud::{BarNumbers, CrosshairType, Intro, PressBehavior, ShortcutNumbers, XpBar},
+    hud::{
+        BarNumbers, ClockFormat, CoordinateDisplayMode, CrosshairType, Intro, NameplateScaleMode,
+        PressBehavior, ShortcutNumbers, XpBar,
+    },
     i18n,
     render::RenderMode,
+    tts::TtsChannel,
     ui::ScaleMode,
-    window::{FullScreenSettings, GameInput, KeyMouse},
+    window::{FullScreenSettings, GameInput, InputDevice, KeyMouse, MouseSteering},
+};
+use common::{
+    comp::item::{Quality, ToolCategory},
+    outcome::OutcomeCategory,
 };
 use directories_next::UserDirs;
 use hashbrown::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
 use tracing::warn;
 use winit::event::{MouseButton, VirtualKeyCode};
 
@@ -24,8 +37,9 @@ impl From<ControlSettings> for ControlSettingsSerde {
         let mut user_bindings: HashMap<GameInput, KeyMouse> = HashMap::new();
         // Do a delta between default() ControlSettings and the argument, and let
         // keybindings be only the custom keybindings chosen by the user.
+        let defaults = ControlSettings::default();
         for (k, v) in control_settings.keybindings {
-            if ControlSettings::default_binding(k) != v {
+            if defaults.get_binding(k) != Some(v) {
                 // Keybinding chosen by the user
                 user_bindings.insert(k, v);
             }
@@ -115,6 +129,62 @@ impl ControlSettings {
         false
     }
 
+    /// Write all current keybindings to `path` in a format that can be shared
+    /// and applied again with `import_bindings`.
+    pub fn export_bindings(&self, path: &Path) -> io::Result<()> {
+        // Inputs are stored by name so that files containing inputs which get
+        // removed later on can still be imported
+        let bindings = self
+            .keybindings
+            .iter()
+            .filter_map(|(game_input, key_mouse)| {
+                Some((ron::ser::to_string(game_input).ok()?, *key_mouse))
+            })
+            .collect::<BTreeMap<String, KeyMouse>>();
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let ron = ron::ser::to_string_pretty(&bindings, ron::ser::PrettyConfig::default())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        fs::write(path, ron.as_bytes())
+    }
+
+    /// Apply the keybindings stored in `path` on top of the current ones.
+    /// Unknown inputs are skipped with a warning. Returns the keys that are
+    /// left with conflicting bindings.
+    pub fn import_bindings(&mut self, path: &Path) -> io::Result<Vec<KeyMouse>> {
+        let file = fs::File::open(path)?;
+        let bindings: HashMap<String, KeyMouse> = ron::de::from_reader(file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        for (name, key_mouse) in bindings {
+            match ron::de::from_str::<GameInput>(&name) {
+                Ok(game_input) => self.modify_binding(game_input, key_mouse),
+                Err(_) => warn!(?name, "Skipping unknown input while importing keybindings"),
+            }
+        }
+
+        Ok(self
+            .inverse_keybindings
+            .keys()
+            .copied()
+            .filter(|key_mouse| self.has_conflicting_bindings(*key_mouse))
+            .collect())
+    }
+
+    pub fn get_bindings_path() -> PathBuf {
+        let mut path = voxygen_data_dir();
+        path.push("keybindings.ron");
+        path
+    }
+
+    pub fn get_bindings_backup_path() -> PathBuf {
+        let mut path = voxygen_data_dir();
+        path.push("keybindings.backup.ron");
+        path
+    }
+
     pub fn default_binding(game_input: GameInput) -> KeyMouse {
         // If a new GameInput is added, be sure to update GameInput::iterator() too!
         match game_input {
@@ -174,6 +244,43 @@ impl ControlSettings {
             GameInput::Select => KeyMouse::Key(VirtualKeyCode::Y),
             GameInput::AcceptGroupInvite => KeyMouse::Key(VirtualKeyCode::U),
             GameInput::DeclineGroupInvite => KeyMouse::Key(VirtualKeyCode::I),
+            GameInput::ToggleInputRecording => KeyMouse::Key(VirtualKeyCode::F9),
+            GameInput::ToggleInputPlayback => KeyMouse::Key(VirtualKeyCode::F10),
+            GameInput::CycleInteractable => KeyMouse::Key(VirtualKeyCode::R),
+            GameInput::BuildModifier => KeyMouse::Key(VirtualKeyCode::V),
+            GameInput::ToggleMouseSteering => KeyMouse::Key(VirtualKeyCode::F7),
+            GameInput::StowGlider => KeyMouse::Key(VirtualKeyCode::H),
+            GameInput::ToggleSessionStats => KeyMouse::Key(VirtualKeyCode::F8),
+            GameInput::CycleShadowMode => KeyMouse::Key(VirtualKeyCode::F5),
+            GameInput::CycleCoordinateDisplay => KeyMouse::Key(VirtualKeyCode::F12),
+            GameInput::RunBenchmark => KeyMouse::Key(VirtualKeyCode::Home),
+            GameInput::ToggleFirstPersonModel => KeyMouse::Key(VirtualKeyCode::Insert),
+            GameInput::ToggleViewDistance => KeyMouse::Key(VirtualKeyCode::End),
+            GameInput::SaveSettings => KeyMouse::Key(VirtualKeyCode::PageDown),
+            GameInput::ToggleInspect => KeyMouse::Key(VirtualKeyCode::Z),
+            GameInput::ToggleDoNotDisturb => KeyMouse::Key(VirtualKeyCode::X),
+            GameInput::ToggleTargetLock => KeyMouse::Key(VirtualKeyCode::Semicolon),
+            GameInput::SwapShoulder => KeyMouse::Key(VirtualKeyCode::Apostrophe),
+            GameInput::ToggleLineTool => KeyMouse::Key(VirtualKeyCode::Comma),
+            GameInput::QuickConsume => KeyMouse::Key(VirtualKeyCode::Grave),
+            GameInput::CycleQuickConsumable => KeyMouse::Key(VirtualKeyCode::Backslash),
+            GameInput::ToggleBuildGrid => KeyMouse::Key(VirtualKeyCode::LBracket),
+            GameInput::ToggleSct => KeyMouse::Key(VirtualKeyCode::RBracket),
+            GameInput::DropAllOfType => KeyMouse::Key(VirtualKeyCode::Delete),
+            GameInput::InspectBlock => KeyMouse::Key(VirtualKeyCode::Minus),
+            // Only suggested, it isn't bound until the player picks a key
+            GameInput::PanicDisconnect => KeyMouse::Key(VirtualKeyCode::Pause),
+            GameInput::ToggleOwnLanternLight => KeyMouse::Key(VirtualKeyCode::Equals),
+            GameInput::RepeatBuild => KeyMouse::Key(VirtualKeyCode::PageUp),
+            GameInput::ToggleScoutOverlay => KeyMouse::Key(VirtualKeyCode::Numpad0),
+            GameInput::SaveCameraPath => KeyMouse::Key(VirtualKeyCode::Numpad1),
+            GameInput::ToggleCombatCamera => KeyMouse::Key(VirtualKeyCode::Numpad2),
+            GameInput::DumpSettings => KeyMouse::Key(VirtualKeyCode::Numpad3),
+            GameInput::ToggleGroupCoordinates => KeyMouse::Key(VirtualKeyCode::Numpad4),
+            GameInput::ToggleWalk => KeyMouse::Key(VirtualKeyCode::Numpad5),
+            GameInput::FreeflyBoost => KeyMouse::Key(VirtualKeyCode::RShift),
+            GameInput::FreeflyPrecision => KeyMouse::Key(VirtualKeyCode::RControl),
+            GameInput::TargetNearest => KeyMouse::Key(VirtualKeyCode::Numpad6),
         }
     }
 }
@@ -244,6 +351,42 @@ impl Default for ControlSettings {
             GameInput::Select,
             GameInput::AcceptGroupInvite,
             GameInput::DeclineGroupInvite,
+            GameInput::ToggleInputRecording,
+            GameInput::ToggleInputPlayback,
+            GameInput::CycleInteractable,
+            GameInput::BuildModifier,
+            GameInput::ToggleMouseSteering,
+            GameInput::StowGlider,
+            GameInput::ToggleSessionStats,
+            GameInput::CycleShadowMode,
+            GameInput::CycleCoordinateDisplay,
+            GameInput::RunBenchmark,
+            GameInput::ToggleFirstPersonModel,
+            GameInput::ToggleViewDistance,
+            GameInput::SaveSettings,
+            GameInput::ToggleInspect,
+            GameInput::ToggleDoNotDisturb,
+            GameInput::ToggleTargetLock,
+            GameInput::SwapShoulder,
+            GameInput::ToggleLineTool,
+            GameInput::QuickConsume,
+            GameInput::CycleQuickConsumable,
+            GameInput::ToggleBuildGrid,
+            GameInput::ToggleSct,
+            GameInput::DropAllOfType,
+            GameInput::InspectBlock,
+            // GameInput::PanicDisconnect is left unbound, so it can't be pressed by accident
+            GameInput::ToggleOwnLanternLight,
+            GameInput::RepeatBuild,
+            GameInput::ToggleScoutOverlay,
+            GameInput::SaveCameraPath,
+            GameInput::ToggleCombatCamera,
+            GameInput::DumpSettings,
+            GameInput::ToggleGroupCoordinates,
+            GameInput::ToggleWalk,
+            GameInput::FreeflyBoost,
+            GameInput::FreeflyPrecision,
+            GameInput::TargetNearest,
         ];
         for game_input in game_inputs {
             new_settings.insert_binding(game_input, ControlSettings::default_binding(game_input));
@@ -265,8 +408,13 @@ pub struct GamepadSettings {
     pub pan_invert_y: bool,
     pub axis_deadzones: HashMap<crate::controller::Axis, f32>,
     pub button_deadzones: HashMap<crate::controller::AnalogButton, f32>,
+    /// Stick movement weaker than this is ignored, so stick drift that gets
+    /// past the deadzones doesn't make the character creep along.
+    pub min_move_magnitude: f32,
     pub mouse_emulation_sensitivity: u32,
     pub inverted_axes: Vec<crate::controller::Axis>,
+    /// Pairs of buttons pressed together that trigger another game input
+    pub chords: Vec<crate::controller::Chord>,
 }
 
 impl Default for GamepadSettings {
@@ -282,8 +430,10 @@ impl Default for GamepadSettings {
             pan_invert_y: false,
             axis_deadzones: HashMap::new(),
             button_deadzones: HashMap::new(),
+            min_move_magnitude: 0.05,
             mouse_emulation_sensitivity: 12,
             inverted_axes: Vec::new(),
+            chords: Vec::new(),
         }
     }
 }
@@ -493,6 +643,12 @@ pub struct GameplaySettings {
     pub zoom_sensitivity: u32,
     pub zoom_inversion: bool,
     pub toggle_debug: bool,
+    /// Draw the borders of the chunks around the player while the debug
+    /// overlay is shown
+    pub show_chunk_borders: bool,
+    /// Draw the ray used to pick what is under the crosshair while the debug
+    /// overlay is shown
+    pub show_targeting_ray: bool,
     pub sct: bool,
     pub sct_player_batch: bool,
     pub sct_damage_batch: bool,
@@ -514,6 +670,245 @@ pub struct GameplaySettings {
     pub stop_auto_walk_on_input: bool,
     pub map_zoom: f64,
     pub loading_tips: bool,
+    /// Whether aiming with a weapon of the given category shifts the camera
+    /// over the shoulder. Categories not in the map shift the camera.
+    pub aim_camera_shift: HashMap<ToolCategory, bool>,
+    /// Compensate held inputs for half of the ping, capped at
+    /// `max_prediction_compensation_ms`.
+    pub prediction_compensation: bool,
+    pub max_prediction_compensation_ms: u32,
+    /// Require the build modifier to be held to break blocks, and to place
+    /// them too if `protected_build_placing` is set.
+    pub protected_build: bool,
+    pub protected_build_placing: bool,
+    /// When the target can be both mounted and picked up, Interact mounts it
+    /// instead of picking it up.
+    pub interact_prefers_mount: bool,
+    /// Whether the camera follows mouse movement or the cursor position on
+    /// screen while the cursor is grabbed.
+    pub mouse_steering: MouseSteering,
+    /// Add the session stats of each session to per-character lifetime totals
+    /// saved in the profile.
+    pub save_lifetime_stats: bool,
+    /// Retry a block placement once when the server rejects it because the
+    /// block was being changed at the same time.
+    pub retry_block_placement: bool,
+    /// Log out after this many minutes without any input, 0 disables it.
+    pub afk_auto_logout_minutes: u32,
+    pub show_compass: bool,
+    pub coordinate_display_mode: CoordinateDisplayMode,
+    pub clock_format: ClockFormat,
+    /// Player aliases of friends.
+    pub friends: Vec<String>,
+    pub auto_accept_group_invites_from_friends: bool,
+    /// Seconds for the freefly camera rotation to catch up with input, 0
+    /// disables the smoothing.
+    pub freefly_rotation_smoothing: f32,
+    pub freefly: FreeflySettings,
+    /// Hide the HUD for the frame captured by the screenshot key.
+    pub hide_hud_in_screenshots: bool,
+    /// Most particles a single outcome, such as an explosion, may spawn.
+    /// `None` leaves it unlimited.
+    pub max_particles_per_outcome: Option<u32>,
+    pub nameplate_scale_mode: NameplateScaleMode,
+    /// Ask to log out safely when the window is closed, instead of quitting
+    /// straight away
+    pub confirm_on_close: bool,
+    /// Draw the crosshair where the aim lands while aiming, instead of at the
+    /// center of the screen
+    pub crosshair_follows_aim: bool,
+    /// How long a quick press of an attack button while recovering from an
+    /// attack is kept for the next one, in milliseconds. 0 disables it.
+    pub attack_input_buffer_ms: u32,
+    /// Which effects of each kind of outcome are played. Kinds not in the map
+    /// play all of them.
+    pub outcome_effects: HashMap<OutcomeCategory, OutcomeEffects>,
+    pub camera_shoulder: ShoulderSide,
+    /// Face the direction of movement rather than the camera, unless aiming.
+    pub face_movement_direction: bool,
+    /// Items of lower quality are picked up without a chat message. Rare items
+    /// are always announced.
+    pub loot_msg_min_quality: Quality,
+    /// Merge the chat messages of the same item picked up in quick succession.
+    pub batch_loot_msgs: bool,
+    /// Say how many of an item were picked up at once in loot messages.
+    pub loot_msg_show_count: bool,
+    /// Milliseconds to hold back the message of a failed pickup, which is left
+    /// out if a pickup succeeds meanwhile. Lag makes quick gathering fail
+    /// spuriously. Failures with a full inventory always show right away.
+    pub collect_fail_grace_ms: u32,
+    /// Degrees the camera kicks up when attacking. 0 disables it.
+    pub camera_recoil: f32,
+    /// Show a ring on the ground around the player at the interact range when
+    /// something to interact with is near.
+    pub show_interact_range: bool,
+    /// Hold back ambient particles while the inventory or another
+    /// large panel is open. Combat effects still play.
+    pub dim_world_when_inventory_open: bool,
+    /// Run at the selected creature while moving forward, until in melee range.
+    pub auto_approach_target: bool,
+    /// Walk to the ground clicked with the primary input while the weapon is
+    /// away, rather than drawing it.
+    pub click_to_move: bool,
+    /// Keep auto-walking while the game window is in the background, every
+    /// other input is still released
+    pub auto_walk_while_unfocused: bool,
+    /// Draw light beams over dropped items of at least this quality, `None`
+    /// disables them.
+    pub loot_beam_min_quality: Option<Quality>,
+    /// Color of the loot beams of each quality, white for those left out.
+    pub loot_beam_colors: BTreeMap<Quality, [u8; 3]>,
+    /// Ask before leaving the group from the group window.
+    pub confirm_leave_group: bool,
+    /// Write every action sent to the server to a log file, to debug desyncs.
+    pub log_client_actions: bool,
+    /// With free look set to hold, a double tap of the key keeps it on until
+    /// the key is tapped again.
+    pub sticky_free_look: bool,
+    /// Longest time between the taps of a double tap, in milliseconds.
+    pub free_look_double_tap_ms: u32,
+    /// Let go of latched free look when starting an attack, which needs the
+    /// character to face the camera direction.
+    pub release_free_look_on_attack: bool,
+    /// Turn the camera towards an entity once when selecting it, unless free
+    /// look is on.
+    pub select_turns_camera: bool,
+    /// Roll in the direction of a movement key when it is tapped twice.
+    pub double_tap_dodge: bool,
+    /// Longest time between the taps of a movement key to roll, in
+    /// milliseconds. Kept short so quick changes of direction don't roll.
+    pub double_tap_dodge_ms: u32,
+    /// Hotbar slot, from 1 to 10, of a consumable used on its own when health
+    /// drops below `auto_consume_health_threshold`.
+    pub auto_consume_health_slot: Option<usize>,
+    /// Fraction of maximum health.
+    pub auto_consume_health_threshold: f32,
+    /// Hotbar slot, from 1 to 10, of a consumable used on its own when energy
+    /// drops below `auto_consume_energy_threshold`.
+    pub auto_consume_energy_slot: Option<usize>,
+    /// Fraction of maximum energy.
+    pub auto_consume_energy_threshold: f32,
+    /// Forget the chat history when disconnecting with the panic key, so it
+    /// isn't shown again after reconnecting.
+    pub panic_disconnect_clears_chat: bool,
+    /// Sheathe the weapon while swimming and wield it again on leaving the
+    /// water, if it was wielded before.
+    pub sheathe_while_swimming: bool,
+    /// Seconds of the camera path kept to be saved for re-rendering, 0 keeps
+    /// none. Applies from the next session.
+    pub camera_path_seconds: u32,
+    /// Third person camera distance for exploring, toggled with the combat
+    /// camera key.
+    pub peaceful_camera_distance: f32,
+    /// Third person camera distance for fighting.
+    pub combat_camera_distance: f32,
+    /// Switch to the combat camera distance when wielding a weapon and back
+    /// when putting it away.
+    pub auto_combat_camera: bool,
+    /// Keep the camera level with the horizon, easing out any roll.
+    pub horizon_lock: bool,
+    /// Seconds after dying to respawn on its own, leaving time to read what
+    /// happened. `None` waits for the respawn key.
+    pub auto_respawn_delay_seconds: Option<u32>,
+    /// Fraction of the full movement speed used while walking is toggled on.
+    pub walk_speed: f32,
+    /// Scale of the reach for selecting blocks to build on or collect and
+    /// for targeting creatures. Only admins can reach further than twice
+    /// the normal reach.
+    pub interaction_range: f32,
+}
+
+/// Which side of the character the third person camera sits on.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ShoulderSide {
+    Centered,
+    Right,
+    Left,
+}
+
+impl ShoulderSide {
+    /// The other shoulder. A centered camera moves to the right one.
+    pub fn swapped(self) -> Self {
+        match self {
+            ShoulderSide::Centered | ShoulderSide::Left => ShoulderSide::Right,
+            ShoulderSide::Right => ShoulderSide::Left,
+        }
+    }
+
+    /// Sign of the sideways camera offset, positive to the right.
+    pub fn sign(self) -> f32 {
+        match self {
+            ShoulderSide::Centered => 0.0,
+            ShoulderSide::Right => 1.0,
+            ShoulderSide::Left => -1.0,
+        }
+    }
+}
+
+/// How the freefly camera moves, in blocks and seconds.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FreeflySettings {
+    /// Speed gained per second while moving
+    pub accel: f32,
+    /// Speed lost per second
+    pub damping: f32,
+    pub max_speed: f32,
+}
+
+impl Default for FreeflySettings {
+    fn default() -> Self {
+        Self {
+            accel: 120.0,
+            damping: 80.0,
+            max_speed: 50.0,
+        }
+    }
+}
+
+impl FreeflySettings {
+    /// These settings with the values the camera can't move with, like a max
+    /// speed of 0 or less, replaced by the defaults.
+    pub fn validated(self) -> Self {
+        let default = Self::default();
+        let positive = |value: f32| value.is_finite() && value > 0.0;
+        Self {
+            accel: if positive(self.accel) {
+                self.accel
+            } else {
+                default.accel
+            },
+            // Without damping the camera keeps gliding, which is fine for slow pans
+            damping: if positive(self.damping) || self.damping == 0.0 {
+                self.damping
+            } else {
+                default.damping
+            },
+            max_speed: if positive(self.max_speed) {
+                self.max_speed
+            } else {
+                default.max_speed
+            },
+        }
+    }
+}
+
+/// The effects played for an outcome, such as an explosion.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutcomeEffects {
+    pub sound: bool,
+    /// Particles and lights
+    pub visual: bool,
+}
+
+impl Default for OutcomeEffects {
+    fn default() -> Self {
+        Self {
+            sound: true,
+            visual: true,
+        }
+    }
 }
 
 impl Default for GameplaySettings {
@@ -525,6 +920,8 @@ impl Default for GameplaySettings {
             mouse_y_inversion: false,
             smooth_pan_enable: true,
             toggle_debug: false,
+            show_chunk_borders: false,
+            show_targeting_ray: false,
             sct: true,
             sct_player_batch: true,
             sct_damage_batch: false,
@@ -544,6 +941,77 @@ impl Default for GameplaySettings {
             stop_auto_walk_on_input: true,
             map_zoom: 4.0,
             loading_tips: true,
+            aim_camera_shift: [(ToolCategory::Staff, false)].iter().copied().collect(),
+            prediction_compensation: false,
+            max_prediction_compensation_ms: 100,
+            protected_build: false,
+            protected_build_placing: false,
+            interact_prefers_mount: false,
+            mouse_steering: MouseSteering::Relative,
+            save_lifetime_stats: false,
+            retry_block_placement: true,
+            afk_auto_logout_minutes: 0,
+            show_compass: true,
+            coordinate_display_mode: CoordinateDisplayMode::Absolute,
+            clock_format: ClockFormat::TwentyFourHour,
+            friends: Vec::new(),
+            auto_accept_group_invites_from_friends: false,
+            freefly_rotation_smoothing: 0.0,
+            freefly: FreeflySettings::default(),
+            hide_hud_in_screenshots: false,
+            max_particles_per_outcome: None,
+            nameplate_scale_mode: NameplateScaleMode::Fixed,
+            confirm_on_close: false,
+            crosshair_follows_aim: false,
+            attack_input_buffer_ms: 0,
+            outcome_effects: HashMap::new(),
+            camera_shoulder: ShoulderSide::Centered,
+            face_movement_direction: false,
+            loot_msg_min_quality: Quality::Low,
+            batch_loot_msgs: false,
+            loot_msg_show_count: true,
+            collect_fail_grace_ms: 300,
+            camera_recoil: 0.0,
+            show_interact_range: false,
+            dim_world_when_inventory_open: false,
+            auto_approach_target: false,
+            click_to_move: false,
+            auto_walk_while_unfocused: false,
+            loot_beam_min_quality: None,
+            loot_beam_colors: vec![
+                (Quality::Low, [105, 105, 105]),
+                (Quality::Common, [201, 255, 255]),
+                (Quality::Moderate, [15, 176, 31]),
+                (Quality::High, [46, 82, 230]),
+                (Quality::Epic, [148, 74, 237]),
+                (Quality::Legendary, [235, 194, 0]),
+                (Quality::Artifact, [189, 61, 28]),
+                (Quality::Debug, [201, 48, 43]),
+            ]
+            .into_iter()
+            .collect(),
+            confirm_leave_group: false,
+            log_client_actions: false,
+            sticky_free_look: false,
+            free_look_double_tap_ms: 300,
+            release_free_look_on_attack: true,
+            select_turns_camera: false,
+            double_tap_dodge: false,
+            double_tap_dodge_ms: 200,
+            auto_consume_health_slot: None,
+            auto_consume_health_threshold: 0.3,
+            auto_consume_energy_slot: None,
+            auto_consume_energy_threshold: 0.2,
+            panic_disconnect_clears_chat: true,
+            sheathe_while_swimming: false,
+            camera_path_seconds: 30,
+            peaceful_camera_distance: 10.0,
+            combat_camera_distance: 16.0,
+            auto_combat_camera: false,
+            horizon_lock: false,
+            auto_respawn_delay_seconds: None,
+            walk_speed: 0.4,
+            interaction_range: 1.0,
         }
     }
 }
@@ -610,34 +1078,63 @@ impl Default for Log {
 #[serde(default)]
 pub struct GraphicsSettings {
     pub view_distance: u32,
+    /// View distance switched to by the view distance toggle key.
+    pub low_view_distance: u32,
+    /// View distance switched back to from the low one.
+    pub high_view_distance: u32,
     pub sprite_render_distance: u32,
     pub particles_enabled: bool,
     pub figure_lod_render_distance: u32,
+    /// Fractions of the figure LoD render distance beyond which figures use
+    /// their medium and low detail models.
+    pub figure_detail_fractions: [f32; 2],
+    /// Distance up to which figures are animated every frame. Further away
+    /// they are updated less often. The player and their group always are.
+    pub figure_full_rate_distance: u32,
+    /// Whether the player's own lantern lights up their view. Others see it
+    /// either way.
+    pub own_lantern_light: bool,
+    /// Whether chunks loaded after the world has streamed in fade in rather
+    /// than popping in, e.g. when raising the view distance.
+    pub chunk_fade_in: bool,
     pub max_fps: u32,
     pub fov: u16,
     pub gamma: f32,
     pub ambiance: f32,
     pub render_mode: RenderMode,
     pub window_size: [u16; 2],
+    /// Where the top left corner of the window was last placed while
+    /// windowed, in physical pixels.
+    pub window_position: Option<[i32; 2]>,
     pub fullscreen: FullScreenSettings,
     pub lod_detail: u32,
+    /// Save FOV and gamma changes for the current character only.
+    pub per_character_fov_gamma: bool,
 }
 
 impl Default for GraphicsSettings {
     fn default() -> Self {
         Self {
             view_distance: 10,
+            low_view_distance: 5,
+            high_view_distance: 10,
             sprite_render_distance: 150,
             particles_enabled: true,
             figure_lod_render_distance: 250,
+            figure_detail_fractions: [0.5, 0.75],
+            figure_full_rate_distance: 50,
+            own_lantern_light: true,
+            chunk_fade_in: false,
             max_fps: 60,
             fov: 50,
             gamma: 1.0,
             ambiance: 20.0,
             render_mode: RenderMode::default(),
             window_size: [1920, 1080],
+            window_position: None,
             fullscreen: FullScreenSettings::default(),
             lod_detail: 300,
+            per_character_fov_gamma: false,
         }
     }
 }
@@ -665,7 +1162,18 @@ pub struct AudioSettings {
     pub master_volume: f32,
     pub music_volume: f32,
     pub sfx_volume: f32,
+    /// Most sound effects played at once, up to 64. Past that the least
+    /// important ones are cut off, keeping the player's and their group's.
     pub max_sfx_channels: usize,
+    /// How much sounds are muffled when terrain blocks the way to them, from 0
+    /// (not at all) to 1 (silenced).
+    pub sfx_occlusion: f32,
+    /// Read incoming chat aloud with the speech synthesizer of the platform.
+    pub chat_tts: bool,
+    /// Chat channels that are read aloud.
+    pub chat_tts_channels: BTreeSet<TtsChannel>,
+    /// Players whose messages are never read aloud.
+    pub chat_tts_muted: Vec<String>,
 
     /// Audio Device that Voxygen will use to play audio.
     pub output: AudioOutput,
@@ -678,6 +1186,13 @@ impl Default for AudioSettings {
             music_volume: 0.4,
             sfx_volume: 0.6,
             max_sfx_channels: 10,
+            sfx_occlusion: 0.0,
+            chat_tts: false,
+            chat_tts_channels: [TtsChannel::Say, TtsChannel::Tell, TtsChannel::Group]
+                .iter()
+                .copied()
+                .collect(),
+            chat_tts_muted: Vec::new(),
             output: AudioOutput::Automatic,
         }
     }
@@ -697,12 +1212,36 @@ impl Default for LanguageSettings {
     }
 }
 
+/// The bindings kept for an input device while another one is in use.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputDeviceControls {
+    pub controls: ControlSettings,
+    pub controller: GamepadSettings,
+}
+
+impl Default for InputDeviceControls {
+    fn default() -> Self {
+        Self {
+            controls: ControlSettings::default(),
+            controller: GamepadSettings::default(),
+        }
+    }
+}
+
 /// `Settings` contains everything that can be configured in the settings.ron
 /// file.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Settings {
     pub controls: ControlSettings,
+    /// Keep separate bindings for each input device, swapping them when the
+    /// player switches devices
+    pub controls_per_input_device: bool,
+    /// Device the bindings in `controls` and `controller` belong to
+    pub input_device: InputDevice,
+    /// Bindings of the input devices not in use
+    pub input_device_controls: HashMap<InputDevice, InputDeviceControls>,
     pub gameplay: GameplaySettings,
     pub networking: NetworkingSettings,
     pub log: Log,
@@ -715,6 +1254,9 @@ pub struct Settings {
     pub language: LanguageSettings,
     pub screenshots_path: PathBuf,
     pub controller: GamepadSettings,
+    /// Don't write changes made in game to the settings file. They still apply
+    /// until the game is closed, and can be saved with the save settings key.
+    pub disable_settings_autosave: bool,
 }
 
 impl Default for Settings {
@@ -738,6 +1280,9 @@ impl Default for Settings {
 
         Settings {
             controls: ControlSettings::default(),
+            controls_per_input_device: false,
+            input_device: InputDevice::KeyboardMouse,
+            input_device_controls: HashMap::new(),
             gameplay: GameplaySettings::default(),
             networking: NetworkingSettings::default(),
             log: Log::default(),
@@ -749,6 +1294,7 @@ impl Default for Settings {
             language: LanguageSettings::default(),
             screenshots_path,
             controller: GamepadSettings::default(),
+            disable_settings_autosave: false,
         }
     }
 }
@@ -780,7 +1326,42 @@ impl Settings {
         default_settings
     }
 
+    /// Put away the key and gamepad bindings of the device in use and load
+    /// the ones of `device`. A device without bindings of its own starts with
+    /// a copy of the current ones. The window has to be given the new gamepad
+    /// bindings afterwards.
+    pub fn switch_input_device(&mut self, device: InputDevice) {
+        if device == self.input_device {
+            return;
+        }
+        let InputDeviceControls {
+            controls,
+            controller,
+        } = self
+            .input_device_controls
+            .remove(&device)
+            .unwrap_or_else(|| InputDeviceControls {
+                controls: self.controls.clone(),
+                controller: self.controller.clone(),
+            });
+        let previous = InputDeviceControls {
+            controls: std::mem::replace(&mut self.controls, controls),
+            controller: std::mem::replace(&mut self.controller, controller),
+        };
+        self.input_device_controls
+            .insert(self.input_device, previous);
+        self.input_device = device;
+    }
+
+    /// Save the settings unless autosave is disabled, logging any failure.
     pub fn save_to_file_warn(&self) {
+        static WARNED_NOT_SAVED: AtomicBool = AtomicBool::new(false);
+        if self.disable_settings_autosave {
+            if !WARNED_NOT_SAVED.swap(true, Ordering::Relaxed) {
+                warn!("Settings autosave is disabled, changes won't be saved");
+            }
+            return;
+        }
         if let Err(e) = self.save_to_file() {
             warn!(?e, "Failed to save settings");
         }
@@ -796,6 +1377,27 @@ impl Settings {
         fs::write(path, ron.as_bytes())
     }
 
+    /// Write the graphics, gameplay and audio settings along with the game
+    /// version to a file to share when asking for help, returning its path.
+    pub fn dump(&self) -> std::io::Result<PathBuf> {
+        let mut path = voxygen_data_dir();
+        path.push("settings_dump.ron");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let dump = SettingsDump {
+            version: &common::util::DISPLAY_VERSION_LONG,
+            graphics: &self.graphics,
+            gameplay: &self.gameplay,
+            audio: &self.audio,
+        };
+        let ron = ron::ser::to_string_pretty(&dump, ron::ser::PrettyConfig::default())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        fs::write(&path, ron.as_bytes())?;
+        Ok(path)
+    }
+
     pub fn get_settings_path() -> PathBuf {
         if let Some(path) = std::env::var_os("VOXYGEN_CONFIG") {
             let settings = PathBuf::from(&path).join("settings.ron");
@@ -811,6 +1413,15 @@ impl Settings {
     }
 }
 
+/// The settings that matter when helping with a problem.
+#[derive(Serialize)]
+struct SettingsDump<'a> {
+    version: &'a str,
+    graphics: &'a GraphicsSettings,
+    gameplay: &'a GameplaySettings,
+    audio: &'a AudioSettings,
+}
+
 pub fn voxygen_data_dir() -> PathBuf {
     // Note: since voxygen is technically a lib we made need to lift this up to
     // run.rs
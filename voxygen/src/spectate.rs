@@ -0,0 +1,83 @@
+//! Watching another player with the camera locked onto them, so moderators
+//! can look into reports.
+//!
+//! The player is looked up by name. If they aren't loaded yet, for example
+//! because they are too far away, the lookup is retried every frame until
+//! they show up or a timeout passes.
+
+use crate::scene::camera::CameraMode;
+use client::Client;
+use common::{comp::Pos, sync::WorldSyncExt};
+use specs::{Entity as EcsEntity, WorldExt};
+use std::time::{Duration, Instant};
+use vek::*;
+
+/// How long to wait for the player to be loaded before giving up.
+const FIND_TIMEOUT: Duration = Duration::from_secs(15);
+/// Distance the camera keeps from the watched player.
+const DISTANCE: f32 = 8.0;
+/// Height above the watched player's feet that the camera looks at.
+const FOCUS_HEIGHT: f32 = 1.8;
+
+pub enum SpectateStatus {
+    /// The player is loaded, with the camera focus position for this frame.
+    Watching(Vec3<f32>),
+    /// The player isn't loaded yet.
+    Searching,
+    /// The player wasn't found in time, or isn't loaded anymore.
+    Lost,
+}
+
+pub struct Spectate {
+    name: String,
+    target: Option<EcsEntity>,
+    started: Instant,
+    prev_camera_mode: CameraMode,
+}
+
+impl Spectate {
+    /// Start looking for the player called `name`, remembering the camera
+    /// mode to restore once spectating ends.
+    pub fn new(name: String, prev_camera_mode: CameraMode) -> Self {
+        Self {
+            name,
+            target: None,
+            started: Instant::now(),
+            prev_camera_mode,
+        }
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+
+    pub fn prev_camera_mode(&self) -> CameraMode { self.prev_camera_mode }
+
+    /// Whether the player has been found.
+    pub fn is_watching(&self) -> bool { self.target.is_some() }
+
+    /// Look for the player if they haven't been found yet, and work out where
+    /// to put the camera, which is facing `cam_forward`, to keep them in view.
+    pub fn update(&mut self, client: &Client, cam_forward: Vec3<f32>) -> SpectateStatus {
+        let ecs = client.state().ecs();
+        if self.target.is_none() {
+            self.target = client
+                .player_list
+                .iter()
+                .find(|(_, info)| info.player_alias.eq_ignore_ascii_case(&self.name))
+                .and_then(|(uid, _)| ecs.entity_from_uid(uid.0))
+                .filter(|entity| *entity != client.entity());
+        }
+
+        let pos = self
+            .target
+            .and_then(|entity| ecs.read_storage::<Pos>().get(entity).map(|pos| pos.0));
+        match pos {
+            Some(pos) => SpectateStatus::Watching(
+                pos + Vec3::unit_z() * FOCUS_HEIGHT - cam_forward * DISTANCE,
+            ),
+            None if self.target.is_none() && self.started.elapsed() < FIND_TIMEOUT => {
+                SpectateStatus::Searching
+            },
+            None => SpectateStatus::Lost,
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Reads incoming chat aloud with the speech synthesizer of the platform, for
+//! players who have trouble reading the chat box.
+//!
+//! The synthesizer runs as a separate process, so speaking never holds up the
+//! tick. Only one message is spoken at a time and the rest wait in a short
+//! queue.
+
+use crate::settings::AudioSettings;
+use client::Client;
+use common::comp::{ChatMsg, ChatType};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    process::{Child, Command, Stdio},
+};
+use tracing::warn;
+
+/// Messages waiting to be spoken. When chat comes in faster than it can be
+/// read, the oldest ones are skipped so the readout doesn't fall behind.
+const MAX_QUEUED: usize = 3;
+/// Longer messages are cut off, so a single one can't go on for minutes
+const MAX_CHARS: usize = 200;
+
+/// Chat channels that can be read aloud.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TtsChannel {
+    Say,
+    Tell,
+    Group,
+    Faction,
+    Region,
+    World,
+    /// Output of chat commands
+    Command,
+    /// Server announcements and group or faction notices
+    System,
+}
+
+impl TtsChannel {
+    /// The channel of `chat_type`, if messages of that type can be read. The
+    /// ones the chat box formats itself, like kills, can't.
+    pub fn of(chat_type: &ChatType<String>) -> Option<Self> {
+        match chat_type {
+            ChatType::Say(_) => Some(Self::Say),
+            ChatType::Tell(..) => Some(Self::Tell),
+            ChatType::Group(..) => Some(Self::Group),
+            ChatType::Faction(..) => Some(Self::Faction),
+            ChatType::Region(_) => Some(Self::Region),
+            ChatType::World(_) => Some(Self::World),
+            ChatType::CommandInfo | ChatType::CommandError => Some(Self::Command),
+            ChatType::Meta | ChatType::GroupMeta(_) | ChatType::FactionMeta(_) => {
+                Some(Self::System)
+            },
+            ChatType::Online(_)
+            | ChatType::Offline(_)
+            | ChatType::Kill(..)
+            | ChatType::Npc(..)
+            | ChatType::Loot => None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ChatReader {
+    queue: VecDeque<String>,
+    speaking: Option<Child>,
+}
+
+impl ChatReader {
+    /// Queue `msg` to be read, if reading is enabled for its channel and the
+    /// sender isn't muted.
+    pub fn read(&mut self, client: &Client, settings: &AudioSettings, msg: &ChatMsg) {
+        let enabled = TtsChannel::of(&msg.chat_type).map_or(false, |channel| {
+            settings.chat_tts_channels.contains(&channel)
+        });
+        if !settings.chat_tts || !enabled {
+            return;
+        }
+        let sender = msg
+            .uid()
+            .and_then(|uid| client.player_list.get(&uid))
+            .map(|info| info.player_alias.as_str());
+        if sender.map_or(false, |sender| {
+            settings.chat_tts_muted.iter().any(|muted| muted == sender)
+        }) {
+            return;
+        }
+
+        let text: String = msg.message.chars().take(MAX_CHARS).collect();
+        self.queue.push_back(match sender {
+            Some(sender) => format!("{}: {}", sender, text),
+            None => text,
+        });
+        while self.queue.len() > MAX_QUEUED {
+            self.queue.pop_front();
+        }
+    }
+
+    /// Start reading the next message once the previous one is done.
+    pub fn maintain(&mut self) {
+        if let Some(child) = &mut self.speaking {
+            match child.try_wait() {
+                Ok(None) => return,
+                Ok(Some(_)) => self.speaking = None,
+                Err(e) => {
+                    warn!(?e, "Failed to check on the speech synthesizer");
+                    self.speaking = None;
+                },
+            }
+        }
+        if let Some(text) = self.queue.pop_front() {
+            match speak(&text) {
+                Ok(child) => self.speaking = Some(child),
+                Err(e) => {
+                    warn!(?e, "Failed to start the speech synthesizer");
+                    self.queue.clear();
+                },
+            }
+        }
+    }
+}
+
+impl Drop for ChatReader {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.speaking {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// Start the speech synthesizer of the platform on `text`. The text is passed
+/// through stdin so it is never interpreted as options.
+fn speak(text: &str) -> io::Result<Child> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("powershell");
+        command.args(&[
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; (New-Object \
+             System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+        ]);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("say");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut command = Command::new("espeak");
+        command.arg("--stdin");
+        command
+    };
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Dropping stdin closes it, which lets the synthesizer start
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    Ok(child)
+}
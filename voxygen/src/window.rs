@@ -1,7 +1,7 @@
 use crate::{
     controller::*,
     render::{Renderer, WinColorFmt, WinDepthFmt},
-    settings::{ControlSettings, Settings},
+    settings::{ControlSettings, GamepadSettings, Settings},
     ui, Error,
 };
 use common::span;
@@ -11,7 +11,7 @@ use hashbrown::HashMap;
 use itertools::Itertools;
 use old_school_gfx_glutin_ext::{ContextBuilderExt, WindowInitExt, WindowUpdateExt};
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{fmt, time::Instant};
 use tracing::{error, info, warn};
 use vek::*;
 use winit::monitor::VideoMode;
@@ -75,6 +75,42 @@ pub enum GameInput {
     Select,
     AcceptGroupInvite,
     DeclineGroupInvite,
+    ToggleInputRecording,
+    ToggleInputPlayback,
+    CycleInteractable,
+    BuildModifier,
+    ToggleMouseSteering,
+    StowGlider,
+    ToggleSessionStats,
+    CycleShadowMode,
+    CycleCoordinateDisplay,
+    RunBenchmark,
+    ToggleFirstPersonModel,
+    ToggleViewDistance,
+    SaveSettings,
+    ToggleInspect,
+    ToggleDoNotDisturb,
+    ToggleTargetLock,
+    SwapShoulder,
+    ToggleLineTool,
+    QuickConsume,
+    CycleQuickConsumable,
+    ToggleBuildGrid,
+    ToggleSct,
+    DropAllOfType,
+    InspectBlock,
+    PanicDisconnect,
+    ToggleOwnLanternLight,
+    RepeatBuild,
+    ToggleScoutOverlay,
+    SaveCameraPath,
+    ToggleCombatCamera,
+    DumpSettings,
+    ToggleGroupCoordinates,
+    ToggleWalk,
+    FreeflyBoost,
+    FreeflyPrecision,
+    TargetNearest,
 }
 
 impl GameInput {
@@ -136,6 +172,42 @@ impl GameInput {
             GameInput::Select => "gameinput.select",
             GameInput::AcceptGroupInvite => "gameinput.acceptgroupinvite",
             GameInput::DeclineGroupInvite => "gameinput.declinegroupinvite",
+            GameInput::ToggleInputRecording => "gameinput.toggleinputrecording",
+            GameInput::ToggleInputPlayback => "gameinput.toggleinputplayback",
+            GameInput::CycleInteractable => "gameinput.cycleinteractable",
+            GameInput::BuildModifier => "gameinput.buildmodifier",
+            GameInput::ToggleMouseSteering => "gameinput.togglemousesteering",
+            GameInput::StowGlider => "gameinput.stowglider",
+            GameInput::ToggleSessionStats => "gameinput.togglesessionstats",
+            GameInput::CycleShadowMode => "gameinput.cycleshadowmode",
+            GameInput::CycleCoordinateDisplay => "gameinput.cyclecoordinatedisplay",
+            GameInput::RunBenchmark => "gameinput.runbenchmark",
+            GameInput::ToggleFirstPersonModel => "gameinput.togglefirstpersonmodel",
+            GameInput::ToggleViewDistance => "gameinput.toggleviewdistance",
+            GameInput::SaveSettings => "gameinput.savesettings",
+            GameInput::ToggleInspect => "gameinput.toggleinspect",
+            GameInput::ToggleDoNotDisturb => "gameinput.toggledonotdisturb",
+            GameInput::ToggleTargetLock => "gameinput.toggletargetlock",
+            GameInput::SwapShoulder => "gameinput.swapshoulder",
+            GameInput::ToggleLineTool => "gameinput.togglelinetool",
+            GameInput::QuickConsume => "gameinput.quickconsume",
+            GameInput::CycleQuickConsumable => "gameinput.cyclequickconsumable",
+            GameInput::ToggleBuildGrid => "gameinput.togglebuildgrid",
+            GameInput::ToggleSct => "gameinput.togglesct",
+            GameInput::DropAllOfType => "gameinput.dropalloftype",
+            GameInput::InspectBlock => "gameinput.inspectblock",
+            GameInput::PanicDisconnect => "gameinput.panicdisconnect",
+            GameInput::ToggleOwnLanternLight => "gameinput.toggleownlanternlight",
+            GameInput::RepeatBuild => "gameinput.repeatbuild",
+            GameInput::ToggleScoutOverlay => "gameinput.togglescoutoverlay",
+            GameInput::SaveCameraPath => "gameinput.savecamerapath",
+            GameInput::ToggleCombatCamera => "gameinput.togglecombatcamera",
+            GameInput::DumpSettings => "gameinput.dumpsettings",
+            GameInput::ToggleGroupCoordinates => "gameinput.togglegroupcoordinates",
+            GameInput::ToggleWalk => "gameinput.togglewalk",
+            GameInput::FreeflyBoost => "gameinput.freeflyboost",
+            GameInput::FreeflyPrecision => "gameinput.freeflyprecision",
+            GameInput::TargetNearest => "gameinput.targetnearest",
         }
     }
 
@@ -191,6 +263,42 @@ impl GameInput {
             GameInput::Slot9,
             GameInput::Slot10,
             GameInput::SwapLoadout,
+            GameInput::ToggleInputRecording,
+            GameInput::ToggleInputPlayback,
+            GameInput::CycleInteractable,
+            GameInput::BuildModifier,
+            GameInput::ToggleMouseSteering,
+            GameInput::StowGlider,
+            GameInput::ToggleSessionStats,
+            GameInput::CycleShadowMode,
+            GameInput::CycleCoordinateDisplay,
+            GameInput::RunBenchmark,
+            GameInput::ToggleFirstPersonModel,
+            GameInput::ToggleViewDistance,
+            GameInput::SaveSettings,
+            GameInput::ToggleInspect,
+            GameInput::ToggleDoNotDisturb,
+            GameInput::ToggleTargetLock,
+            GameInput::SwapShoulder,
+            GameInput::ToggleLineTool,
+            GameInput::QuickConsume,
+            GameInput::CycleQuickConsumable,
+            GameInput::ToggleBuildGrid,
+            GameInput::ToggleSct,
+            GameInput::DropAllOfType,
+            GameInput::InspectBlock,
+            GameInput::PanicDisconnect,
+            GameInput::ToggleOwnLanternLight,
+            GameInput::RepeatBuild,
+            GameInput::ToggleScoutOverlay,
+            GameInput::SaveCameraPath,
+            GameInput::ToggleCombatCamera,
+            GameInput::DumpSettings,
+            GameInput::ToggleGroupCoordinates,
+            GameInput::ToggleWalk,
+            GameInput::FreeflyBoost,
+            GameInput::FreeflyPrecision,
+            GameInput::TargetNearest,
         ]
         .iter()
         .copied()
@@ -256,6 +364,22 @@ pub enum AnalogGameInput {
     CameraY(f32),
 }
 
+/// How mouse movement steers the camera while the cursor is grabbed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum MouseSteering {
+    /// Mouse movement rotates the camera
+    Relative,
+    /// The camera turns towards where the cursor is on the screen
+    Absolute,
+}
+
+/// Kind of device the player gives input with
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum InputDevice {
+    KeyboardMouse,
+    Gamepad,
+}
+
 /// Represents an incoming event from the window.
 #[derive(Clone, Debug)]
 pub enum Event {
@@ -271,6 +395,9 @@ pub enum Event {
     CursorPan(Vec2<f32>),
     /// The cursor has been moved across the screen while ungrabbed.
     CursorMove(Vec2<f32>),
+    /// The cursor position while grabbed with absolute mouse steering, from
+    /// -1 to 1 relative to the center of the window.
+    CursorSteer(Vec2<f32>),
     /// A mouse button has been pressed or released
     MouseButton(MouseButton, PressState),
     /// The camera has been requested to zoom.
@@ -486,6 +613,7 @@ pub struct Window {
     renderer: Renderer,
     window: glutin::ContextWrapper<glutin::PossiblyCurrent, winit::window::Window>,
     cursor_grabbed: bool,
+    mouse_steering: MouseSteering,
     pub pan_sensitivity: u32,
     pub zoom_sensitivity: u32,
     pub zoom_inversion: bool,
@@ -507,6 +635,11 @@ pub struct Window {
     // Used for screenshots & fullscreen toggle to deduplicate/postpone to after event handler
     take_screenshot: bool,
     toggle_fullscreen: bool,
+    // Whether the screenshot key is passed on to the play state instead
+    delegate_screenshots: bool,
+    // Device the last input came from, and since when it is the one in use
+    last_input_device: (InputDevice, Instant),
+    chord_resolver: ChordResolver,
 }
 
 impl Window {
@@ -590,6 +723,7 @@ impl Window {
             )?,
             window,
             cursor_grabbed: false,
+            mouse_steering: settings.gameplay.mouse_steering,
             pan_sensitivity: settings.gameplay.pan_sensitivity,
             zoom_sensitivity: settings.gameplay.zoom_sensitivity,
             zoom_inversion: settings.gameplay.zoom_inversion,
@@ -609,9 +743,15 @@ impl Window {
             message_sender,
             message_receiver,
             take_screenshot: false,
+            delegate_screenshots: false,
             toggle_fullscreen: false,
+            last_input_device: (settings.input_device, Instant::now()),
+            chord_resolver: ChordResolver::default(),
         };
 
+        if let Some(position) = settings.graphics.window_position {
+            this.restore_position(position);
+        }
         this.set_fullscreen_mode(settings.graphics.fullscreen);
 
         Ok((this, event_loop))
@@ -653,28 +793,67 @@ impl Window {
         }
 
         if let Some(gilrs) = &mut self.gilrs {
-            while let Some(event) = gilrs.next_event() {
-                fn handle_buttons(
-                    settings: &ControllerSettings,
-                    events: &mut Vec<Event>,
-                    button: &Button,
-                    is_pressed: bool,
-                ) {
-                    if let Some(evs) = settings.game_button_map.get(button) {
-                        for ev in evs {
-                            events.push(Event::InputUpdate(*ev, is_pressed));
-                        }
+            fn handle_buttons(
+                settings: &ControllerSettings,
+                events: &mut Vec<Event>,
+                button: &Button,
+                is_pressed: bool,
+            ) {
+                if let Some(evs) = settings.game_button_map.get(button) {
+                    for ev in evs {
+                        events.push(Event::InputUpdate(*ev, is_pressed));
                     }
-                    if let Some(evs) = settings.menu_button_map.get(button) {
-                        for ev in evs {
-                            events.push(Event::MenuInput(*ev, is_pressed));
-                        }
+                }
+                if let Some(evs) = settings.menu_button_map.get(button) {
+                    for ev in evs {
+                        events.push(Event::MenuInput(*ev, is_pressed));
+                    }
+                }
+            }
+            fn handle_resolved(
+                settings: &ControllerSettings,
+                events: &mut Vec<Event>,
+                resolved: impl IntoIterator<Item = ResolvedInput>,
+            ) {
+                for input in resolved {
+                    match input {
+                        ResolvedInput::Button(button, is_pressed) => {
+                            handle_buttons(settings, events, &button, is_pressed)
+                        },
+                        ResolvedInput::Chord(input, is_pressed) => {
+                            events.push(Event::InputUpdate(input, is_pressed))
+                        },
                     }
                 }
+            }
+
+            while let Some(event) = gilrs.next_event() {
+                match event.event {
+                    EventType::ButtonPressed(..) => {
+                        Self::note_input_device(&mut self.last_input_device, InputDevice::Gamepad)
+                    },
+                    // Stick drift shouldn't count as using the gamepad
+                    EventType::AxisChanged(_, value, _) if value.abs() > 0.5 => {
+                        Self::note_input_device(&mut self.last_input_device, InputDevice::Gamepad)
+                    },
+                    _ => {},
+                }
 
                 match event.event {
-                    EventType::ButtonPressed(button, code)
-                    | EventType::ButtonRepeated(button, code) => {
+                    EventType::ButtonPressed(button, code) => {
+                        let resolved = self.chord_resolver.press(
+                            &self.controller_settings.chords,
+                            Button::from((button, code)),
+                        );
+                        handle_resolved(&self.controller_settings, &mut self.events, resolved);
+                    },
+                    // Repeats of buttons that are part of a chord would bypass it
+                    EventType::ButtonRepeated(button, code)
+                        if !ChordResolver::is_chord_button(
+                            &self.controller_settings.chords,
+                            Button::from((button, code)),
+                        ) =>
+                    {
                         handle_buttons(
                             &self.controller_settings,
                             &mut self.events,
@@ -682,13 +861,10 @@ impl Window {
                             true,
                         );
                     },
+                    EventType::ButtonRepeated(..) => {},
                     EventType::ButtonReleased(button, code) => {
-                        handle_buttons(
-                            &self.controller_settings,
-                            &mut self.events,
-                            &Button::from((button, code)),
-                            false,
-                        );
+                        let resolved = self.chord_resolver.release(Button::from((button, code)));
+                        handle_resolved(&self.controller_settings, &mut self.events, resolved);
                     },
                     EventType::ButtonChanged(button, _value, code) => {
                         if let Some(actions) = self
@@ -804,6 +980,10 @@ impl Window {
                     EventType::Dropped => {},
                 }
             }
+
+            // A chord button held past the chord window acts on its own
+            let resolved = self.chord_resolver.maintain();
+            handle_resolved(&self.controller_settings, &mut self.events, resolved);
         }
 
         let mut events = std::mem::take(&mut self.events);
@@ -868,7 +1048,10 @@ impl Window {
                 );
 
                 if self.cursor_grabbed {
-                    self.events.push(Event::CursorPan(delta));
+                    // With absolute steering the cursor position is used instead
+                    if self.mouse_steering == MouseSteering::Relative {
+                        self.events.push(Event::CursorPan(delta));
+                    }
                 } else {
                     self.events.push(Event::CursorMove(delta));
                 }
@@ -924,8 +1107,19 @@ impl Window {
             WindowEvent::ScaleFactorChanged { .. } => {
                 // TODO: Handle properly!
             },
+            // Fullscreen windows sit at the origin of their monitor, which would lose the
+            // windowed position
+            WindowEvent::Moved(position) if !self.fullscreen.enabled => {
+                settings.graphics.window_position = Some([position.x, position.y]);
+            },
             WindowEvent::ReceivedCharacter(c) => self.events.push(Event::Char(c)),
             WindowEvent::MouseInput { button, state, .. } => {
+                if state == winit::event::ElementState::Pressed {
+                    Self::note_input_device(
+                        &mut self.last_input_device,
+                        InputDevice::KeyboardMouse,
+                    );
+                }
                 if let (true, Some(game_inputs)) =
                     // Mouse input not mapped to input if it is not grabbed
                     (
@@ -971,6 +1165,13 @@ impl Window {
                     return;
                 }
 
+                if input.state == winit::event::ElementState::Pressed {
+                    Self::note_input_device(
+                        &mut self.last_input_device,
+                        InputDevice::KeyboardMouse,
+                    );
+                }
+
                 if let Some(key) = input.virtual_keycode {
                     if let Some(game_inputs) = Window::map_input(
                         KeyMouse::Key(key),
@@ -995,12 +1196,22 @@ impl Window {
                                     );
                                 },
                                 GameInput::Screenshot => {
-                                    self.take_screenshot = input.state
+                                    let take_screenshot = input.state
                                         == winit::event::ElementState::Pressed
                                         && !Self::is_pressed(
                                             &mut self.keypress_map,
                                             GameInput::Screenshot,
                                         );
+                                    if self.delegate_screenshots {
+                                        if take_screenshot {
+                                            self.events.push(Event::InputUpdate(
+                                                GameInput::Screenshot,
+                                                true,
+                                            ));
+                                        }
+                                    } else {
+                                        self.take_screenshot = take_screenshot;
+                                    }
                                     Self::set_pressed(
                                         &mut self.keypress_map,
                                         GameInput::Screenshot,
@@ -1022,6 +1233,10 @@ impl Window {
             },
             WindowEvent::CursorMoved { position, .. } => {
                 self.cursor_position = position;
+                if self.cursor_grabbed && self.mouse_steering == MouseSteering::Absolute {
+                    self.events
+                        .push(Event::CursorSteer(self.cursor_steer_offset()));
+                }
             },
             _ => {},
         }
@@ -1052,10 +1267,44 @@ impl Window {
 
     pub fn is_cursor_grabbed(&self) -> bool { self.cursor_grabbed }
 
+    pub fn is_focused(&self) -> bool { self.focused }
+
     pub fn grab_cursor(&mut self, grab: bool) {
         self.cursor_grabbed = grab;
-        self.window.window().set_cursor_visible(!grab);
+        // The cursor is what steers the camera in absolute mode, so keep it visible
+        self.window
+            .window()
+            .set_cursor_visible(!grab || self.mouse_steering == MouseSteering::Absolute);
         let _ = self.window.window().set_cursor_grab(grab);
+        if !grab {
+            self.events.push(Event::CursorSteer(Vec2::zero()));
+        }
+    }
+
+    pub fn mouse_steering(&self) -> MouseSteering { self.mouse_steering }
+
+    pub fn set_mouse_steering(&mut self, mouse_steering: MouseSteering) {
+        self.mouse_steering = mouse_steering;
+        // Start from the center so the camera doesn't jump, and drop any steering
+        // left over from the previous mode
+        let size = self.window.window().inner_size();
+        let center =
+            winit::dpi::PhysicalPosition::new(size.width as f64 / 2.0, size.height as f64 / 2.0);
+        if let Err(err) = self.window.window().set_cursor_position(center) {
+            error!("Error setting cursor position: {:?}", err);
+        }
+        self.cursor_position = center;
+        self.events.push(Event::CursorSteer(Vec2::zero()));
+        self.grab_cursor(self.cursor_grabbed);
+    }
+
+    /// Offset of the cursor from the center of the window, from -1 to 1 on
+    /// each axis
+    fn cursor_steer_offset(&self) -> Vec2<f32> {
+        let size = self.window.window().inner_size();
+        let size = Vec2::new(size.width as f32, size.height as f32).map(|e| e.max(1.0));
+        let pos = Vec2::new(self.cursor_position.x as f32, self.cursor_position.y as f32);
+        ((pos / size) * 2.0 - 1.0).map(|e| e.max(-1.0).min(1.0))
     }
 
     pub fn toggle_fullscreen(&mut self, settings: &mut Settings) {
@@ -1271,6 +1520,27 @@ impl Window {
 
     pub fn needs_refresh_resize(&mut self) { self.needs_refresh_resize = true; }
 
+    /// Move the window to `position`, or onto the primary monitor if no
+    /// connected monitor shows it anymore, for example after one was unplugged.
+    fn restore_position(&self, position: [i32; 2]) {
+        // Maximized windows can stick out of the monitor a little, so a point just
+        // inside the corner is checked
+        const CORNER_MARGIN: i32 = 32;
+        let window = self.window.window();
+        let corner = Vec2::from(position) + CORNER_MARGIN;
+        let on_monitor = window.available_monitors().any(|monitor| {
+            let origin = monitor.position();
+            let size = monitor.size();
+            (origin.x..origin.x + size.width as i32).contains(&corner.x)
+                && (origin.y..origin.y + size.height as i32).contains(&corner.y)
+        });
+        if on_monitor {
+            window.set_outer_position(winit::dpi::PhysicalPosition::new(position[0], position[1]));
+        } else {
+            window.set_outer_position(window.primary_monitor().position());
+        }
+    }
+
     #[allow(clippy::or_fun_call)] // TODO: Pending review in #587
     pub fn logical_size(&self) -> Vec2<f64> {
         let (w, h) = self
@@ -1293,6 +1563,27 @@ impl Window {
 
     pub fn send_event(&mut self, event: Event) { self.events.push(event) }
 
+    /// When set, the screenshot key is passed on as a game input for the play
+    /// state to handle with `request_screenshot` instead of capturing the
+    /// current frame straight away. Reset whenever the play state changes.
+    pub fn set_delegate_screenshots(&mut self, delegate: bool) {
+        self.delegate_screenshots = delegate;
+    }
+
+    /// Capture the most recently rendered frame at the start of the next one.
+    pub fn request_screenshot(&mut self) { self.take_screenshot = true; }
+
+    /// The device the last input came from, and since when the player has
+    /// been using it.
+    pub fn last_input_device(&self) -> (InputDevice, Instant) { self.last_input_device }
+
+    /// Map gamepad input with `settings` from now on. Chords being held are
+    /// dropped, since their buttons may mean something else now.
+    pub fn set_controller_settings(&mut self, settings: &GamepadSettings) {
+        self.controller_settings = ControllerSettings::from(settings);
+        self.chord_resolver = ChordResolver::default();
+    }
+
     pub fn take_screenshot(&mut self, settings: &Settings) {
         match self.renderer.create_screenshot() {
             Ok(img) => {
@@ -1347,6 +1638,12 @@ impl Window {
         map.insert(input, state);
     }
 
+    fn note_input_device(last: &mut (InputDevice, Instant), device: InputDevice) {
+        if last.0 != device {
+            *last = (device, Instant::now());
+        }
+    }
+
     // Function used to handle Mouse and Key events. It first checks if we're in
     // remapping mode for a specific GameInput. If we are, we modify the binding
     // of that GameInput with the KeyMouse passed. Else, we return an iterator of